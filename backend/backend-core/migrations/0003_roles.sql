@@ -0,0 +1,13 @@
+-- Global role per user; users without a row are viewers
+CREATE TABLE IF NOT EXISTS user_roles (
+    user_id TEXT PRIMARY KEY,
+    role    TEXT NOT NULL CHECK (role IN ('viewer', 'planner', 'admin'))
+);
+
+-- Users allowed to edit assignments on a project
+CREATE TABLE IF NOT EXISTS project_managers (
+    project_id BLOB(16) NOT NULL,
+    user_id    TEXT NOT NULL,
+    PRIMARY KEY (project_id, user_id),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- What a resource does on a project, e.g. "Tech Lead", plus free-text notes
+ALTER TABLE assignments ADD COLUMN role TEXT;
+ALTER TABLE assignments ADD COLUMN note TEXT;
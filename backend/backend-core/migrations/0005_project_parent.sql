@@ -0,0 +1,4 @@
+-- Projects can be grouped under a parent programme. Deferred so that a batch
+-- may insert a child before its parent; orphans become top-level projects.
+ALTER TABLE projects ADD COLUMN parent_id BLOB(16)
+    REFERENCES projects(id) ON DELETE SET NULL DEFERRABLE INITIALLY DEFERRED;
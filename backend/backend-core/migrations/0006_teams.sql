@@ -0,0 +1,8 @@
+-- Teams group resources for line management; each resource is in at most one.
+CREATE TABLE IF NOT EXISTS teams (
+    id   BLOB(16) PRIMARY KEY,  -- UUID stored as 16-byte blob
+    name TEXT NOT NULL
+);
+
+ALTER TABLE resources ADD COLUMN team_id BLOB(16)
+    REFERENCES teams(id) ON DELETE SET NULL;
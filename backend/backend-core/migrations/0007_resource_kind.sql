@@ -0,0 +1,3 @@
+-- Placeholders stand in for roles that haven't been filled yet, e.g. "Backend Engineer #2 (TBH)".
+ALTER TABLE resources ADD COLUMN kind TEXT NOT NULL DEFAULT 'person'
+    CHECK (kind IN ('person', 'placeholder'));
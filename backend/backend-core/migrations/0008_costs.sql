@@ -0,0 +1,12 @@
+-- Amounts are whole units of the planning currency.
+ALTER TABLE projects ADD COLUMN budget INTEGER CHECK (budget >= 0);
+
+-- Monthly cost of a resource from (year, month) until the next rate takes over.
+CREATE TABLE IF NOT EXISTS cost_rates (
+    resource_id BLOB(16) NOT NULL,
+    year        INTEGER NOT NULL,
+    month       INTEGER NOT NULL CHECK (month BETWEEN 1 AND 12),
+    rate        INTEGER NOT NULL CHECK (rate >= 0),
+    PRIMARY KEY (resource_id, year, month),
+    FOREIGN KEY (resource_id) REFERENCES resources(id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+-- Effort actually spent, as a percentage of a full month (100 = the whole month).
+CREATE TABLE IF NOT EXISTS actuals (
+    resource_id BLOB(16) NOT NULL,
+    project_id  BLOB(16) NOT NULL,
+    year        INTEGER NOT NULL,
+    month       INTEGER NOT NULL CHECK (month BETWEEN 1 AND 12),
+    effort      INTEGER NOT NULL CHECK (effort >= 0),
+    PRIMARY KEY (resource_id, project_id, year, month),
+    FOREIGN KEY (resource_id) REFERENCES resources(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id)  REFERENCES projects(id)  ON DELETE CASCADE
+);
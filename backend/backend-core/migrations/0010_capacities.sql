@@ -0,0 +1,10 @@
+-- Per-month capacity overrides, as a percentage of a full month (100 = 1.0 FTE).
+-- Months without a row are full time.
+CREATE TABLE IF NOT EXISTS capacities (
+    resource_id BLOB(16) NOT NULL,
+    year        INTEGER NOT NULL,
+    month       INTEGER NOT NULL CHECK (month BETWEEN 1 AND 12),
+    percent     INTEGER NOT NULL CHECK (percent >= 0),
+    PRIMARY KEY (resource_id, year, month),
+    FOREIGN KEY (resource_id) REFERENCES resources(id) ON DELETE CASCADE
+);
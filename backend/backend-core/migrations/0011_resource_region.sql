@@ -0,0 +1,3 @@
+-- Working-day calendar region of each resource, e.g. 'UK' or 'DE'. NULL uses
+-- weekdays without public holidays.
+ALTER TABLE resources ADD COLUMN region TEXT;
@@ -0,0 +1,12 @@
+-- Assignments planned by ISO 8601 week rather than by month
+CREATE TABLE IF NOT EXISTS week_assignments (
+    resource_id BLOB(16) NOT NULL,
+    project_id  BLOB(16) NOT NULL,
+    year        INTEGER NOT NULL,  -- ISO week-numbering year
+    week        INTEGER NOT NULL CHECK (week BETWEEN 1 AND 53),
+    role        TEXT,
+    note        TEXT,
+    PRIMARY KEY (resource_id, project_id, year, week),
+    FOREIGN KEY (resource_id) REFERENCES resources(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id)  REFERENCES projects(id)  ON DELETE CASCADE
+);
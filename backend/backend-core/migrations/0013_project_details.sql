@@ -0,0 +1,5 @@
+-- Lifecycle status, display colour ('#rrggbb') and free-text description of each project
+ALTER TABLE projects ADD COLUMN status TEXT NOT NULL DEFAULT 'active'
+    CHECK (status IN ('proposed', 'active', 'on-hold', 'done'));
+ALTER TABLE projects ADD COLUMN colour TEXT;
+ALTER TABLE projects ADD COLUMN description TEXT;
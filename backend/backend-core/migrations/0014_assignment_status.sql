@@ -0,0 +1,5 @@
+-- Whether an assignment is pencilled in or confirmed; existing rows are confirmed
+ALTER TABLE assignments ADD COLUMN status TEXT NOT NULL DEFAULT 'confirmed'
+    CHECK (status IN ('tentative', 'confirmed'));
+ALTER TABLE week_assignments ADD COLUMN status TEXT NOT NULL DEFAULT 'confirmed'
+    CHECK (status IN ('tentative', 'confirmed'));
@@ -0,0 +1,12 @@
+//! Request-scoped identity shared between the HTTP layer and GraphQL resolvers.
+
+/// The authenticated caller of a GraphQL request.
+///
+/// The server inserts this into the request data after validating credentials;
+/// requests without one are anonymous and may only run queries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CurrentUser {
+    /// Stable user identifier (the token subject).
+    pub id: String,
+    pub name: Option<String>,
+}
@@ -0,0 +1,584 @@
+//! Versioned JSON backups of the whole planning database.
+//!
+//! Backups hold every team, project, resource, monthly and weekly assignment,
+//! actual, cost rate and capacity along with role grants and project managers.
+//! The audit log is not included. Restoring goes through the repository's
+//! upsert/assign semantics, so it is idempotent and merges into whatever the
+//! target database already holds.
+
+use crate::{
+    domain,
+    repo::{AssignmentFilter, Repository, WeekFilter},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt};
+use uuid::Uuid;
+
+/// Format version written by [`export`] and accepted by [`restore`].
+///
+/// Bump this when a change can't be read by older code; purely additive fields
+/// should instead default when missing.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    #[serde(default)]
+    pub teams: Vec<TeamRecord>,
+    #[serde(default)]
+    pub projects: Vec<ProjectRecord>,
+    #[serde(default)]
+    pub resources: Vec<ResourceRecord>,
+    #[serde(default)]
+    pub assignments: Vec<AssignmentRecord>,
+    #[serde(default)]
+    pub week_assignments: Vec<WeekAssignmentRecord>,
+    #[serde(default)]
+    pub actuals: Vec<ActualRecord>,
+    #[serde(default)]
+    pub cost_rates: Vec<CostRateRecord>,
+    #[serde(default)]
+    pub capacities: Vec<CapacityRecord>,
+    #[serde(default)]
+    pub user_roles: Vec<UserRoleRecord>,
+    #[serde(default)]
+    pub project_managers: Vec<ProjectManagerRecord>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamRecord {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectRecord {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    #[serde(default)]
+    pub budget: Option<i64>,
+    #[serde(default = "default_status")]
+    pub status: String,
+    #[serde(default)]
+    pub colour: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+fn default_status() -> String {
+    domain::ProjectStatus::default().to_string()
+}
+
+impl TryFrom<ProjectRecord> for domain::Project {
+    type Error = BackupError;
+
+    fn try_from(p: ProjectRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: p.id,
+            name: p.name,
+            parent_id: p.parent_id,
+            budget: p.budget,
+            status: p.status.parse().map_err(BackupError::Invalid)?,
+            colour: p.colour,
+            description: p.description,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceRecord {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub team_id: Option<Uuid>,
+    #[serde(default = "default_kind")]
+    pub kind: String,
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+fn default_kind() -> String {
+    domain::ResourceKind::Person.to_string()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssignmentRecord {
+    pub resource_id: Uuid,
+    pub project_id: Uuid,
+    pub year: i32,
+    pub month: i32,
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default = "default_assignment_status")]
+    pub status: String,
+}
+
+fn default_assignment_status() -> String {
+    domain::AssignmentStatus::default().to_string()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeekAssignmentRecord {
+    pub resource_id: Uuid,
+    pub project_id: Uuid,
+    /// ISO week-numbering year.
+    pub year: i32,
+    pub week: i32,
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default = "default_assignment_status")]
+    pub status: String,
+}
+
+impl From<domain::Assignment> for AssignmentRecord {
+    fn from(a: domain::Assignment) -> Self {
+        Self {
+            resource_id: a.resource_id,
+            project_id: a.project_id,
+            year: a.year,
+            month: a.month,
+            role: a.role,
+            note: a.note,
+            status: a.status.to_string(),
+        }
+    }
+}
+
+impl TryFrom<AssignmentRecord> for domain::Assignment {
+    type Error = BackupError;
+
+    fn try_from(a: AssignmentRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            resource_id: a.resource_id,
+            project_id: a.project_id,
+            year: a.year,
+            month: a.month,
+            role: a.role,
+            note: a.note,
+            status: a.status.parse().map_err(BackupError::Invalid)?,
+        })
+    }
+}
+
+impl From<domain::WeekAssignment> for WeekAssignmentRecord {
+    fn from(a: domain::WeekAssignment) -> Self {
+        Self {
+            resource_id: a.resource_id,
+            project_id: a.project_id,
+            year: a.year,
+            week: a.week,
+            role: a.role,
+            note: a.note,
+            status: a.status.to_string(),
+        }
+    }
+}
+
+impl TryFrom<WeekAssignmentRecord> for domain::WeekAssignment {
+    type Error = BackupError;
+
+    fn try_from(a: WeekAssignmentRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            resource_id: a.resource_id,
+            project_id: a.project_id,
+            year: a.year,
+            week: a.week,
+            role: a.role,
+            note: a.note,
+            status: a.status.parse().map_err(BackupError::Invalid)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActualRecord {
+    pub resource_id: Uuid,
+    pub project_id: Uuid,
+    pub year: i32,
+    pub month: i32,
+    pub effort: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostRateRecord {
+    pub resource_id: Uuid,
+    pub year: i32,
+    pub month: i32,
+    pub rate: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapacityRecord {
+    pub resource_id: Uuid,
+    pub year: i32,
+    pub month: i32,
+    pub percent: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserRoleRecord {
+    pub user_id: String,
+    pub role: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectManagerRecord {
+    pub project_id: Uuid,
+    pub user_id: String,
+}
+
+#[derive(Debug)]
+pub enum BackupError {
+    UnsupportedVersion(u32),
+    /// The document is well-formed JSON but describes impossible data.
+    Invalid(String),
+    Json(serde_json::Error),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion(v) => write!(
+                f,
+                "unsupported backup version {v} (expected {FORMAT_VERSION})"
+            ),
+            Self::Invalid(msg) => write!(f, "invalid backup: {msg}"),
+            Self::Json(e) => write!(f, "malformed backup: {e}"),
+            Self::Database(e) => write!(f, "database error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BackupError {}
+
+impl From<sqlx::Error> for BackupError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+impl From<serde_json::Error> for BackupError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl Backup {
+    /// Parses a backup document, rejecting versions this build can't restore.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for malformed JSON or an unsupported `version`.
+    pub fn from_json(json: &str) -> Result<Self, BackupError> {
+        let backup: Self = serde_json::from_str(json)?;
+        if backup.version != FORMAT_VERSION {
+            return Err(BackupError::UnsupportedVersion(backup.version));
+        }
+        Ok(backup)
+    }
+
+    /// Renders the backup as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialisation fails.
+    pub fn to_json(&self) -> Result<String, BackupError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Reads the whole database into a [`Backup`].
+///
+/// # Errors
+///
+/// Returns an error if any repository read fails.
+pub async fn export(repo: &dyn Repository) -> Result<Backup, BackupError> {
+    let teams = repo.list_teams().await?;
+    let projects = repo.list_projects().await?;
+    let resources = repo.list_resources().await?;
+    let assignments = repo.list_assignments(&AssignmentFilter::default()).await?;
+    let week_assignments = repo.list_week_assignments(&WeekFilter::default()).await?;
+    let actuals = repo.list_actuals(&AssignmentFilter::default()).await?;
+    let cost_rates = repo.list_cost_rates().await?;
+    let capacities = repo.list_capacities().await?;
+    let user_roles = repo.list_user_roles().await?;
+    let project_managers = repo.list_project_managers().await?;
+
+    Ok(Backup {
+        version: FORMAT_VERSION,
+        teams: teams
+            .into_iter()
+            .map(|t| TeamRecord {
+                id: t.id,
+                name: t.name,
+            })
+            .collect(),
+        projects: projects
+            .into_iter()
+            .map(|p| ProjectRecord {
+                id: p.id,
+                name: p.name,
+                parent_id: p.parent_id,
+                budget: p.budget,
+                status: p.status.to_string(),
+                colour: p.colour,
+                description: p.description,
+            })
+            .collect(),
+        resources: resources
+            .into_iter()
+            .map(|r| ResourceRecord {
+                id: r.id,
+                name: r.name,
+                team_id: r.team_id,
+                kind: r.kind.to_string(),
+                region: r.region,
+            })
+            .collect(),
+        assignments: assignments.into_iter().map(Into::into).collect(),
+        week_assignments: week_assignments.into_iter().map(Into::into).collect(),
+        actuals: actuals
+            .into_iter()
+            .map(|a| ActualRecord {
+                resource_id: a.resource_id,
+                project_id: a.project_id,
+                year: a.year,
+                month: a.month,
+                effort: a.effort,
+            })
+            .collect(),
+        cost_rates: cost_rates
+            .into_iter()
+            .map(|c| CostRateRecord {
+                resource_id: c.resource_id,
+                year: c.year,
+                month: c.month,
+                rate: c.rate,
+            })
+            .collect(),
+        capacities: capacities
+            .into_iter()
+            .map(|c| CapacityRecord {
+                resource_id: c.resource_id,
+                year: c.year,
+                month: c.month,
+                percent: c.percent,
+            })
+            .collect(),
+        user_roles: user_roles
+            .into_iter()
+            .map(|(user_id, role)| UserRoleRecord {
+                user_id,
+                role: role.to_string(),
+            })
+            .collect(),
+        project_managers: project_managers
+            .into_iter()
+            .map(|(project_id, user_id)| ProjectManagerRecord {
+                project_id,
+                user_id,
+            })
+            .collect(),
+    })
+}
+
+/// Writes `backup` into `repo`.
+///
+/// Everything is applied in one transaction, so a failing restore leaves the
+/// database as it was. Restoring the same backup twice leaves it unchanged.
+///
+/// # Errors
+///
+/// Returns an error if the backup references unknown rows, holds invalid
+/// values, or a repository write fails.
+pub async fn restore(repo: &dyn Repository, backup: &Backup) -> Result<(), BackupError> {
+    if backup.version != FORMAT_VERSION {
+        return Err(BackupError::UnsupportedVersion(backup.version));
+    }
+    let user_roles = backup
+        .user_roles
+        .iter()
+        .map(|r| {
+            let role: domain::Role = r.role.parse().map_err(BackupError::Invalid)?;
+            Ok((r.user_id.clone(), role))
+        })
+        .collect::<Result<Vec<_>, BackupError>>()?;
+    let projects = backup
+        .projects
+        .iter()
+        .cloned()
+        .map(TryInto::try_into)
+        .collect::<Result<Vec<_>, BackupError>>()?;
+    let resources = backup
+        .resources
+        .iter()
+        .map(|r| {
+            Ok(domain::Resource {
+                id: r.id,
+                name: r.name.clone(),
+                team_id: r.team_id,
+                kind: r.kind.parse().map_err(BackupError::Invalid)?,
+                region: r.region.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>, BackupError>>()?;
+    check_ranges(backup)?;
+
+    let batch = domain::Batch {
+        teams: backup
+            .teams
+            .iter()
+            .map(|t| domain::Team {
+                id: t.id,
+                name: t.name.clone(),
+            })
+            .collect(),
+        projects,
+        resources,
+        assignments: backup
+            .assignments
+            .iter()
+            .cloned()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?,
+        week_assignments: backup
+            .week_assignments
+            .iter()
+            .cloned()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?,
+        actuals: backup
+            .actuals
+            .iter()
+            .map(|a| domain::Actual {
+                resource_id: a.resource_id,
+                project_id: a.project_id,
+                year: a.year,
+                month: a.month,
+                effort: a.effort,
+            })
+            .collect(),
+        cost_rates: backup
+            .cost_rates
+            .iter()
+            .map(|c| domain::CostRate {
+                resource_id: c.resource_id,
+                year: c.year,
+                month: c.month,
+                rate: c.rate,
+            })
+            .collect(),
+        capacities: backup
+            .capacities
+            .iter()
+            .map(|c| domain::Capacity {
+                resource_id: c.resource_id,
+                year: c.year,
+                month: c.month,
+                percent: c.percent,
+            })
+            .collect(),
+        project_managers: backup
+            .project_managers
+            .iter()
+            .map(|m| (m.project_id, m.user_id.clone()))
+            .collect(),
+        user_roles,
+    };
+    Ok(repo.apply_batch(&batch).await?)
+}
+
+/// Rejects numbers the database constraints would refuse, before anything is
+/// written.
+fn check_ranges(backup: &Backup) -> Result<(), BackupError> {
+    let parents: HashMap<Uuid, Option<Uuid>> = backup
+        .projects
+        .iter()
+        .map(|p| (p.id, p.parent_id))
+        .collect();
+    if let Some(p) = backup.projects.iter().find(|p| {
+        let mut ancestor = p.parent_id;
+        // An acyclic chain leaves the backup's projects within this many steps
+        for _ in 0..parents.len() {
+            match ancestor {
+                Some(id) => ancestor = parents.get(&id).copied().flatten(),
+                None => return false,
+            }
+        }
+        ancestor.is_some()
+    }) {
+        return Err(BackupError::Invalid(format!(
+            "project {} has a cycle among its parents",
+            p.id
+        )));
+    }
+    if let Some(a) = backup
+        .assignments
+        .iter()
+        .find(|a| !(1..=12).contains(&a.month))
+    {
+        return Err(BackupError::Invalid(format!(
+            "assignment month {} is not between 1 and 12",
+            a.month
+        )));
+    }
+    if let Some(a) = backup.week_assignments.iter().find(|a| {
+        u8::try_from(a.week)
+            .ok()
+            .and_then(|week| domain::IsoWeek::new(a.year, week))
+            .is_none()
+    }) {
+        return Err(BackupError::Invalid(format!(
+            "assignment week {} is not a week of {}",
+            a.week, a.year
+        )));
+    }
+    if let Some(a) = backup
+        .actuals
+        .iter()
+        .find(|a| !(1..=12).contains(&a.month) || a.effort < 0)
+    {
+        return Err(BackupError::Invalid(format!(
+            "actual effort {} in month {} is out of range",
+            a.effort, a.month
+        )));
+    }
+    if let Some(c) = backup
+        .cost_rates
+        .iter()
+        .find(|c| !(1..=12).contains(&c.month) || c.rate < 0)
+    {
+        return Err(BackupError::Invalid(format!(
+            "cost rate {} from month {} is out of range",
+            c.rate, c.month
+        )));
+    }
+    if let Some(c) = backup
+        .capacities
+        .iter()
+        .find(|c| !(1..=12).contains(&c.month) || !(0..=domain::FULL_MONTH).contains(&c.percent))
+    {
+        return Err(BackupError::Invalid(format!(
+            "capacity {} in month {} is out of range",
+            c.percent, c.month
+        )));
+    }
+    if let Some(p) = backup
+        .projects
+        .iter()
+        .find(|p| p.budget.is_some_and(|b| b < 0))
+    {
+        return Err(BackupError::Invalid(format!(
+            "project {} has a negative budget",
+            p.id
+        )));
+    }
+    Ok(())
+}
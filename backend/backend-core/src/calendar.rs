@@ -0,0 +1,139 @@
+//! iCalendar (RFC 5545) feeds of resource assignments.
+//!
+//! Each assigned month becomes an all-day event spanning the whole month, so
+//! bookings show up in ordinary calendar clients. Tentative assignments are
+//! marked as tentative events.
+
+use crate::{
+    domain::{AssignmentStatus, YearMonth},
+    repo::{AssignmentFilter, Repository},
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Longest content line, in octets, before it is folded.
+const MAX_LINE: usize = 75;
+
+/// Renders the calendar for `resource_id`, or `None` if there is no such resource.
+///
+/// # Errors
+///
+/// Returns an error if reading from the repository fails.
+pub async fn resource_calendar(
+    repo: &dyn Repository,
+    resource_id: Uuid,
+) -> sqlx::Result<Option<String>> {
+    let Some(resource) = repo.resources(&[resource_id]).await?.pop() else {
+        return Ok(None);
+    };
+    let filter = AssignmentFilter {
+        resource_id: Some(resource_id),
+        ..Default::default()
+    };
+    let assignments = repo.list_assignments(&filter).await?;
+    let project_ids: Vec<Uuid> = assignments.iter().map(|a| a.project_id).collect();
+    let names: HashMap<Uuid, String> = repo
+        .projects(&project_ids)
+        .await?
+        .into_iter()
+        .map(|p| (p.id, p.name))
+        .collect();
+
+    let mut events: Vec<(YearMonth, Uuid, &str, AssignmentStatus)> = assignments
+        .iter()
+        .filter_map(|a| {
+            let month = YearMonth {
+                year: a.year,
+                month: u8::try_from(a.month).ok()?,
+            };
+            let name = names.get(&a.project_id)?;
+            Some((month, a.project_id, name.as_str(), a.status))
+        })
+        .collect();
+    events.sort_by(|(m1, _, n1, _), (m2, _, n2, _)| {
+        (m1.year, m1.month, n1).cmp(&(m2.year, m2.month, n2))
+    });
+
+    let stamp = time::OffsetDateTime::now_utc();
+    let stamp = format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        stamp.year(),
+        u8::from(stamp.month()),
+        stamp.day(),
+        stamp.hour(),
+        stamp.minute(),
+        stamp.second()
+    );
+
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//resourcing//assignments//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(
+        &mut out,
+        &format!("X-WR-CALNAME:{}", escape(&resource.name)),
+    );
+    for (month, project_id, name, status) in events {
+        let end = month.next();
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(
+            &mut out,
+            &format!("UID:{resource_id}-{project_id}-{month}@resourcing"),
+        );
+        push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+        push_line(
+            &mut out,
+            &format!("DTSTART;VALUE=DATE:{:04}{:02}01", month.year, month.month),
+        );
+        push_line(
+            &mut out,
+            &format!("DTEND;VALUE=DATE:{:04}{:02}01", end.year, end.month),
+        );
+        push_line(&mut out, &format!("SUMMARY:{}", escape(name)));
+        push_line(
+            &mut out,
+            match status {
+                AssignmentStatus::Tentative => "STATUS:TENTATIVE",
+                AssignmentStatus::Confirmed => "STATUS:CONFIRMED",
+            },
+        );
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    Ok(Some(out))
+}
+
+/// Escapes a TEXT property value.
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Appends `line` terminated by CRLF, folding it at [`MAX_LINE`] octets
+/// without splitting a UTF-8 character.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE {
+            out.push_str("\r\n ");
+            // The leading space counts towards the continuation line.
+            width = 1;
+        }
+        width += c.len_utf8();
+        out.push(c);
+    }
+    out.push_str("\r\n");
+}
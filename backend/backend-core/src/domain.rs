@@ -1,15 +1,121 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    ops::{Add, Sub},
+    str::FromStr,
+};
+use time::{Date, Month, OffsetDateTime, Weekday};
 use uuid::Uuid;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Project {
     pub id: Uuid,
     pub name: String,
+    /// The programme this project belongs to, if any.
+    pub parent_id: Option<Uuid>,
+    /// Total budget in whole units of the planning currency.
+    pub budget: Option<i64>,
+    pub status: ProjectStatus,
+    /// Display colour as `#rrggbb`, if any.
+    pub colour: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Where a project is in its lifecycle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ProjectStatus {
+    /// In the pipeline but not yet approved.
+    Proposed,
+    #[default]
+    Active,
+    OnHold,
+    Done,
+}
+
+impl ProjectStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Proposed => "proposed",
+            Self::Active => "active",
+            Self::OnHold => "on-hold",
+            Self::Done => "done",
+        }
+    }
+}
+
+impl fmt::Display for ProjectStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProjectStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "proposed" => Ok(Self::Proposed),
+            "active" => Ok(Self::Active),
+            "on-hold" => Ok(Self::OnHold),
+            "done" => Ok(Self::Done),
+            other => Err(format!("unknown project status: {other}")),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Resource {
     pub id: Uuid,
     pub name: String,
+    /// The team this resource belongs to, if any.
+    pub team_id: Option<Uuid>,
+    pub kind: ResourceKind,
+    /// Region whose working-day calendar applies, if any.
+    pub region: Option<String>,
+}
+
+/// Whether a resource is a named person or a placeholder for an unfilled role.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    #[default]
+    Person,
+    Placeholder,
+}
+
+impl ResourceKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Person => "person",
+            Self::Placeholder => "placeholder",
+        }
+    }
+}
+
+impl fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ResourceKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "person" => Ok(Self::Person),
+            "placeholder" => Ok(Self::Placeholder),
+            other => Err(format!("unknown resource kind: {other}")),
+        }
+    }
+}
+
+/// A group of resources planned together by a line manager.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Team {
+    pub id: Uuid,
+    pub name: String,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -18,4 +124,531 @@ pub struct Assignment {
     pub project_id: Uuid,
     pub year: i32,
     pub month: i32, // 1..=12
+    /// What the resource does on the project, e.g. "Tech Lead".
+    pub role: Option<String>,
+    pub note: Option<String>,
+    pub status: AssignmentStatus,
+}
+
+impl Assignment {
+    /// The columns identifying an assignment: `(resource_id, project_id, year, month)`.
+    #[must_use]
+    pub const fn key(&self) -> (Uuid, Uuid, i32, i32) {
+        (self.resource_id, self.project_id, self.year, self.month)
+    }
+}
+
+/// Whether an assignment is pencilled in or agreed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AssignmentStatus {
+    /// Pencilled in, pending confirmation.
+    Tentative,
+    #[default]
+    Confirmed,
+}
+
+impl AssignmentStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Tentative => "tentative",
+            Self::Confirmed => "confirmed",
+        }
+    }
+}
+
+impl fmt::Display for AssignmentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AssignmentStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tentative" => Ok(Self::Tentative),
+            "confirmed" => Ok(Self::Confirmed),
+            other => Err(format!("unknown assignment status: {other}")),
+        }
+    }
+}
+
+/// Longest window, in months, that a month range, an export or a bulk capacity
+/// change may cover.
+pub const MAX_MONTHS: usize = 240;
+
+/// A calendar month, ordered chronologically. Adding or subtracting an `i32`
+/// moves by that many months, and subtracting two months counts the months
+/// between them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct YearMonth {
+    pub year: i32,
+    pub month: u8, // 1..=12
+}
+
+impl YearMonth {
+    /// Latest year accepted when parsing; keeps arithmetic far from overflow.
+    const MAX_YEAR: i32 = 9999;
+
+    /// Returns `None` unless `month` is in 1..=12.
+    #[must_use]
+    pub fn new(year: i32, month: u8) -> Option<Self> {
+        (1..=12).contains(&month).then_some(Self { year, month })
+    }
+
+    /// The month holding `date`.
+    #[must_use]
+    pub fn containing(date: Date) -> Self {
+        Self {
+            year: date.year(),
+            month: u8::from(date.month()),
+        }
+    }
+
+    /// The current month in UTC.
+    #[must_use]
+    pub fn current() -> Self {
+        Self::containing(OffsetDateTime::now_utc().date())
+    }
+
+    /// The calendar month following `self`.
+    #[must_use]
+    pub fn next(self) -> Self {
+        self + 1
+    }
+
+    /// The calendar month preceding `self`.
+    #[must_use]
+    pub fn prev(self) -> Self {
+        self - 1
+    }
+
+    /// Every month from `from` to `to`, both inclusive; empty if `to` precedes `from`.
+    #[must_use]
+    pub fn range_inclusive(from: Self, to: Self) -> Vec<Self> {
+        let len = usize::try_from(to - from + 1).unwrap_or_default();
+        (0..len)
+            .scan(from, |m, _| {
+                let out = *m;
+                *m = m.next();
+                Some(out)
+            })
+            .collect()
+    }
+
+    /// Parses `YYYY-MM`, or a month relative to `current`: `current` itself,
+    /// `+N` months after it or `-N` months before it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message for anything else, or a year beyond 0..=9999.
+    pub fn resolve(value: &str, current: Self) -> Result<Self, &'static str> {
+        let month = match value {
+            "current" => current,
+            _ if value.starts_with(['+', '-']) => {
+                let offset: i32 = value
+                    .parse()
+                    .map_err(|_| "Relative month must be `current`, `+N` or `-N`")?;
+                current + offset
+            }
+            _ => value.parse()?,
+        };
+        if (0..=Self::MAX_YEAR).contains(&month.year) {
+            Ok(month)
+        } else {
+            Err("Month must fall between years 0000 and 9999")
+        }
+    }
+
+    /// Months since the start of year 0.
+    fn index(self) -> i64 {
+        i64::from(self.year) * 12 + i64::from(self.month) - 1
+    }
+
+    fn from_index(index: i64) -> Self {
+        let year = i32::try_from(index.div_euclid(12)).expect("YearMonth year overflowed");
+        let month = u8::try_from(index.rem_euclid(12) + 1).expect("rem_euclid(12) + 1 is 1..=12");
+        Self { year, month }
+    }
+}
+
+impl Add<i32> for YearMonth {
+    type Output = Self;
+
+    fn add(self, months: i32) -> Self {
+        Self::from_index(self.index() + i64::from(months))
+    }
+}
+
+impl Sub<i32> for YearMonth {
+    type Output = Self;
+
+    fn sub(self, months: i32) -> Self {
+        Self::from_index(self.index() - i64::from(months))
+    }
+}
+
+impl Sub for YearMonth {
+    type Output = i64;
+
+    /// Months from `other` to `self`; negative if `self` is earlier.
+    fn sub(self, other: Self) -> i64 {
+        self.index() - other.index()
+    }
+}
+
+impl fmt::Display for YearMonth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+impl FromStr for YearMonth {
+    type Err = &'static str;
+
+    /// Parses `YYYY-MM`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split('-').collect();
+        if parts.len() != 2 {
+            return Err("Month must be in YYYY-MM format");
+        }
+        let year: i32 = parts[0].parse().map_err(|_| "Invalid year in Month")?;
+        let month: u8 = parts[1].parse().map_err(|_| "Invalid month in Month")?;
+        Self::new(year, month).ok_or("Month must be between 01 and 12")
+    }
+}
+
+/// An ISO 8601 week. Weeks start on Monday and belong to the year, and the
+/// month, holding their Thursday, so every week falls in exactly one month.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IsoWeek {
+    /// The ISO week-numbering year, which differs from the calendar year for
+    /// some days around New Year.
+    pub year: i32,
+    pub week: u8, // 1..=52 or 53
+}
+
+impl IsoWeek {
+    /// Returns `None` unless `year` has a week `week`.
+    #[must_use]
+    pub fn new(year: i32, week: u8) -> Option<Self> {
+        Date::from_iso_week_date(year, week, Weekday::Monday)
+            .ok()
+            .map(|_| Self { year, week })
+    }
+
+    /// The week holding `date`.
+    #[must_use]
+    pub const fn containing(date: Date) -> Self {
+        let (year, week, _) = date.to_iso_week_date();
+        Self { year, week }
+    }
+
+    /// The week holding the first day of `month` in `year`.
+    #[must_use]
+    pub fn first_of_month(year: i32, month: u8) -> Option<Self> {
+        let month = Month::try_from(month).ok()?;
+        Date::from_calendar_date(year, month, 1)
+            .ok()
+            .map(Self::containing)
+    }
+
+    /// The week holding the last day of `month` in `year`.
+    #[must_use]
+    pub fn last_of_month(year: i32, month: u8) -> Option<Self> {
+        let month = Month::try_from(month).ok()?;
+        Date::from_calendar_date(year, month, month.length(year))
+            .ok()
+            .map(Self::containing)
+    }
+
+    fn thursday(self) -> Date {
+        Date::from_iso_week_date(self.year, self.week, Weekday::Thursday)
+            .expect("IsoWeek is always a valid week")
+    }
+
+    /// The `(year, month)` the week rolls up into.
+    #[must_use]
+    pub fn month(self) -> (i32, u8) {
+        let thursday = self.thursday();
+        (thursday.year(), u8::from(thursday.month()))
+    }
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        Self::containing(self.thursday() + time::Duration::weeks(1))
+    }
+
+    /// The weeks rolling up into `month` of `year`, in order.
+    #[must_use]
+    pub fn of_month(year: i32, month: u8) -> Vec<Self> {
+        let (Some(mut week), Some(last)) = (
+            Self::first_of_month(year, month),
+            Self::last_of_month(year, month),
+        ) else {
+            return vec![];
+        };
+        let mut out = Vec::new();
+        loop {
+            if week.month() == (year, month) {
+                out.push(week);
+            }
+            if week == last {
+                return out;
+            }
+            week = week.next();
+        }
+    }
+}
+
+impl fmt::Display for IsoWeek {
+    /// Formats as `YYYY-Www`, e.g. `2025-W07`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-W{:02}", self.year, self.week)
+    }
+}
+
+impl FromStr for IsoWeek {
+    type Err = &'static str;
+
+    /// Parses `YYYY-Www`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (year, week) = s
+            .split_once("-W")
+            .ok_or("Week must be in YYYY-Www format")?;
+        let year: i32 = year.parse().map_err(|_| "Invalid year in Week")?;
+        let week: u8 = week.parse().map_err(|_| "Invalid week in Week")?;
+        Self::new(year, week).ok_or("Week must be between W01 and the last week of its year")
+    }
+}
+
+/// A resource booked on a project for a single week rather than a month.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeekAssignment {
+    pub resource_id: Uuid,
+    pub project_id: Uuid,
+    /// ISO week-numbering year.
+    pub year: i32,
+    pub week: i32, // 1..=53
+    /// What the resource does on the project, e.g. "Tech Lead".
+    pub role: Option<String>,
+    pub note: Option<String>,
+    pub status: AssignmentStatus,
+}
+
+impl WeekAssignment {
+    /// The columns identifying a week assignment: `(resource_id, project_id, year, week)`.
+    #[must_use]
+    pub const fn key(&self) -> (Uuid, Uuid, i32, i32) {
+        (self.resource_id, self.project_id, self.year, self.week)
+    }
+
+    /// The booked week, or `None` if the row names a week its year lacks.
+    #[must_use]
+    pub fn iso_week(&self) -> Option<IsoWeek> {
+        IsoWeek::new(self.year, u8::try_from(self.week).ok()?)
+    }
+
+    /// The monthly assignment this week rolls up into, keeping its role, note
+    /// and status.
+    #[must_use]
+    pub fn to_month(&self) -> Option<Assignment> {
+        let (year, month) = self.iso_week()?.month();
+        Some(Assignment {
+            resource_id: self.resource_id,
+            project_id: self.project_id,
+            year,
+            month: i32::from(month),
+            role: self.role.clone(),
+            note: self.note.clone(),
+            status: self.status,
+        })
+    }
+}
+
+/// Effort of a whole month, in the percentages used by [`Actual::effort`] and
+/// [`Capacity::percent`]. A planned assignment stands for this much.
+pub const FULL_MONTH: i32 = 100;
+
+/// A monthly assignment, or the weeks a resource is booked on a project rolled
+/// up into their month.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Booking {
+    pub assignment: Assignment,
+    /// Share of the month booked, in [`FULL_MONTH`] percent: all of it for a
+    /// monthly assignment, otherwise the booked weeks' share of the month's weeks.
+    pub effort: i32,
+}
+
+/// Rolls `weeks` up into the month holding their Thursday, next to `monthly`.
+/// A week only adds a booking where the resource isn't already assigned to the
+/// project for that month; weeks rolled up together are confirmed if any of
+/// them is.
+#[must_use]
+pub fn roll_up(
+    monthly: Vec<Assignment>,
+    weeks: impl IntoIterator<Item = WeekAssignment>,
+) -> Vec<Booking> {
+    let mut out: Vec<Booking> = monthly
+        .into_iter()
+        .map(|assignment| Booking {
+            assignment,
+            effort: FULL_MONTH,
+        })
+        .collect();
+    let monthly = out.len();
+    let mut index: HashMap<_, usize> = out
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.assignment.key(), i))
+        .collect();
+    // Count the weeks of each rolled-up booking first, then turn them into a share
+    for week in weeks {
+        let Some(a) = week.to_month() else {
+            continue;
+        };
+        match index.get(&a.key()) {
+            None => {
+                index.insert(a.key(), out.len());
+                out.push(Booking {
+                    assignment: a,
+                    effort: 1,
+                });
+            }
+            Some(&i) if i >= monthly => {
+                let b = &mut out[i];
+                b.effort += 1;
+                if a.status == AssignmentStatus::Confirmed {
+                    b.assignment.status = a.status;
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    for b in &mut out[monthly..] {
+        let a = &b.assignment;
+        let weeks = u8::try_from(a.month).map_or(0, |m| IsoWeek::of_month(a.year, m).len());
+        let weeks = i32::try_from(weeks).unwrap_or(i32::MAX).max(1);
+        b.effort = (b.effort * FULL_MONTH / weeks).min(FULL_MONTH);
+    }
+    out
+}
+
+/// Effort a resource actually spent on a project in a month.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Actual {
+    pub resource_id: Uuid,
+    pub project_id: Uuid,
+    pub year: i32,
+    pub month: i32, // 1..=12
+    /// Percentage of a full month; 100 matches one planned assignment.
+    pub effort: i32,
+}
+
+/// How much of a month a resource can work, overriding the default of
+/// [`FULL_MONTH`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Capacity {
+    pub resource_id: Uuid,
+    pub year: i32,
+    pub month: i32, // 1..=12
+    /// Percentage of a full month: 100 is 1.0 FTE, 50 a half-time month and
+    /// 0 a month off.
+    pub percent: i32,
+}
+
+/// Monthly cost of a resource from `year`/`month` until its next rate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostRate {
+    pub resource_id: Uuid,
+    pub year: i32,
+    pub month: i32, // 1..=12
+    /// Whole units of the planning currency per assigned month.
+    pub rate: i64,
+}
+
+/// Rows written together by [`crate::Repository::apply_batch`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Batch {
+    pub teams: Vec<Team>,
+    pub projects: Vec<Project>,
+    pub resources: Vec<Resource>,
+    pub assignments: Vec<Assignment>,
+    pub week_assignments: Vec<WeekAssignment>,
+    pub actuals: Vec<Actual>,
+    pub cost_rates: Vec<CostRate>,
+    pub capacities: Vec<Capacity>,
+    /// Manager grants as `(project_id, user_id)` pairs.
+    pub project_managers: Vec<(Uuid, String)>,
+    /// Role grants as `(user_id, role)` pairs.
+    pub user_roles: Vec<(String, Role)>,
+}
+
+/// Dimension that [`crate::Repository::utilisation`] aggregates assignments over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UtilisationGroup {
+    /// Resources assigned to each project in each month.
+    Project,
+    /// Months booked by each resource.
+    Resource,
+    /// Distinct resources assigned in each month.
+    Month,
+}
+
+/// One aggregate from [`crate::Repository::utilisation`]. Keys that are not
+/// part of the grouping are `None`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Utilisation {
+    pub project_id: Option<Uuid>,
+    pub resource_id: Option<Uuid>,
+    pub year: Option<i32>,
+    pub month: Option<i32>,
+    pub count: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub user_id: String,
+    pub action: String,
+    pub detail: String,
+}
+
+/// Global permission level of a user, ordered from least to most privileged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Role {
+    Viewer,
+    Planner,
+    Admin,
+}
+
+impl Role {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Planner => "planner",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "viewer" => Ok(Self::Viewer),
+            "planner" => Ok(Self::Planner),
+            "admin" => Ok(Self::Admin),
+            other => Err(format!("unknown role: {other}")),
+        }
+    }
 }
@@ -0,0 +1,207 @@
+//! Spreadsheet exports of the project and resource month matrices.
+//!
+//! Exports are built from the same matrices as `projectMonthMatrix` and
+//! `resourceMonthMatrix`, so a file always matches what the UI shows.
+
+use crate::{
+    domain::MAX_MONTHS,
+    gql::{
+        matrix::{self, RowPage},
+        types::{MatrixRowFilter, MatrixSort, MonthScalar},
+    },
+    repo::Repository,
+    workdays::WorkingCalendars,
+};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ExportError {
+    /// The requested month window is malformed or too large.
+    InvalidWindow(String),
+    Database(sqlx::Error),
+    Csv(csv::Error),
+    Xlsx(rust_xlsxwriter::XlsxError),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidWindow(msg) => f.write_str(msg),
+            Self::Database(e) => write!(f, "database error: {e}"),
+            Self::Csv(e) => write!(f, "CSV error: {e}"),
+            Self::Xlsx(e) => write!(f, "XLSX error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<sqlx::Error> for ExportError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+impl From<csv::Error> for ExportError {
+    fn from(e: csv::Error) -> Self {
+        Self::Csv(e)
+    }
+}
+
+impl From<rust_xlsxwriter::XlsxError> for ExportError {
+    fn from(e: rust_xlsxwriter::XlsxError) -> Self {
+        Self::Xlsx(e)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Matrix {
+    /// Projects down the side, assigned resources in each cell.
+    Project,
+    /// Resources down the side, their projects in each cell.
+    Resource,
+}
+
+/// A rendered matrix: a header row followed by one row per project or resource.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Separator between names sharing a cell.
+const CELL_SEPARATOR: &str = "; ";
+
+/// Builds `matrix` for the inclusive window `from..=to`. Each bound is `YYYY-MM`
+/// or relative to the current month, like a GraphQL `Month` ("current", "+3").
+///
+/// # Errors
+///
+/// Returns [`ExportError::InvalidWindow`] if either bound fails to parse, `to`
+/// precedes `from`, or the window exceeds [`MAX_MONTHS`].
+pub async fn matrix_table(
+    repo: &dyn Repository,
+    matrix: Matrix,
+    from: &str,
+    to: &str,
+) -> Result<Table, ExportError> {
+    let months = window(from, to)?;
+    let mut header = vec![match matrix {
+        Matrix::Project => "Project".to_string(),
+        Matrix::Resource => "Resource".to_string(),
+    }];
+    header.extend(months.iter().map(ToString::to_string));
+
+    let rows = match matrix {
+        Matrix::Project => matrix::project_month_matrix(
+            repo,
+            months,
+            &MatrixRowFilter::default(),
+            &MatrixSort::default(),
+            RowPage::default(),
+        )
+        .await?
+        .rows
+        .edges
+        .into_iter()
+        .map(|edge| edge.node)
+        .map(|row| {
+            let cells = row
+                .cells
+                .into_iter()
+                .map(|cell| join_names(cell.resources.into_iter().map(|r| r.name)));
+            std::iter::once(row.project.name).chain(cells).collect()
+        })
+        .collect(),
+        // Exports only list project names, so working days don't matter here
+        Matrix::Resource => matrix::resource_month_matrix(
+            repo,
+            &WorkingCalendars::default(),
+            months,
+            &MatrixRowFilter::default(),
+            &MatrixSort::default(),
+            RowPage::default(),
+        )
+        .await?
+        .rows
+        .edges
+        .into_iter()
+        .map(|edge| edge.node)
+        .map(|row| {
+            let cells = row
+                .cells
+                .into_iter()
+                .map(|cell| join_names(cell.projects.into_iter().map(|p| p.name)));
+            std::iter::once(row.resource.name).chain(cells).collect()
+        })
+        .collect(),
+    };
+    Ok(Table { header, rows })
+}
+
+/// Joins a cell's names alphabetically, so files don't depend on id order.
+fn join_names(names: impl Iterator<Item = String>) -> String {
+    let mut names: Vec<String> = names.collect();
+    names.sort();
+    names.join(CELL_SEPARATOR)
+}
+
+fn window(from: &str, to: &str) -> Result<Vec<MonthScalar>, ExportError> {
+    let current = MonthScalar::current();
+    let parse = |name: &str, value: &str| {
+        MonthScalar::resolve(value, current)
+            .map_err(|e| ExportError::InvalidWindow(format!("`{name}`: {e}")))
+    };
+    let months = MonthScalar::range_inclusive(parse("from", from)?, parse("to", to)?);
+    if months.is_empty() {
+        return Err(ExportError::InvalidWindow(
+            "`to` must not be before `from`".into(),
+        ));
+    }
+    if months.len() > MAX_MONTHS {
+        return Err(ExportError::InvalidWindow(format!(
+            "window must not exceed {MAX_MONTHS} months"
+        )));
+    }
+    Ok(months)
+}
+
+impl Table {
+    /// Renders the table as RFC 4180 CSV.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CSV writer fails.
+    pub fn to_csv(&self) -> Result<Vec<u8>, ExportError> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(&self.header)?;
+        for row in &self.rows {
+            writer.write_record(row)?;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| ExportError::Csv(e.into_error().into()))
+    }
+
+    /// Renders the table as a single-sheet XLSX workbook.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the workbook cannot be written.
+    pub fn to_xlsx(&self) -> Result<Vec<u8>, ExportError> {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        let bold = rust_xlsxwriter::Format::new().set_bold();
+        for (col, value) in (0u16..).zip(&self.header) {
+            sheet.write_string_with_format(0, col, value, &bold)?;
+        }
+        for (row, values) in (1u32..).zip(&self.rows) {
+            for (col, value) in (0u16..).zip(values) {
+                sheet.write_string(row, col, value)?;
+            }
+        }
+        sheet.set_freeze_panes(1, 1)?;
+        sheet.autofit();
+        Ok(workbook.save_to_buffer()?)
+    }
+}
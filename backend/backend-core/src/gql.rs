@@ -1,21 +1,62 @@
+mod actuals;
+mod cost;
+mod guard;
 mod loader;
+pub mod matrix;
 pub mod mutation;
 pub mod query;
 pub mod types;
 
-use crate::repo::Repo;
+use crate::{repo::Repo, workdays::WorkingCalendars};
 
-use async_graphql::{EmptySubscription, Schema, dataloader::DataLoader};
+use async_graphql::{
+    EmptyMutation, EmptySubscription, Schema, SchemaBuilder, dataloader::DataLoader,
+};
 
 pub type AppSchema = Schema<query::QueryRoot, mutation::MutationRoot, EmptySubscription>;
 
+/// Schema exposing only [`query::QueryRoot`]; every mutation is rejected.
+pub type ReadOnlySchema = Schema<query::QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Builds the schema with weekdays-only working-day calendars.
 pub fn build_schema(repo: Repo) -> AppSchema {
+    build_schema_with_calendars(repo, WorkingCalendars::default())
+}
+
+pub fn build_schema_with_calendars(repo: Repo, calendars: WorkingCalendars) -> AppSchema {
+    with_repo(
+        Schema::build(query::QueryRoot, mutation::MutationRoot, EmptySubscription),
+        repo,
+    )
+    .data(calendars)
+    .finish()
+}
+
+/// Builds the read-only schema with weekdays-only working-day calendars.
+pub fn build_read_only_schema(repo: Repo) -> ReadOnlySchema {
+    build_read_only_schema_with_calendars(repo, WorkingCalendars::default())
+}
+
+pub fn build_read_only_schema_with_calendars(
+    repo: Repo,
+    calendars: WorkingCalendars,
+) -> ReadOnlySchema {
+    with_repo(
+        Schema::build(query::QueryRoot, EmptyMutation, EmptySubscription),
+        repo,
+    )
+    .data(calendars)
+    .finish()
+}
+
+fn with_repo<Q, M, S>(builder: SchemaBuilder<Q, M, S>, repo: Repo) -> SchemaBuilder<Q, M, S> {
     let project_loader = DataLoader::new(loader::ProjectLoader::new(repo.clone()), tokio::spawn);
     let resource_loader = DataLoader::new(loader::ResourceLoader::new(repo.clone()), tokio::spawn);
+    let team_loader = DataLoader::new(loader::TeamLoader::new(repo.clone()), tokio::spawn);
 
-    Schema::build(query::QueryRoot, mutation::MutationRoot, EmptySubscription)
+    builder
         .data(repo)
         .data(project_loader)
         .data(resource_loader)
-        .finish()
+        .data(team_loader)
 }
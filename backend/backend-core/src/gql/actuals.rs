@@ -0,0 +1,113 @@
+//! Planned effort compared with the actuals recorded against it.
+
+use super::{
+    matrix,
+    types::{EffortComparison, MonthScalar, PlanVsActual, PlanVsActualRow},
+};
+use crate::{
+    repo::{AssignmentFilter, Repository},
+    workdays::WorkingCalendars,
+};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Planned against actual effort on `project_id` in each of `months`, with a
+/// row for every resource planned or recorded in the window. Person-days follow
+/// each resource's working-day calendar. Returns `None` if the project doesn't
+/// exist.
+pub async fn plan_vs_actual(
+    repo: &dyn Repository,
+    calendars: &WorkingCalendars,
+    project_id: Uuid,
+    months: Vec<MonthScalar>,
+) -> sqlx::Result<Option<PlanVsActual>> {
+    let Some(project) = repo.projects(&[project_id]).await?.pop() else {
+        return Ok(None);
+    };
+    let filter = AssignmentFilter {
+        project_id: Some(project_id),
+        ..Default::default()
+    };
+    let window: HashSet<(i32, i32)> = months
+        .iter()
+        .map(|m| (m.year, i32::from(m.month)))
+        .collect();
+
+    let planned: HashMap<(Uuid, i32, i32), i32> = matrix::project_bookings(repo, project_id)
+        .await?
+        .into_iter()
+        .filter(|b| window.contains(&(b.assignment.year, b.assignment.month)))
+        .map(|b| {
+            let a = b.assignment;
+            ((a.resource_id, a.year, a.month), b.effort)
+        })
+        .collect();
+    let actual: HashMap<(Uuid, i32, i32), i32> = repo
+        .list_actuals(&filter)
+        .await?
+        .into_iter()
+        .filter(|a| window.contains(&(a.year, a.month)))
+        .map(|a| ((a.resource_id, a.year, a.month), a.effort))
+        .collect();
+
+    let ids: HashSet<Uuid> = planned
+        .keys()
+        .map(|(id, ..)| *id)
+        .chain(actual.keys().map(|(id, ..)| *id))
+        .collect();
+    let mut resources = repo.resources(&ids.into_iter().collect::<Vec<_>>()).await?;
+    resources.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut totals = vec![EffortComparison::default(); months.len()];
+    let rows = resources
+        .into_iter()
+        .map(|r| {
+            let cells: Vec<EffortComparison> = months
+                .iter()
+                .map(|m| {
+                    let key = (r.id, m.year, i32::from(m.month));
+                    let planned = planned.get(&key).copied().unwrap_or(0);
+                    let working_days = calendars.working_days(r.region.as_deref(), m.year, m.month);
+                    let actual = actual.get(&key).copied().unwrap_or(0);
+                    EffortComparison::new(planned, actual, working_days)
+                })
+                .collect();
+            for (total, cell) in totals.iter_mut().zip(&cells) {
+                *total = total.add(cell);
+            }
+            PlanVsActualRow {
+                resource: r.into(),
+                total: cells
+                    .iter()
+                    .fold(EffortComparison::default(), EffortComparison::add),
+                cells,
+            }
+        })
+        .collect();
+    Ok(Some(PlanVsActual {
+        project: project.into(),
+        months,
+        rows,
+        totals,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain;
+
+    #[test]
+    fn variance_is_actual_minus_planned() {
+        let over = EffortComparison::new(domain::FULL_MONTH, 120, 20);
+        assert_eq!(over.variance, 20);
+        assert!((over.actual_days - 24.0).abs() < f64::EPSILON);
+        let unplanned = EffortComparison::new(0, 30, 20);
+        let sum = over.add(&unplanned);
+        assert_eq!(
+            (sum.planned, sum.actual, sum.variance),
+            (domain::FULL_MONTH, 150, 50)
+        );
+        assert!((sum.planned_days - 20.0).abs() < f64::EPSILON);
+    }
+}
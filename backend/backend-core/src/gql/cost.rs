@@ -0,0 +1,111 @@
+//! Planned spend derived from assignments and resource cost rates.
+
+use super::{
+    matrix,
+    types::{MonthScalar, ProjectCost, ProjectCostMonth},
+};
+use crate::{domain, repo::Repository};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// Rates keyed by the `(year, month)` they take effect.
+type Rates = Vec<((i32, i32), i64)>;
+
+/// Each resource's rates, ordered by the month they take effect.
+struct RateCard(HashMap<Uuid, Rates>);
+
+impl RateCard {
+    fn new(rates: Vec<domain::CostRate>) -> Self {
+        let mut by_resource: HashMap<Uuid, Rates> = HashMap::new();
+        for c in rates {
+            by_resource
+                .entry(c.resource_id)
+                .or_default()
+                .push(((c.year, c.month), c.rate));
+        }
+        for rates in by_resource.values_mut() {
+            rates.sort_unstable();
+        }
+        Self(by_resource)
+    }
+
+    /// The rate in effect for `resource_id` in `month`, if one has started.
+    fn rate(&self, resource_id: Uuid, month: (i32, i32)) -> Option<i64> {
+        let rates = self.0.get(&resource_id)?;
+        let started = rates.partition_point(|(from, _)| *from <= month);
+        started.checked_sub(1).map(|i| rates[i].1)
+    }
+}
+
+/// Planned spend on `project_id` in each of `months`, with a burn-down against
+/// its budget. Each assignment costs its resource's rate for that month, and
+/// week assignments their share of it.
+/// Returns `None` if the project doesn't exist.
+pub async fn project_cost(
+    repo: &dyn Repository,
+    project_id: Uuid,
+    months: Vec<MonthScalar>,
+) -> sqlx::Result<Option<ProjectCost>> {
+    let Some(project) = repo.projects(&[project_id]).await?.pop() else {
+        return Ok(None);
+    };
+    let rates = RateCard::new(repo.list_cost_rates().await?);
+
+    // Spend and unrated assignments for every planned month, not just the
+    // requested ones, so the burn-down includes earlier months
+    let mut by_month: BTreeMap<(i32, i32), (i64, usize)> = BTreeMap::new();
+    for b in matrix::project_bookings(repo, project_id).await? {
+        let month = (b.assignment.year, b.assignment.month);
+        let entry = by_month.entry(month).or_default();
+        match rates.rate(b.assignment.resource_id, month) {
+            Some(rate) => entry.0 += rate * i64::from(b.effort) / i64::from(domain::FULL_MONTH),
+            None => entry.1 += 1,
+        }
+    }
+
+    let budget = project.budget;
+    let months = months
+        .into_iter()
+        .map(|m| {
+            let key = (m.year, i32::from(m.month));
+            let (planned, unrated_count) = by_month.get(&key).copied().unwrap_or_default();
+            let cumulative: i64 = by_month.range(..=key).map(|(_, (spend, _))| spend).sum();
+            ProjectCostMonth {
+                month: m,
+                planned,
+                cumulative,
+                remaining: budget.map(|b| b - cumulative),
+                unrated_count,
+            }
+        })
+        .collect();
+    Ok(Some(ProjectCost {
+        project: project.into(),
+        budget,
+        total_planned: by_month.values().map(|(spend, _)| spend).sum(),
+        months,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_apply_from_their_month_until_replaced() {
+        let r = Uuid::new_v4();
+        let rate = |year, month, rate| domain::CostRate {
+            resource_id: r,
+            year,
+            month,
+            rate,
+        };
+        let card = RateCard::new(vec![rate(2025, 4, 120), rate(2025, 1, 100)]);
+        assert_eq!(card.rate(r, (2024, 12)), None);
+        assert_eq!(card.rate(r, (2025, 1)), Some(100));
+        assert_eq!(card.rate(r, (2025, 3)), Some(100));
+        assert_eq!(card.rate(r, (2025, 4)), Some(120));
+        assert_eq!(card.rate(r, (2026, 1)), Some(120));
+        assert_eq!(card.rate(Uuid::new_v4(), (2025, 4)), None);
+    }
+}
@@ -0,0 +1,74 @@
+//! Field guards enforcing authentication and role-based access.
+
+use crate::{auth::CurrentUser, domain::Role, repo::Repo};
+use async_graphql::{Context, Guard, Result};
+use uuid::Uuid;
+
+/// Returns the authenticated caller, rejecting anonymous requests.
+pub(super) fn current_user<'a>(ctx: &Context<'a>) -> Result<&'a CurrentUser> {
+    ctx.data_opt::<CurrentUser>()
+        .ok_or_else(|| "Authentication required".into())
+}
+
+/// Looks up the caller's role; users without a grant are viewers.
+async fn current_role<'a>(ctx: &Context<'a>) -> Result<(&'a CurrentUser, Role)> {
+    let user = current_user(ctx)?;
+    let repo = ctx.data_unchecked::<Repo>();
+    let role = repo.user_role(&user.id).await?.unwrap_or(Role::Viewer);
+    Ok((user, role))
+}
+
+/// Requires the caller to hold at least `min`.
+pub struct RoleGuard {
+    min: Role,
+}
+
+impl RoleGuard {
+    pub const fn new(min: Role) -> Self {
+        Self { min }
+    }
+}
+
+impl Guard for RoleGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let (_, role) = current_role(ctx).await?;
+        if role >= self.min {
+            Ok(())
+        } else {
+            Err(format!("Requires the {} role", self.min).into())
+        }
+    }
+}
+
+/// Requires the caller to manage `project_id`. Admins manage every project.
+pub struct ProjectManagerGuard {
+    project_id: Uuid,
+}
+
+impl ProjectManagerGuard {
+    pub const fn new(project_id: Uuid) -> Self {
+        Self { project_id }
+    }
+}
+
+impl Guard for ProjectManagerGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        require_project_manager(ctx, &[self.project_id]).await
+    }
+}
+
+/// Requires the caller to manage every project in `project_ids`, for resolvers
+/// whose projects aren't known until they run. Admins manage every project.
+pub(super) async fn require_project_manager(ctx: &Context<'_>, project_ids: &[Uuid]) -> Result<()> {
+    let (user, role) = current_role(ctx).await?;
+    if role == Role::Admin {
+        return Ok(());
+    }
+    let repo = ctx.data_unchecked::<Repo>();
+    for project_id in project_ids {
+        if !repo.is_project_manager(*project_id, &user.id).await? {
+            return Err("Only the project's managers can change its assignments".into());
+        }
+    }
+    Ok(())
+}
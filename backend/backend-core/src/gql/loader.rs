@@ -12,6 +12,10 @@ pub struct ProjectLoader {
 pub struct ResourceLoader {
     repo: Repo,
 }
+#[derive(Clone)]
+pub struct TeamLoader {
+    repo: Repo,
+}
 
 impl ProjectLoader {
     pub fn new(repo: Repo) -> Self {
@@ -23,6 +27,11 @@ impl ResourceLoader {
         Self { repo }
     }
 }
+impl TeamLoader {
+    pub fn new(repo: Repo) -> Self {
+        Self { repo }
+    }
+}
 
 impl Loader<Uuid> for ProjectLoader {
     type Value = domain::Project;
@@ -52,3 +61,17 @@ impl Loader<Uuid> for ResourceLoader {
         Ok(items.into_iter().map(|r| (r.id, r)).collect())
     }
 }
+
+impl Loader<Uuid> for TeamLoader {
+    type Value = domain::Team;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let items = self
+            .repo
+            .teams(keys)
+            .await
+            .map_err(|e| async_graphql::Error::new(e.to_string()))?;
+        Ok(items.into_iter().map(|t| (t.id, t)).collect())
+    }
+}
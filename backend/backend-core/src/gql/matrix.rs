@@ -0,0 +1,695 @@
+//! Month and week matrix construction shared by `QueryRoot` and the file exports.
+//!
+//! Week assignments roll up into the month holding their Thursday, so a
+//! project booked for any week of a month shows in that month's cells.
+
+use super::types::{
+    MatrixRowFilter, MatrixSort, MatrixSortField, MonthScalar, Project, ProjectMonthCell,
+    ProjectMonthMatrix, ProjectMonthMatrixRow, ProjectWeekCell, ProjectWeekMatrix,
+    ProjectWeekMatrixRow, Resource, ResourceMonthCell, ResourceMonthMatrix, ResourceMonthMatrixRow,
+    RowConnection, RowCount, RowCursor, TeamMonthCell, TeamMonthMatrix, TeamMonthMatrixRow,
+    WeekScalar,
+};
+use crate::{
+    domain,
+    repo::{AssignmentFilter, Repository, RowFilter, WeekFilter},
+    workdays::{self, WorkingCalendars},
+};
+use async_graphql::{
+    OutputType,
+    connection::{Connection, Edge},
+};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Assignments keyed by row id (project or resource), year and month or week.
+type ByPeriod<T> = HashMap<(Uuid, i32, i32), Vec<T>>;
+type ByMonth = ByPeriod<domain::Assignment>;
+
+/// Forward pagination over matrix rows.
+#[derive(Clone, Debug, Default)]
+pub struct RowPage {
+    /// Maximum number of rows to return; all remaining rows if `None`.
+    pub first: Option<usize>,
+    /// Return rows after this cursor, which must fit the matrix's sort.
+    pub after: Option<RowCursor>,
+}
+
+/// Where a page of rows sits among every row matching a matrix's filter.
+struct Paging {
+    /// One per row on the page, in order.
+    cursors: Vec<RowCursor>,
+    total_count: usize,
+    has_previous_page: bool,
+    has_next_page: bool,
+}
+
+impl Paging {
+    /// Builds the connection holding `nodes`, one per cursor.
+    fn connect<N: OutputType>(self, nodes: Vec<N>) -> RowConnection<N> {
+        let mut out = Connection::with_additional_fields(
+            self.has_previous_page,
+            self.has_next_page,
+            RowCount {
+                total_count: self.total_count,
+            },
+        );
+        let edges = self.cursors.into_iter().zip(nodes);
+        out.edges
+            .extend(edges.map(|(cursor, node)| Edge::new(cursor, node)));
+        out
+    }
+}
+
+/// The months just outside `months`, whose counts are reported on each row.
+fn edges(months: &[MonthScalar]) -> (Option<MonthScalar>, Option<MonthScalar>) {
+    (
+        months.first().copied().map(MonthScalar::prev),
+        months.last().copied().map(MonthScalar::next),
+    )
+}
+
+/// `(year, month)` keys of `months`, as used in [`ByMonth`].
+fn month_keys(months: &[MonthScalar]) -> Vec<(i32, i32)> {
+    months
+        .iter()
+        .map(|m| (m.year, i32::from(m.month)))
+        .collect()
+}
+
+/// Loads the bookings of every distinct month in `months`, including weeks
+/// rolled up into them as by [`domain::roll_up`].
+async fn bookings_in<'a>(
+    repo: &dyn Repository,
+    months: impl Iterator<Item = &'a MonthScalar>,
+) -> sqlx::Result<Vec<domain::Booking>> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    for m in months {
+        if !seen.insert(m) {
+            continue;
+        }
+        let f = AssignmentFilter {
+            year: Some(m.year),
+            month: Some(i32::from(m.month)),
+            ..Default::default()
+        };
+        out.extend(repo.list_assignments(&f).await?);
+    }
+
+    let (Some(first), Some(last)) = (
+        seen.iter().min_by_key(|m| (m.year, m.month)),
+        seen.iter().max_by_key(|m| (m.year, m.month)),
+    ) else {
+        return Ok(vec![]);
+    };
+    let f = WeekFilter {
+        from: domain::IsoWeek::first_of_month(first.year, first.month),
+        to: domain::IsoWeek::last_of_month(last.year, last.month),
+        ..Default::default()
+    };
+    let weeks = repo.list_week_assignments(&f).await?;
+    let mut bookings = domain::roll_up(out, weeks);
+    bookings.retain(|b| {
+        seen.contains(&MonthScalar {
+            year: b.assignment.year,
+            month: u8::try_from(b.assignment.month).unwrap_or_default(),
+        })
+    });
+    Ok(bookings)
+}
+
+/// Loads every booking of `project_id`, in any month, with its weeks rolled up.
+pub(super) async fn project_bookings(
+    repo: &dyn Repository,
+    project_id: Uuid,
+) -> sqlx::Result<Vec<domain::Booking>> {
+    let monthly = repo
+        .list_assignments(&AssignmentFilter {
+            project_id: Some(project_id),
+            ..Default::default()
+        })
+        .await?;
+    let weeks = repo
+        .list_week_assignments(&WeekFilter {
+            project_id: Some(project_id),
+            ..Default::default()
+        })
+        .await?;
+    Ok(domain::roll_up(monthly, weeks))
+}
+
+/// The assignments of [`bookings_in`], for cells that don't weigh them.
+async fn assignments_in<'a>(
+    repo: &dyn Repository,
+    months: impl Iterator<Item = &'a MonthScalar>,
+) -> sqlx::Result<Vec<domain::Assignment>> {
+    let bookings = bookings_in(repo, months).await?;
+    Ok(bookings.into_iter().map(|b| b.assignment).collect())
+}
+
+/// Splits `resources` into those with a confirmed entry in `assigned` and
+/// those that are only pencilled in.
+fn split_by_status(
+    resources: &[Resource],
+    assigned: impl Iterator<Item = (Uuid, domain::AssignmentStatus)>,
+) -> (Vec<Resource>, Vec<Resource>) {
+    let confirmed: HashSet<Uuid> = assigned
+        .filter(|(_, status)| *status == domain::AssignmentStatus::Confirmed)
+        .map(|(id, _)| id)
+        .collect();
+    resources
+        .iter()
+        .cloned()
+        .partition(|r| confirmed.contains(&r.id))
+}
+
+/// Converts a cell's rows for the schema. Rows are grouped under the requested
+/// months or weeks, which are valid, so none are expected to be dropped.
+fn convert<D: Clone, T: TryFrom<D>>(assigned: &[D]) -> Vec<T> {
+    assigned
+        .iter()
+        .cloned()
+        .filter_map(|a| T::try_from(a).ok())
+        .collect()
+}
+
+/// Drops repeated ids, keeping the first occurrence of each.
+fn distinct(ids: impl Iterator<Item = Uuid>) -> Vec<Uuid> {
+    let mut seen = HashSet::new();
+    ids.filter(|id| seen.insert(*id)).collect()
+}
+
+/// Sums the entries under `id` across `periods`.
+fn allocation<A>(by_period: &ByPeriod<A>, id: Uuid, periods: &[(i32, i32)]) -> usize {
+    periods
+        .iter()
+        .filter_map(|&(year, period)| by_period.get(&(id, year, period)))
+        .map(Vec::len)
+        .sum()
+}
+
+/// A project or resource heading a matrix row.
+trait RowHeader: Sized {
+    fn id(&self) -> Uuid;
+    fn name(&self) -> &str;
+    /// Loads the rows matching `filter`, in its order.
+    fn find<'a>(
+        repo: &'a dyn Repository,
+        filter: &'a RowFilter,
+    ) -> impl Future<Output = sqlx::Result<Vec<Self>>> + Send + 'a;
+    /// Counts the rows matching `filter`, ignoring its `after` and `limit`.
+    fn count<'a>(
+        repo: &'a dyn Repository,
+        filter: &'a RowFilter,
+    ) -> impl Future<Output = sqlx::Result<usize>> + Send + 'a;
+}
+
+impl RowHeader for domain::Project {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn find<'a>(
+        repo: &'a dyn Repository,
+        filter: &'a RowFilter,
+    ) -> impl Future<Output = sqlx::Result<Vec<Self>>> + Send + 'a {
+        repo.find_projects(filter)
+    }
+    fn count<'a>(
+        repo: &'a dyn Repository,
+        filter: &'a RowFilter,
+    ) -> impl Future<Output = sqlx::Result<usize>> + Send + 'a {
+        repo.count_projects(filter)
+    }
+}
+
+impl RowHeader for domain::Resource {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn find<'a>(
+        repo: &'a dyn Repository,
+        filter: &'a RowFilter,
+    ) -> impl Future<Output = sqlx::Result<Vec<Self>>> + Send + 'a {
+        repo.find_resources(filter)
+    }
+    fn count<'a>(
+        repo: &'a dyn Repository,
+        filter: &'a RowFilter,
+    ) -> impl Future<Output = sqlx::Result<usize>> + Send + 'a {
+        repo.count_resources(filter)
+    }
+}
+
+/// Loads the rows on `page` of a matrix with `filter` and `sort`, where a
+/// row's allocation is its entries over `periods` in `by_period`. The
+/// repository filters the rows, and also orders and limits them unless the
+/// sort or filter depends on allocation.
+async fn page_rows<T: RowHeader, A: Sync>(
+    repo: &dyn Repository,
+    top_level: bool,
+    by_period: &ByPeriod<A>,
+    periods: &[(i32, i32)],
+    filter: &MatrixRowFilter,
+    sort: &MatrixSort,
+    page: RowPage,
+) -> sqlx::Result<(Vec<T>, Paging)> {
+    let mut rows_filter = RowFilter {
+        ids: filter.ids.clone(),
+        search: filter.search.clone(),
+        statuses: filter
+            .statuses
+            .as_ref()
+            .map(|s| s.iter().copied().map(Into::into).collect()),
+        top_level,
+        descending: sort.descending,
+        ..Default::default()
+    };
+    let (total_count, mut rows) = if sort.by == MatrixSortField::Name && !filter.assigned_only {
+        let total_count = T::count(repo, &rows_filter).await?;
+        rows_filter.after = page.after.as_ref().map(RowCursor::name_key);
+        // One more than the page holds tells whether another page follows
+        rows_filter.limit = page.first.map(|f| f.saturating_add(1));
+        let rows: Vec<_> = T::find(repo, &rows_filter)
+            .await?
+            .into_iter()
+            .map(|r| (RowCursor::new(sort, 0, r.name(), r.id()), r))
+            .collect();
+        (total_count, rows)
+    } else {
+        let mut rows: Vec<_> = T::find(repo, &rows_filter)
+            .await?
+            .into_iter()
+            .map(|r| (allocation(by_period, r.id(), periods), r))
+            .filter(|(total, _)| !filter.assigned_only || *total > 0)
+            .map(|(total, r)| (RowCursor::new(sort, total, r.name(), r.id()), r))
+            .collect();
+        rows.sort_by(|(a, _), (b, _)| a.cmp_rows(b));
+        let total_count = rows.len();
+        if let Some(after) = &page.after {
+            rows.retain(|(cursor, _)| cursor.cmp_rows(after).is_gt());
+        }
+        (total_count, rows)
+    };
+    let has_next_page = page.first.is_some_and(|f| rows.len() > f);
+    rows.truncate(page.first.unwrap_or(usize::MAX));
+    let (cursors, rows) = rows.into_iter().unzip();
+    Ok((
+        rows,
+        Paging {
+            cursors,
+            total_count,
+            has_previous_page: page.after.is_some(),
+            has_next_page,
+        },
+    ))
+}
+
+/// Number of distinct `other` ids under `id` in `month`, or 0 without a month.
+fn edge_count(
+    by_month: &ByMonth,
+    id: Uuid,
+    month: Option<&MonthScalar>,
+    other: fn(&domain::Assignment) -> Uuid,
+) -> usize {
+    month
+        .and_then(|m| by_month.get(&(id, m.year, i32::from(m.month))))
+        .map_or(0, |assigned| {
+            assigned.iter().map(other).collect::<HashSet<_>>().len()
+        })
+}
+
+/// Maps every project to the top-level project (programme) above it.
+fn programme_ids(projects: &[domain::Project]) -> HashMap<Uuid, Uuid> {
+    let parents: HashMap<Uuid, Option<Uuid>> =
+        projects.iter().map(|p| (p.id, p.parent_id)).collect();
+    projects
+        .iter()
+        .map(|p| {
+            let mut root = p.id;
+            // Bounded so that a cycle can't loop forever
+            for _ in 0..projects.len() {
+                match parents.get(&root).copied().flatten() {
+                    Some(parent) => root = parent,
+                    None => break,
+                }
+            }
+            (p.id, root)
+        })
+        .collect()
+}
+
+/// Builds one row per selected project on `page`, with one cell per entry in `months`.
+pub async fn project_month_matrix(
+    repo: &dyn Repository,
+    months: Vec<MonthScalar>,
+    filter: &MatrixRowFilter,
+    sort: &MatrixSort,
+    page: RowPage,
+) -> sqlx::Result<ProjectMonthMatrix> {
+    project_rows(repo, false, &HashMap::new(), months, filter, sort, page).await
+}
+
+/// Like [`project_month_matrix`], but with one row per top-level project whose
+/// cells merge the resources of every project beneath it.
+pub async fn programme_month_matrix(
+    repo: &dyn Repository,
+    months: Vec<MonthScalar>,
+    filter: &MatrixRowFilter,
+    sort: &MatrixSort,
+    page: RowPage,
+) -> sqlx::Result<ProjectMonthMatrix> {
+    let programmes = programme_ids(&repo.list_projects().await?);
+    project_rows(repo, true, &programmes, months, filter, sort, page).await
+}
+
+/// Builds project rows, of top-level projects only if `top_level`, counting each
+/// assignment under `roll_up[project]`, or under its own project if unmapped.
+async fn project_rows(
+    repo: &dyn Repository,
+    top_level: bool,
+    roll_up: &HashMap<Uuid, Uuid>,
+    months: Vec<MonthScalar>,
+    filter: &MatrixRowFilter,
+    sort: &MatrixSort,
+    page: RowPage,
+) -> sqlx::Result<ProjectMonthMatrix> {
+    let (prev, next) = edges(&months);
+
+    // Preload all assignments for requested months and the edges either side
+    let mut by_project_month: ByMonth = HashMap::new();
+    for a in assignments_in(repo, months.iter().chain(&prev).chain(&next)).await? {
+        let row = roll_up.get(&a.project_id).copied().unwrap_or(a.project_id);
+        by_project_month
+            .entry((row, a.year, a.month))
+            .or_default()
+            .push(a);
+    }
+    let keys = month_keys(&months);
+    let (projects, page) = page_rows::<domain::Project, _>(
+        repo,
+        top_level,
+        &by_project_month,
+        &keys,
+        filter,
+        sort,
+        page,
+    )
+    .await?;
+
+    // Build rows: projects on the page; cells length equals months length; empty cells have []
+    let mut rows_out: Vec<ProjectMonthMatrixRow> = Vec::new();
+    for p in projects {
+        let mut cells: Vec<ProjectMonthCell> = Vec::with_capacity(months.len());
+        for m in &months {
+            let key = (p.id, m.year, i32::from(m.month));
+            if let Some(assigned) = by_project_month.get(&key) {
+                let resource_ids = distinct(assigned.iter().map(|a| a.resource_id));
+                let items = repo.resources(&resource_ids).await?;
+                let resources: Vec<Resource> = items.into_iter().map(Into::into).collect();
+                let (confirmed_resources, tentative_resources) = split_by_status(
+                    &resources,
+                    assigned.iter().map(|a| (a.resource_id, a.status)),
+                );
+                let assignments = convert(assigned);
+                cells.push(ProjectMonthCell {
+                    resources,
+                    confirmed_resources,
+                    tentative_resources,
+                    assignments,
+                });
+            } else {
+                cells.push(ProjectMonthCell {
+                    resources: vec![],
+                    confirmed_resources: vec![],
+                    tentative_resources: vec![],
+                    assignments: vec![],
+                });
+            }
+        }
+        rows_out.push(ProjectMonthMatrixRow {
+            prev_count: edge_count(&by_project_month, p.id, prev.as_ref(), |a| a.resource_id),
+            next_count: edge_count(&by_project_month, p.id, next.as_ref(), |a| a.resource_id),
+            project: p.into(),
+            cells,
+        });
+    }
+    Ok(ProjectMonthMatrix {
+        months,
+        rows: page.connect(rows_out),
+    })
+}
+
+/// Builds one row per selected project on `page`, with one cell per entry in
+/// `weeks` listing its week assignments. Monthly assignments are not split
+/// into weeks.
+pub async fn project_week_matrix(
+    repo: &dyn Repository,
+    weeks: Vec<WeekScalar>,
+    filter: &MatrixRowFilter,
+    sort: &MatrixSort,
+    page: RowPage,
+) -> sqlx::Result<ProjectWeekMatrix> {
+    let mut by_project_week: ByPeriod<domain::WeekAssignment> = HashMap::new();
+    if let (Some(from), Some(to)) = (weeks.iter().min(), weeks.iter().max()) {
+        let f = WeekFilter {
+            from: Some(from.0),
+            to: Some(to.0),
+            ..Default::default()
+        };
+        for a in repo.list_week_assignments(&f).await? {
+            by_project_week
+                .entry((a.project_id, a.year, a.week))
+                .or_default()
+                .push(a);
+        }
+    }
+    let keys: Vec<(i32, i32)> = weeks
+        .iter()
+        .map(|w| (w.0.year, i32::from(w.0.week)))
+        .collect();
+    let (projects, page) =
+        page_rows::<domain::Project, _>(repo, false, &by_project_week, &keys, filter, sort, page)
+            .await?;
+
+    let mut rows_out: Vec<ProjectWeekMatrixRow> = Vec::with_capacity(projects.len());
+    for p in projects {
+        let mut cells: Vec<ProjectWeekCell> = Vec::with_capacity(weeks.len());
+        for &(year, week) in &keys {
+            let assigned = by_project_week
+                .get(&(p.id, year, week))
+                .map_or(&[][..], Vec::as_slice);
+            let resource_ids = distinct(assigned.iter().map(|a| a.resource_id));
+            let resources: Vec<Resource> = repo
+                .resources(&resource_ids)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect();
+            let (confirmed_resources, tentative_resources) = split_by_status(
+                &resources,
+                assigned.iter().map(|a| (a.resource_id, a.status)),
+            );
+            cells.push(ProjectWeekCell {
+                resources,
+                confirmed_resources,
+                tentative_resources,
+                assignments: convert(assigned),
+            });
+        }
+        rows_out.push(ProjectWeekMatrixRow {
+            project: p.into(),
+            cells,
+        });
+    }
+    Ok(ProjectWeekMatrix {
+        weeks,
+        rows: page.connect(rows_out),
+    })
+}
+
+/// Builds one row per selected resource on `page`, with one cell per entry in `months`.
+pub async fn resource_month_matrix(
+    repo: &dyn Repository,
+    calendars: &WorkingCalendars,
+    months: Vec<MonthScalar>,
+    filter: &MatrixRowFilter,
+    sort: &MatrixSort,
+    page: RowPage,
+) -> sqlx::Result<ResourceMonthMatrix> {
+    let (prev, next) = edges(&months);
+
+    let mut by_resource_month: ByMonth = HashMap::new();
+    // Effort booked in each resource month, in total and confirmed only
+    let mut effort: HashMap<(Uuid, i32, i32), (i32, i32)> = HashMap::new();
+    for b in bookings_in(repo, months.iter().chain(&prev).chain(&next)).await? {
+        let a = b.assignment;
+        let key = (a.resource_id, a.year, a.month);
+        let booked = effort.entry(key).or_default();
+        booked.0 = booked.0.saturating_add(b.effort);
+        if a.status == domain::AssignmentStatus::Confirmed {
+            booked.1 = booked.1.saturating_add(b.effort);
+        }
+        by_resource_month.entry(key).or_default().push(a);
+    }
+    let keys = month_keys(&months);
+    let (resources, page) = page_rows::<domain::Resource, _>(
+        repo,
+        false,
+        &by_resource_month,
+        &keys,
+        filter,
+        sort,
+        page,
+    )
+    .await?;
+    let resource_ids: Vec<Uuid> = resources.iter().map(|r| r.id).collect();
+    let capacities: HashMap<(Uuid, i32, i32), i32> = repo
+        .capacities_in(&resource_ids, &keys)
+        .await?
+        .into_iter()
+        .map(|c| ((c.resource_id, c.year, c.month), c.percent))
+        .collect();
+    let project_ids = distinct(
+        resource_ids
+            .iter()
+            .flat_map(|&id| keys.iter().map(move |&(year, month)| (id, year, month)))
+            .filter_map(|key| by_resource_month.get(&key))
+            .flatten()
+            .map(|a| a.project_id),
+    );
+    let projects_by_id: HashMap<Uuid, Project> = repo
+        .projects(&project_ids)
+        .await?
+        .into_iter()
+        .map(|p| (p.id, p.into()))
+        .collect();
+
+    let mut rows_out: Vec<ResourceMonthMatrixRow> = Vec::new();
+    for r in resources {
+        let mut cells: Vec<ResourceMonthCell> = Vec::with_capacity(months.len());
+        for m in &months {
+            let key = (r.id, m.year, i32::from(m.month));
+            let capacity = capacities.get(&key).copied().unwrap_or(domain::FULL_MONTH);
+            let working_days = calendars.working_days(r.region.as_deref(), m.year, m.month);
+            let available_days = workdays::person_days(capacity, working_days);
+            if let Some(assigned) = by_resource_month.get(&key) {
+                let projects = distinct(assigned.iter().map(|a| a.project_id))
+                    .iter()
+                    .filter_map(|id| projects_by_id.get(id).cloned())
+                    .collect();
+                let assignments = convert(assigned);
+                let (booked, confirmed) = effort.get(&key).copied().unwrap_or_default();
+                cells.push(ResourceMonthCell {
+                    projects,
+                    assignments,
+                    working_days,
+                    available_days,
+                    planned_days: workdays::person_days(booked, working_days),
+                    capacity,
+                    remaining_capacity: capacity.saturating_sub(booked),
+                    remaining_confirmed_capacity: capacity.saturating_sub(confirmed),
+                });
+            } else {
+                cells.push(ResourceMonthCell {
+                    projects: vec![],
+                    assignments: vec![],
+                    working_days,
+                    available_days,
+                    planned_days: 0.0,
+                    capacity,
+                    remaining_capacity: capacity,
+                    remaining_confirmed_capacity: capacity,
+                });
+            }
+        }
+        rows_out.push(ResourceMonthMatrixRow {
+            prev_count: edge_count(&by_resource_month, r.id, prev.as_ref(), |a| a.project_id),
+            next_count: edge_count(&by_resource_month, r.id, next.as_ref(), |a| a.project_id),
+            resource: r.into(),
+            cells,
+        });
+    }
+    Ok(ResourceMonthMatrix {
+        months,
+        rows: page.connect(rows_out),
+    })
+}
+
+/// Builds one row per team, with one cell per entry in `months` adding up the
+/// [`resource_month_matrix`] cells of every member. Resources without a team
+/// are left out.
+pub async fn team_month_matrix(
+    repo: &dyn Repository,
+    calendars: &WorkingCalendars,
+    months: Vec<MonthScalar>,
+) -> sqlx::Result<TeamMonthMatrix> {
+    let teams = repo.list_teams().await?;
+    let resources = resource_month_matrix(
+        repo,
+        calendars,
+        months.clone(),
+        &MatrixRowFilter::default(),
+        &MatrixSort::default(),
+        RowPage::default(),
+    )
+    .await?;
+    // Members in name order, as the resource matrix returns them
+    let mut members: HashMap<Uuid, Vec<ResourceMonthMatrixRow>> = HashMap::new();
+    for row in resources.rows.edges.into_iter().map(|edge| edge.node) {
+        if let Some(team_id) = row.resource.team_id {
+            members.entry(team_id).or_default().push(row);
+        }
+    }
+
+    let rows_out = teams
+        .into_iter()
+        .map(|t| {
+            let members = members.remove(&t.id).unwrap_or_default();
+            TeamMonthMatrixRow {
+                team: t.into(),
+                cells: (0..months.len()).map(|i| team_cell(&members, i)).collect(),
+            }
+        })
+        .collect();
+    Ok(TeamMonthMatrix {
+        months,
+        rows: rows_out,
+    })
+}
+
+/// Adds up the `i`th cell of each of a team's `members`.
+fn team_cell(members: &[ResourceMonthMatrixRow], i: usize) -> TeamMonthCell {
+    let mut cell = TeamMonthCell::default();
+    let mut project_ids = HashSet::new();
+    for member in members {
+        let c = &member.cells[i];
+        if !c.assignments.is_empty() {
+            cell.resources.push(member.resource.clone());
+        }
+        let projects = c.projects.iter().filter(|p| project_ids.insert(p.id));
+        cell.projects.extend(projects.cloned());
+        cell.assignments.extend(c.assignments.iter().cloned());
+        cell.capacity = cell.capacity.saturating_add(c.capacity);
+        cell.remaining_capacity = cell.remaining_capacity.saturating_add(c.remaining_capacity);
+        cell.remaining_confirmed_capacity = cell
+            .remaining_confirmed_capacity
+            .saturating_add(c.remaining_confirmed_capacity);
+        cell.available_days += c.available_days;
+        cell.planned_days += c.planned_days;
+    }
+    cell.projects.sort_by(|a, b| a.name.cmp(&b.name));
+    (cell.confirmed_resources, cell.tentative_resources) = split_by_status(
+        &cell.resources,
+        cell.assignments
+            .iter()
+            .map(|a| (a.resource_id, a.status.into())),
+    );
+    cell
+}
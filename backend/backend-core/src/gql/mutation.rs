@@ -1,71 +1,776 @@
+use super::guard::{ProjectManagerGuard, RoleGuard, current_user, require_project_manager};
 use super::types::{
-    AssignInput, Assignment, CreateProjectInput, CreateResourceInput, Project, Resource,
-    UnassignInput,
+    AssignInput, AssignWeekInput, Assignment, CostRate, CreateProjectInput, CreateResourceInput,
+    CreateTeamInput, ImportReport, MonthScalar, Project, ProjectDetailsInput, Resource, Role, Team,
+    UnassignInput, UnassignWeekInput, WeekAssignment,
+};
+use crate::{
+    auth::CurrentUser,
+    domain, import,
+    repo::{AssignmentFilter, Repo, WeekFilter},
+    workdays::WorkingCalendars,
 };
-use crate::{domain, repo::Repo};
 use async_graphql::{Context, Object, Result};
+use std::collections::HashMap;
+use uuid::Uuid;
 
 #[derive(Default)]
 pub struct MutationRoot;
 
+/// Records `action` in the audit trail on behalf of `user`.
+async fn audit(repo: &Repo, user: &CurrentUser, action: &str, detail: String) -> Result<()> {
+    let entry = domain::AuditEntry {
+        user_id: user.id.clone(),
+        action: action.to_string(),
+        detail,
+    };
+    repo.record_audit(&entry).await?;
+    Ok(())
+}
+
+/// Treats empty or whitespace-only text as absent.
+fn non_blank(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+/// Whether `value` is a `#rrggbb` colour.
+fn is_hex_colour(value: &str) -> bool {
+    value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
 #[Object]
 impl MutationRoot {
-    #[graphql(name = "createProject")]
+    /// Creates a project managed by the caller.
+    #[graphql(
+        name = "createProject",
+        guard = "RoleGuard::new(domain::Role::Planner)"
+    )]
     async fn create_project(
         &self,
         ctx: &Context<'_>,
         input: CreateProjectInput,
     ) -> Result<Project> {
+        let user = current_user(ctx)?;
         let repo = ctx.data_unchecked::<Repo>();
         let p = repo.create_project(&input.name).await?;
+        repo.add_project_manager(p.id, &user.id).await?;
+        audit(repo, user, "createProject", p.id.to_string()).await?;
         Ok(p.into())
     }
 
-    #[graphql(name = "deleteProject")]
+    #[graphql(name = "deleteProject", guard = "RoleGuard::new(domain::Role::Admin)")]
     async fn delete_project(&self, ctx: &Context<'_>, id: uuid::Uuid) -> Result<bool> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let deleted = repo.delete_project(id).await? > 0;
+        if deleted {
+            audit(repo, user, "deleteProject", id.to_string()).await?;
+        }
+        Ok(deleted)
+    }
+
+    /// Moves a project under `parentId`, or back to the top level without one.
+    /// The caller must manage the new parent as well as the project.
+    #[graphql(
+        name = "setProjectParent",
+        guard = "ProjectManagerGuard::new(project_id)"
+    )]
+    async fn set_project_parent(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "projectId")] project_id: Uuid,
+        #[graphql(name = "parentId")] parent_id: Option<Uuid>,
+    ) -> Result<Project> {
+        let user = current_user(ctx)?;
+        if let Some(parent_id) = parent_id {
+            require_project_manager(ctx, &[parent_id]).await?;
+        }
         let repo = ctx.data_unchecked::<Repo>();
-        Ok(repo.delete_project(id).await? > 0)
+        let projects = repo.list_projects().await?;
+        let parents: HashMap<Uuid, Option<Uuid>> =
+            projects.iter().map(|p| (p.id, p.parent_id)).collect();
+        if let Some(parent_id) = parent_id {
+            // Walk up from the new parent; reaching the project would close a cycle
+            let mut ancestor = Some(parent_id);
+            // Bounded so that a cycle already stored can't loop forever
+            for _ in 0..=projects.len() {
+                let Some(id) = ancestor else {
+                    break;
+                };
+                if id == project_id {
+                    return Err("A project cannot be nested under itself".into());
+                }
+                ancestor = *parents
+                    .get(&id)
+                    .ok_or_else(|| async_graphql::Error::new("Parent project not found"))?;
+            }
+            if ancestor.is_some() {
+                return Err("Parent project is nested under itself".into());
+            }
+        }
+        if repo.set_project_parent(project_id, parent_id).await? == 0 {
+            return Err("Project not found".into());
+        }
+        let detail = parent_id.map_or_else(
+            || project_id.to_string(),
+            |parent_id| format!("{project_id}:{parent_id}"),
+        );
+        audit(repo, user, "setProjectParent", detail).await?;
+        let mut project = projects
+            .into_iter()
+            .find(|p| p.id == project_id)
+            .ok_or_else(|| async_graphql::Error::new("Project not found"))?;
+        project.parent_id = parent_id;
+        Ok(project.into())
     }
 
-    #[graphql(name = "createResource")]
+    /// Sets the project's budget, or clears it without one.
+    #[graphql(
+        name = "setProjectBudget",
+        guard = "ProjectManagerGuard::new(project_id)"
+    )]
+    async fn set_project_budget(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "projectId")] project_id: Uuid,
+        budget: Option<i64>,
+    ) -> Result<Project> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        if budget.is_some_and(|b| b < 0) {
+            return Err("Budget cannot be negative".into());
+        }
+        if repo.set_project_budget(project_id, budget).await? == 0 {
+            return Err("Project not found".into());
+        }
+        let detail = budget.map_or_else(
+            || project_id.to_string(),
+            |budget| format!("{project_id}:{budget}"),
+        );
+        audit(repo, user, "setProjectBudget", detail).await?;
+        let p = repo
+            .projects(&[project_id])
+            .await?
+            .pop()
+            .ok_or_else(|| async_graphql::Error::new("Project not found"))?;
+        Ok(p.into())
+    }
+
+    /// Replaces the project's status, colour and description.
+    #[graphql(
+        name = "setProjectDetails",
+        guard = "ProjectManagerGuard::new(project_id)"
+    )]
+    async fn set_project_details(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "projectId")] project_id: Uuid,
+        input: ProjectDetailsInput,
+    ) -> Result<Project> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let colour = non_blank(input.colour).map(|c| c.trim().to_lowercase());
+        if colour.as_deref().is_some_and(|c| !is_hex_colour(c)) {
+            return Err("Colour must be a hex code like #1f77b4".into());
+        }
+        let description = non_blank(input.description);
+        let status = domain::ProjectStatus::from(input.status);
+        if repo
+            .set_project_details(
+                project_id,
+                status,
+                colour.as_deref(),
+                description.as_deref(),
+            )
+            .await?
+            == 0
+        {
+            return Err("Project not found".into());
+        }
+        audit(
+            repo,
+            user,
+            "setProjectDetails",
+            format!("{project_id}:{status}"),
+        )
+        .await?;
+        let p = repo
+            .projects(&[project_id])
+            .await?
+            .pop()
+            .ok_or_else(|| async_graphql::Error::new("Project not found"))?;
+        Ok(p.into())
+    }
+
+    #[graphql(
+        name = "createResource",
+        guard = "RoleGuard::new(domain::Role::Planner)"
+    )]
     async fn create_resource(
         &self,
         ctx: &Context<'_>,
         input: CreateResourceInput,
     ) -> Result<Resource> {
+        let user = current_user(ctx)?;
         let repo = ctx.data_unchecked::<Repo>();
-        let r = repo.create_resource(&input.name).await?;
+        let r = domain::Resource {
+            id: Uuid::new_v4(),
+            name: input.name,
+            team_id: None,
+            kind: input.kind.into(),
+            region: None,
+        };
+        repo.upsert_resource(&r).await?;
+        audit(repo, user, "createResource", r.id.to_string()).await?;
         Ok(r.into())
     }
 
-    #[graphql(name = "deleteResource")]
+    /// Moves every monthly and weekly assignment of a placeholder to a real
+    /// person in one transaction. Where the person is already booked on the same
+    /// project and period, their own assignment is kept. The placeholder itself is left in
+    /// place, with no assignments.
+    #[graphql(
+        name = "replacePlaceholder",
+        guard = "RoleGuard::new(domain::Role::Planner)"
+    )]
+    async fn replace_placeholder(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "placeholderId")] placeholder_id: Uuid,
+        #[graphql(name = "resourceId")] resource_id: Uuid,
+    ) -> Result<Resource> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let found = repo.resources(&[placeholder_id, resource_id]).await?;
+        let find = |id| found.iter().find(|r| r.id == id);
+        match find(placeholder_id) {
+            Some(r) if r.kind == domain::ResourceKind::Placeholder => {}
+            Some(_) => return Err("Only placeholders can be replaced".into()),
+            None => return Err("Placeholder not found".into()),
+        }
+        let person = match find(resource_id) {
+            Some(r) if r.kind == domain::ResourceKind::Person => r.clone(),
+            Some(_) => return Err("A placeholder can only be replaced by a person".into()),
+            None => return Err("Resource not found".into()),
+        };
+
+        // Moving touches every project the placeholder is booked on
+        let filter = AssignmentFilter {
+            resource_id: Some(placeholder_id),
+            ..Default::default()
+        };
+        let weeks = WeekFilter {
+            resource_id: Some(placeholder_id),
+            ..Default::default()
+        };
+        let mut project_ids: Vec<Uuid> = repo
+            .list_assignments(&filter)
+            .await?
+            .into_iter()
+            .map(|a| a.project_id)
+            .chain(
+                repo.list_week_assignments(&weeks)
+                    .await?
+                    .into_iter()
+                    .map(|a| a.project_id),
+            )
+            .collect();
+        project_ids.sort_unstable();
+        project_ids.dedup();
+        require_project_manager(ctx, &project_ids).await?;
+
+        let moved = repo.move_assignments(placeholder_id, resource_id).await?;
+        audit(
+            repo,
+            user,
+            "replacePlaceholder",
+            format!("{placeholder_id}:{resource_id}:{moved}"),
+        )
+        .await?;
+        Ok(person.into())
+    }
+
+    #[graphql(name = "deleteResource", guard = "RoleGuard::new(domain::Role::Admin)")]
     async fn delete_resource(&self, ctx: &Context<'_>, id: uuid::Uuid) -> Result<bool> {
+        let user = current_user(ctx)?;
         let repo = ctx.data_unchecked::<Repo>();
-        Ok(repo.delete_resource(id).await? > 0)
+        let deleted = repo.delete_resource(id).await? > 0;
+        if deleted {
+            audit(repo, user, "deleteResource", id.to_string()).await?;
+        }
+        Ok(deleted)
+    }
+
+    #[graphql(name = "createTeam", guard = "RoleGuard::new(domain::Role::Planner)")]
+    async fn create_team(&self, ctx: &Context<'_>, input: CreateTeamInput) -> Result<Team> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let t = repo.create_team(&input.name).await?;
+        audit(repo, user, "createTeam", t.id.to_string()).await?;
+        Ok(t.into())
+    }
+
+    #[graphql(name = "renameTeam", guard = "RoleGuard::new(domain::Role::Planner)")]
+    async fn rename_team(&self, ctx: &Context<'_>, id: Uuid, name: String) -> Result<Team> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        if repo.teams(&[id]).await?.is_empty() {
+            return Err("Team not found".into());
+        }
+        let t = domain::Team { id, name };
+        repo.upsert_team(&t).await?;
+        audit(repo, user, "renameTeam", id.to_string()).await?;
+        Ok(t.into())
+    }
+
+    /// Deletes a team; its members stay, without a team.
+    #[graphql(name = "deleteTeam", guard = "RoleGuard::new(domain::Role::Admin)")]
+    async fn delete_team(&self, ctx: &Context<'_>, id: Uuid) -> Result<bool> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let deleted = repo.delete_team(id).await? > 0;
+        if deleted {
+            audit(repo, user, "deleteTeam", id.to_string()).await?;
+        }
+        Ok(deleted)
+    }
+
+    /// Moves a resource into `teamId`, or out of its team without one.
+    #[graphql(
+        name = "setResourceTeam",
+        guard = "RoleGuard::new(domain::Role::Planner)"
+    )]
+    async fn set_resource_team(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "resourceId")] resource_id: Uuid,
+        #[graphql(name = "teamId")] team_id: Option<Uuid>,
+    ) -> Result<Resource> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        if let Some(team_id) = team_id
+            && repo.teams(&[team_id]).await?.is_empty()
+        {
+            return Err("Team not found".into());
+        }
+        if repo.set_resource_team(resource_id, team_id).await? == 0 {
+            return Err("Resource not found".into());
+        }
+        let detail = team_id.map_or_else(
+            || resource_id.to_string(),
+            |team_id| format!("{resource_id}:{team_id}"),
+        );
+        audit(repo, user, "setResourceTeam", detail).await?;
+        let r = repo
+            .resources(&[resource_id])
+            .await?
+            .pop()
+            .ok_or_else(|| async_graphql::Error::new("Resource not found"))?;
+        Ok(r.into())
+    }
+
+    /// Sets the region whose working-day calendar applies to a resource, or
+    /// clears it without one.
+    #[graphql(
+        name = "setResourceRegion",
+        guard = "RoleGuard::new(domain::Role::Planner)"
+    )]
+    async fn set_resource_region(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "resourceId")] resource_id: Uuid,
+        region: Option<String>,
+    ) -> Result<Resource> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let calendars = ctx.data_unchecked::<WorkingCalendars>();
+        if let Some(region) = &region
+            && !calendars.contains(region)
+        {
+            return Err(format!("Unknown region '{region}'").into());
+        }
+        if repo
+            .set_resource_region(resource_id, region.as_deref())
+            .await?
+            == 0
+        {
+            return Err("Resource not found".into());
+        }
+        let detail = region.as_ref().map_or_else(
+            || resource_id.to_string(),
+            |region| format!("{resource_id}:{region}"),
+        );
+        audit(repo, user, "setResourceRegion", detail).await?;
+        let r = repo
+            .resources(&[resource_id])
+            .await?
+            .pop()
+            .ok_or_else(|| async_graphql::Error::new("Resource not found"))?;
+        Ok(r.into())
     }
 
-    #[graphql(name = "assign")]
+    /// Sets the resource's monthly cost from `from` until its next rate,
+    /// replacing any rate starting in the same month.
+    #[graphql(name = "setCostRate", guard = "RoleGuard::new(domain::Role::Planner)")]
+    async fn set_cost_rate(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "resourceId")] resource_id: Uuid,
+        from: MonthScalar,
+        rate: i64,
+    ) -> Result<CostRate> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        if rate < 0 {
+            return Err("Rate cannot be negative".into());
+        }
+        if repo.resources(&[resource_id]).await?.is_empty() {
+            return Err("Resource not found".into());
+        }
+        let c = domain::CostRate {
+            resource_id,
+            year: from.year,
+            month: i32::from(from.month),
+            rate,
+        };
+        repo.set_cost_rate(&c).await?;
+        audit(
+            repo,
+            user,
+            "setCostRate",
+            format!("{resource_id}:{from}:{rate}"),
+        )
+        .await?;
+        Ok(c.into())
+    }
+
+    #[graphql(
+        name = "deleteCostRate",
+        guard = "RoleGuard::new(domain::Role::Planner)"
+    )]
+    async fn delete_cost_rate(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "resourceId")] resource_id: Uuid,
+        from: MonthScalar,
+    ) -> Result<bool> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let deleted = repo
+            .delete_cost_rate(resource_id, from.year, i32::from(from.month))
+            .await?
+            > 0;
+        if deleted {
+            audit(
+                repo,
+                user,
+                "deleteCostRate",
+                format!("{resource_id}:{from}"),
+            )
+            .await?;
+        }
+        Ok(deleted)
+    }
+
+    /// Sets the resource's capacity, as a percentage of a full month, for every
+    /// month from `from` to `to` inclusive. Omitting `capacity` returns those
+    /// months to full time.
+    #[graphql(name = "setCapacity", guard = "RoleGuard::new(domain::Role::Planner)")]
+    async fn set_capacity(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "resourceId")] resource_id: Uuid,
+        from: MonthScalar,
+        to: MonthScalar,
+        capacity: Option<i32>,
+    ) -> Result<Resource> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        if capacity.is_some_and(|c| !(0..=domain::FULL_MONTH).contains(&c)) {
+            return Err(format!("Capacity must be between 0 and {}", domain::FULL_MONTH).into());
+        }
+        let months = MonthScalar::range_inclusive(from, to);
+        if months.is_empty() {
+            return Err("`to` must not precede `from`".into());
+        }
+        if months.len() > domain::MAX_MONTHS {
+            return Err(
+                format!("Cannot set more than {} months at once", domain::MAX_MONTHS).into(),
+            );
+        }
+        let resource = repo
+            .resources(&[resource_id])
+            .await?
+            .pop()
+            .ok_or_else(|| async_graphql::Error::new("Resource not found"))?;
+        let keys: Vec<(i32, i32)> = months
+            .iter()
+            .map(|m| (m.year, i32::from(m.month)))
+            .collect();
+        repo.set_capacities(resource_id, &keys, capacity).await?;
+        let value = capacity.map_or_else(|| "default".to_string(), |c| c.to_string());
+        audit(
+            repo,
+            user,
+            "setCapacity",
+            format!("{resource_id}:{from}..{to}:{value}"),
+        )
+        .await?;
+        Ok(resource.into())
+    }
+
+    /// Assigns a resource to a project for a month. Assigning again replaces the
+    /// role, note and status.
+    #[graphql(name = "assign", guard = "ProjectManagerGuard::new(input.project_id)")]
     async fn assign(&self, ctx: &Context<'_>, input: AssignInput) -> Result<Assignment> {
+        let user = current_user(ctx)?;
         let repo = ctx.data_unchecked::<Repo>();
         let d = domain::Assignment {
             resource_id: input.resource_id,
             project_id: input.project_id,
             year: input.month.year,
             month: i32::from(input.month.month),
+            role: non_blank(input.role),
+            note: non_blank(input.note),
+            status: input.status.into(),
         };
         repo.assign(&d).await?;
-        Ok(d.into())
+        let out = Assignment::try_from(d)?;
+        audit(repo, user, "assign", out.id.clone()).await?;
+        Ok(out)
     }
 
-    #[graphql(name = "unassign")]
+    #[graphql(
+        name = "unassign",
+        guard = "ProjectManagerGuard::new(input.project_id)"
+    )]
     async fn unassign(&self, ctx: &Context<'_>, input: UnassignInput) -> Result<bool> {
+        let user = current_user(ctx)?;
         let repo = ctx.data_unchecked::<Repo>();
         let d = domain::Assignment {
             resource_id: input.resource_id,
             project_id: input.project_id,
             year: input.month.year,
             month: i32::from(input.month.month),
+            role: None,
+            note: None,
+            status: domain::AssignmentStatus::default(),
+        };
+        let removed = repo.unassign(&d).await? > 0;
+        if removed {
+            let id = Assignment::try_from(d)?.id;
+            audit(repo, user, "unassign", id).await?;
+        }
+        Ok(removed)
+    }
+
+    /// Assigns a resource to a project for an ISO week. Assigning again replaces
+    /// the role, note and status.
+    #[graphql(
+        name = "assignWeek",
+        guard = "ProjectManagerGuard::new(input.project_id)"
+    )]
+    async fn assign_week(
+        &self,
+        ctx: &Context<'_>,
+        input: AssignWeekInput,
+    ) -> Result<WeekAssignment> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let d = domain::WeekAssignment {
+            resource_id: input.resource_id,
+            project_id: input.project_id,
+            year: input.week.0.year,
+            week: i32::from(input.week.0.week),
+            role: non_blank(input.role),
+            note: non_blank(input.note),
+            status: input.status.into(),
+        };
+        repo.assign_week(&d).await?;
+        let out = WeekAssignment::try_from(d)?;
+        audit(repo, user, "assignWeek", out.id.clone()).await?;
+        Ok(out)
+    }
+
+    #[graphql(
+        name = "unassignWeek",
+        guard = "ProjectManagerGuard::new(input.project_id)"
+    )]
+    async fn unassign_week(&self, ctx: &Context<'_>, input: UnassignWeekInput) -> Result<bool> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let d = domain::WeekAssignment {
+            resource_id: input.resource_id,
+            project_id: input.project_id,
+            year: input.week.0.year,
+            week: i32::from(input.week.0.week),
+            role: None,
+            note: None,
+            status: domain::AssignmentStatus::default(),
+        };
+        let removed = repo.unassign_week(&d).await? > 0;
+        if removed {
+            let id = WeekAssignment::try_from(d)?.id;
+            audit(repo, user, "unassignWeek", id).await?;
+        }
+        Ok(removed)
+    }
+
+    /// Confirms the project's tentative assignments in `months`, including weeks
+    /// rolling up into them. Returns how many were confirmed.
+    #[graphql(
+        name = "confirmAssignments",
+        guard = "ProjectManagerGuard::new(project_id)"
+    )]
+    async fn confirm_assignments(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "projectId")] project_id: Uuid,
+        months: Vec<MonthScalar>,
+    ) -> Result<u64> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let keys: Vec<(i32, i32)> = months
+            .iter()
+            .map(|m| (m.year, i32::from(m.month)))
+            .collect();
+        let confirmed = repo.confirm_assignments(project_id, &keys).await?;
+        if confirmed > 0 {
+            let months: Vec<String> = months.iter().map(ToString::to_string).collect();
+            audit(
+                repo,
+                user,
+                "confirmAssignments",
+                format!("{project_id}:{}", months.join(",")),
+            )
+            .await?;
+        }
+        Ok(confirmed)
+    }
+
+    #[graphql(name = "setUserRole", guard = "RoleGuard::new(domain::Role::Admin)")]
+    async fn set_user_role(&self, ctx: &Context<'_>, user_id: String, role: Role) -> Result<Role> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        repo.set_user_role(&user_id, role.into()).await?;
+        audit(
+            repo,
+            user,
+            "setUserRole",
+            format!("{user_id}:{}", domain::Role::from(role)),
+        )
+        .await?;
+        Ok(role)
+    }
+
+    #[graphql(
+        name = "addProjectManager",
+        guard = "RoleGuard::new(domain::Role::Admin)"
+    )]
+    async fn add_project_manager(
+        &self,
+        ctx: &Context<'_>,
+        project_id: Uuid,
+        user_id: String,
+    ) -> Result<bool> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        repo.add_project_manager(project_id, &user_id).await?;
+        audit(
+            repo,
+            user,
+            "addProjectManager",
+            format!("{project_id}:{user_id}"),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    #[graphql(
+        name = "removeProjectManager",
+        guard = "RoleGuard::new(domain::Role::Admin)"
+    )]
+    async fn remove_project_manager(
+        &self,
+        ctx: &Context<'_>,
+        project_id: Uuid,
+        user_id: String,
+    ) -> Result<bool> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let removed = repo.remove_project_manager(project_id, &user_id).await? > 0;
+        if removed {
+            audit(
+                repo,
+                user,
+                "removeProjectManager",
+                format!("{project_id}:{user_id}"),
+            )
+            .await?;
+        }
+        Ok(removed)
+    }
+
+    /// Imports `project,resource,month` CSV rows, reporting rows that were
+    /// skipped or rejected. Non-admins may only import into projects they manage.
+    #[graphql(
+        name = "importAssignments",
+        guard = "RoleGuard::new(domain::Role::Planner)"
+    )]
+    async fn import_assignments(
+        &self,
+        ctx: &Context<'_>,
+        csv: String,
+        #[graphql(name = "dryRun", default)] dry_run: bool,
+        #[graphql(name = "createMissing", default)] create_missing: bool,
+    ) -> Result<ImportReport> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let is_admin = repo.user_role(&user.id).await? == Some(domain::Role::Admin);
+        let options = import::ImportOptions {
+            create_missing,
+            dry_run,
+            manager: (!is_admin).then(|| user.id.clone()),
+        };
+        let report = import::import_csv(repo.as_ref(), &csv, &options).await?;
+        if !dry_run {
+            let detail = format!(
+                "{} assignments, {} projects, {} resources",
+                report.imported,
+                report.created_projects.len(),
+                report.created_resources.len()
+            );
+            audit(repo, user, "importAssignments", detail).await?;
+        }
+        Ok(ImportReport::new(report, dry_run))
+    }
+
+    /// Imports `project,resource,month,effort` CSV rows of actual effort,
+    /// replacing any effort already recorded for the same month. Non-admins may
+    /// only import into projects they manage.
+    #[graphql(
+        name = "importActuals",
+        guard = "RoleGuard::new(domain::Role::Planner)"
+    )]
+    async fn import_actuals(
+        &self,
+        ctx: &Context<'_>,
+        csv: String,
+        #[graphql(name = "dryRun", default)] dry_run: bool,
+    ) -> Result<ImportReport> {
+        let user = current_user(ctx)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let is_admin = repo.user_role(&user.id).await? == Some(domain::Role::Admin);
+        let options = import::ImportOptions {
+            dry_run,
+            manager: (!is_admin).then(|| user.id.clone()),
+            ..Default::default()
         };
-        Ok(repo.unassign(&d).await? > 0)
+        let report = import::import_actuals_csv(repo.as_ref(), &csv, &options).await?;
+        if !dry_run {
+            let detail = format!("{} actuals", report.imported);
+            audit(repo, user, "importActuals", detail).await?;
+        }
+        Ok(ImportReport::new(report, dry_run))
     }
 }
@@ -1,22 +1,56 @@
+use super::matrix::{self, RowPage};
 use super::types::{
-    Assignment, MonthScalar, Project, ProjectMonthCell, ProjectMonthMatrix, ProjectMonthMatrixRow,
-    Resource, ResourceMonthCell, ResourceMonthMatrix, ResourceMonthMatrixRow,
+    Assignment, MatrixRowFilter, MatrixSort, MonthRange, MonthScalar, PlanVsActual, Project,
+    ProjectCost, ProjectMonthMatrix, ProjectStatus, ProjectWeekMatrix, Resource,
+    ResourceMonthMatrix, RowCursor, Team, TeamMonthMatrix, Utilisation, UtilisationGroupBy,
+    WeekScalar,
 };
-use crate::repo::Repo;
-use async_graphql::{Context, Object, Result};
-use std::collections::HashMap;
+use super::{actuals, cost};
+use crate::{repo::Repo, workdays::WorkingCalendars};
+use async_graphql::{Context, Object, Result, connection::query_with};
 use uuid::Uuid;
 
 #[derive(Default)]
 pub struct QueryRoot;
 
+/// Resolves the `months` or `range` argument of a month-based query; exactly
+/// one must be given.
+fn month_window(
+    months: Option<Vec<MonthScalar>>,
+    range: Option<MonthRange>,
+) -> Result<Vec<MonthScalar>> {
+    match (months, range) {
+        (Some(months), None) => Ok(months),
+        (None, Some(range)) => Ok(range.months()),
+        _ => Err("Pass exactly one of `months` and `range`".into()),
+    }
+}
+
+/// Pages matrix rows sorted by `sort`; a cursor only continues the sort it was
+/// issued for.
+fn row_page(sort: &MatrixSort, first: Option<usize>, after: Option<RowCursor>) -> Result<RowPage> {
+    if after.as_ref().is_some_and(|c| !c.fits(sort)) {
+        return Err("Cursor was issued for a different sort".into());
+    }
+    Ok(RowPage { first, after })
+}
+
 #[Object]
 impl QueryRoot {
+    /// All projects, or only those with one of `statuses`.
     #[graphql(name = "projects")]
-    async fn projects(&self, ctx: &Context<'_>) -> Result<Vec<Project>> {
+    async fn projects(
+        &self,
+        ctx: &Context<'_>,
+        statuses: Option<Vec<ProjectStatus>>,
+    ) -> Result<Vec<Project>> {
         let repo = ctx.data_unchecked::<Repo>();
         let out = repo.list_projects().await?;
-        Ok(out.into_iter().map(Into::into).collect())
+        Ok(out
+            .into_iter()
+            .map(Project::from)
+            .filter(|p| statuses.as_ref().is_none_or(|s| s.contains(&p.status)))
+            .collect())
     }
 
     #[graphql(name = "resources")]
@@ -26,13 +60,27 @@ impl QueryRoot {
         Ok(out.into_iter().map(Into::into).collect())
     }
 
+    #[graphql(name = "teams")]
+    async fn teams(&self, ctx: &Context<'_>) -> Result<Vec<Team>> {
+        let repo = ctx.data_unchecked::<Repo>();
+        let out = repo.list_teams().await?;
+        Ok(out.into_iter().map(Into::into).collect())
+    }
+
+    /// Regions with a working-day calendar, in name order.
+    #[graphql(name = "regions")]
+    async fn regions(&self, ctx: &Context<'_>) -> Vec<String> {
+        let calendars = ctx.data_unchecked::<WorkingCalendars>();
+        calendars.regions().map(str::to_string).collect()
+    }
+
     #[graphql(name = "assignments")]
     async fn assignments(&self, ctx: &Context<'_>) -> Result<Vec<Assignment>> {
         let repo = ctx.data_unchecked::<Repo>();
         let out = repo
             .list_assignments(&crate::repo::AssignmentFilter::default())
             .await?;
-        Ok(out.into_iter().map(Into::into).collect())
+        out.into_iter().map(Assignment::try_from).collect()
     }
 
     #[graphql(name = "resource")]
@@ -50,103 +98,172 @@ impl QueryRoot {
     }
 
     #[graphql(name = "projectMonthMatrix")]
+    #[allow(clippy::too_many_arguments)] // one per GraphQL argument
     async fn project_month_matrix(
         &self,
         ctx: &Context<'_>,
-        #[graphql(name = "months")] months: Vec<MonthScalar>,
+        #[graphql(name = "months")] months: Option<Vec<MonthScalar>>,
+        range: Option<MonthRange>,
+        filter: Option<MatrixRowFilter>,
+        sort: Option<MatrixSort>,
+        first: Option<i32>,
+        after: Option<String>,
     ) -> Result<ProjectMonthMatrix> {
+        let months = month_window(months, range)?;
         let repo = ctx.data_unchecked::<Repo>();
-        let projects = repo.list_projects().await?;
-
-        // Preload all assignments for requested months
-        let mut by_project_month: HashMap<(Uuid, i32, i32), Vec<Uuid>> = HashMap::new();
-        for m in &months {
-            let f = crate::repo::AssignmentFilter {
-                year: Some(m.year),
-                month: Some(i32::from(m.month)),
-                ..Default::default()
-            };
-            let rows = repo.list_assignments(&f).await?;
-            for a in rows {
-                by_project_month
-                    .entry((a.project_id, a.year, a.month))
-                    .or_default()
-                    .push(a.resource_id);
-            }
-        }
-
-        // Build rows: all projects; cells length equals months length; empty cells have []
-        let mut rows_out: Vec<ProjectMonthMatrixRow> = Vec::new();
-        for p in projects {
-            let mut cells: Vec<ProjectMonthCell> = Vec::with_capacity(months.len());
-            for m in &months {
-                let key = (p.id, m.year, i32::from(m.month));
-                if let Some(resource_ids) = by_project_month.get(&key) {
-                    let items = repo.resources(resource_ids).await?;
-                    let resources: Vec<Resource> = items.into_iter().map(Into::into).collect();
-                    cells.push(ProjectMonthCell { resources });
-                } else {
-                    cells.push(ProjectMonthCell { resources: vec![] });
-                }
-            }
-            rows_out.push(ProjectMonthMatrixRow {
-                project: p.into(),
-                cells,
-            });
-        }
-        Ok(ProjectMonthMatrix {
-            months,
-            rows: rows_out,
+        let (filter, sort) = (filter.unwrap_or_default(), sort.unwrap_or_default());
+        query_with(after, None, first, None, |after, _, first, _| async move {
+            let page = row_page(&sort, first, after)?;
+            let matrix =
+                matrix::project_month_matrix(repo.as_ref(), months, &filter, &sort, page).await?;
+            Ok::<_, async_graphql::Error>(matrix)
+        })
+        .await
+    }
+
+    /// Like `projectMonthMatrix`, but with one row per top-level project whose
+    /// cells roll up the resources of every project beneath it.
+    #[graphql(name = "programmeMonthMatrix")]
+    #[allow(clippy::too_many_arguments)] // one per GraphQL argument
+    async fn programme_month_matrix(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "months")] months: Option<Vec<MonthScalar>>,
+        range: Option<MonthRange>,
+        filter: Option<MatrixRowFilter>,
+        sort: Option<MatrixSort>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<ProjectMonthMatrix> {
+        let months = month_window(months, range)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let (filter, sort) = (filter.unwrap_or_default(), sort.unwrap_or_default());
+        query_with(after, None, first, None, |after, _, first, _| async move {
+            let page = row_page(&sort, first, after)?;
+            let matrix =
+                matrix::programme_month_matrix(repo.as_ref(), months, &filter, &sort, page).await?;
+            Ok::<_, async_graphql::Error>(matrix)
         })
+        .await
+    }
+
+    /// Like `projectMonthMatrix`, but with one cell per ISO week listing the
+    /// project's week assignments.
+    #[graphql(name = "projectWeekMatrix")]
+    async fn project_week_matrix(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "weeks")] weeks: Vec<WeekScalar>,
+        filter: Option<MatrixRowFilter>,
+        sort: Option<MatrixSort>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<ProjectWeekMatrix> {
+        let repo = ctx.data_unchecked::<Repo>();
+        let (filter, sort) = (filter.unwrap_or_default(), sort.unwrap_or_default());
+        query_with(after, None, first, None, |after, _, first, _| async move {
+            let page = row_page(&sort, first, after)?;
+            let matrix =
+                matrix::project_week_matrix(repo.as_ref(), weeks, &filter, &sort, page).await?;
+            Ok::<_, async_graphql::Error>(matrix)
+        })
+        .await
     }
 
     #[graphql(name = "resourceMonthMatrix")]
+    #[allow(clippy::too_many_arguments)] // one per GraphQL argument
     async fn resource_month_matrix(
         &self,
         ctx: &Context<'_>,
-        #[graphql(name = "months")] months: Vec<MonthScalar>,
+        #[graphql(name = "months")] months: Option<Vec<MonthScalar>>,
+        range: Option<MonthRange>,
+        filter: Option<MatrixRowFilter>,
+        sort: Option<MatrixSort>,
+        first: Option<i32>,
+        after: Option<String>,
     ) -> Result<ResourceMonthMatrix> {
+        let months = month_window(months, range)?;
         let repo = ctx.data_unchecked::<Repo>();
-        let resources = repo.list_resources().await?;
-
-        let mut by_resource_month: HashMap<(Uuid, i32, i32), Vec<Uuid>> = HashMap::new();
-        for m in &months {
-            let f = crate::repo::AssignmentFilter {
-                year: Some(m.year),
-                month: Some(i32::from(m.month)),
-                ..Default::default()
-            };
-            let rows = repo.list_assignments(&f).await?;
-            for a in rows {
-                by_resource_month
-                    .entry((a.resource_id, a.year, a.month))
-                    .or_default()
-                    .push(a.project_id);
-            }
-        }
-
-        let mut rows_out: Vec<ResourceMonthMatrixRow> = Vec::new();
-        for r in resources {
-            let mut cells: Vec<ResourceMonthCell> = Vec::with_capacity(months.len());
-            for m in &months {
-                let key = (r.id, m.year, i32::from(m.month));
-                if let Some(project_ids) = by_resource_month.get(&key) {
-                    let items = repo.projects(project_ids).await?;
-                    let projects: Vec<Project> = items.into_iter().map(Into::into).collect();
-                    cells.push(ResourceMonthCell { projects });
-                } else {
-                    cells.push(ResourceMonthCell { projects: vec![] });
-                }
-            }
-            rows_out.push(ResourceMonthMatrixRow {
-                resource: r.into(),
-                cells,
-            });
-        }
-        Ok(ResourceMonthMatrix {
-            months,
-            rows: rows_out,
+        let (filter, sort) = (filter.unwrap_or_default(), sort.unwrap_or_default());
+        let calendars = ctx.data_unchecked::<WorkingCalendars>();
+        query_with(after, None, first, None, |after, _, first, _| async move {
+            let page = row_page(&sort, first, after)?;
+            let matrix = matrix::resource_month_matrix(
+                repo.as_ref(),
+                calendars,
+                months,
+                &filter,
+                &sort,
+                page,
+            )
+            .await?;
+            Ok::<_, async_graphql::Error>(matrix)
         })
+        .await
+    }
+
+    /// One row per team, with each cell adding up its members' assignments and capacity.
+    #[graphql(name = "teamMonthMatrix")]
+    async fn team_month_matrix(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "months")] months: Option<Vec<MonthScalar>>,
+        range: Option<MonthRange>,
+    ) -> Result<TeamMonthMatrix> {
+        let months = month_window(months, range)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let calendars = ctx.data_unchecked::<WorkingCalendars>();
+        Ok(matrix::team_month_matrix(repo.as_ref(), calendars, months).await?)
+    }
+
+    /// Planned spend on a project in each of `months`, with a burn-down
+    /// against its budget. Null if the project doesn't exist.
+    #[graphql(name = "projectCost")]
+    async fn project_cost(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "projectId")] project_id: Uuid,
+        months: Option<Vec<MonthScalar>>,
+        range: Option<MonthRange>,
+    ) -> Result<Option<ProjectCost>> {
+        let months = month_window(months, range)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        Ok(cost::project_cost(repo.as_ref(), project_id, months).await?)
+    }
+
+    /// Planned against actual effort on a project in each of `months`, per
+    /// resource. Null if the project doesn't exist.
+    #[graphql(name = "planVsActual")]
+    async fn plan_vs_actual(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "projectId")] project_id: Uuid,
+        months: Option<Vec<MonthScalar>>,
+        range: Option<MonthRange>,
+    ) -> Result<Option<PlanVsActual>> {
+        let months = month_window(months, range)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let calendars = ctx.data_unchecked::<WorkingCalendars>();
+        Ok(actuals::plan_vs_actual(repo.as_ref(), calendars, project_id, months).await?)
+    }
+
+    /// Assignment counts over `months`, aggregated in the database.
+    #[graphql(name = "utilisation")]
+    async fn utilisation(
+        &self,
+        ctx: &Context<'_>,
+        months: Option<Vec<MonthScalar>>,
+        range: Option<MonthRange>,
+        #[graphql(name = "groupBy")] group_by: UtilisationGroupBy,
+    ) -> Result<Vec<Utilisation>> {
+        let months = month_window(months, range)?;
+        let repo = ctx.data_unchecked::<Repo>();
+        let months: Vec<(i32, i32)> = months
+            .iter()
+            .map(|m| (m.year, i32::from(m.month)))
+            .collect();
+        let out = repo.utilisation(&months, group_by.into()).await?;
+        out.into_iter().map(Utilisation::try_from).collect()
     }
 }
 
@@ -155,28 +272,124 @@ mod tests {
     use super::*;
     use crate::{
         domain,
-        repo::{AssignmentFilter, Repository},
+        repo::{AssignmentFilter, Repository, RowFilter, WeekFilter},
     };
     use async_graphql::{EmptySubscription, Schema, dataloader::DataLoader};
     use async_trait::async_trait;
-    use std::collections::{HashMap, HashSet};
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
     use std::sync::{Arc, Mutex};
 
     #[derive(Clone, Default)]
     struct MemRepo {
         projects: Arc<Mutex<HashMap<Uuid, domain::Project>>>,
         resources: Arc<Mutex<HashMap<Uuid, domain::Resource>>>,
+        teams: Arc<Mutex<HashMap<Uuid, domain::Team>>>,
         assignments: Arc<Mutex<Vec<domain::Assignment>>>,
+        week_assignments: Arc<Mutex<Vec<domain::WeekAssignment>>>,
+        actuals: Arc<Mutex<Vec<domain::Actual>>>,
+        cost_rates: Arc<Mutex<Vec<domain::CostRate>>>,
+        capacities: Arc<Mutex<Vec<domain::Capacity>>>,
+        audit: Arc<Mutex<Vec<domain::AuditEntry>>>,
+        roles: Arc<Mutex<HashMap<String, domain::Role>>>,
+        managers: Arc<Mutex<HashSet<(Uuid, String)>>>,
+    }
+
+    /// Whether a row with `id` and `name` passes `filter`'s ids and search.
+    fn row_matches(filter: &RowFilter, id: Uuid, name: &str) -> bool {
+        filter.ids.as_ref().is_none_or(|ids| ids.contains(&id))
+            && filter
+                .search
+                .as_ref()
+                .is_none_or(|s| name.to_ascii_lowercase().contains(&s.to_ascii_lowercase()))
+    }
+
+    /// Orders `rows` by name then id and applies `filter`'s `after` and `limit`.
+    fn row_page<T>(mut rows: Vec<T>, filter: &RowFilter, key: fn(&T) -> (String, Uuid)) -> Vec<T> {
+        rows.sort_by_key(key);
+        if filter.descending {
+            rows.reverse();
+        }
+        rows.retain(|r| {
+            filter.after.as_ref().is_none_or(|after| {
+                let ord = key(r).cmp(after);
+                ord == if filter.descending {
+                    std::cmp::Ordering::Less
+                } else {
+                    std::cmp::Ordering::Greater
+                }
+            })
+        });
+        rows.truncate(filter.limit.unwrap_or(usize::MAX));
+        rows
     }
 
     #[async_trait]
     impl Repository for MemRepo {
         async fn list_projects(&self) -> sqlx::Result<Vec<domain::Project>> {
-            Ok(self.projects.lock().unwrap().values().cloned().collect())
+            let mut out: Vec<_> = self.projects.lock().unwrap().values().cloned().collect();
+            out.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(out)
         }
 
         async fn list_resources(&self) -> sqlx::Result<Vec<domain::Resource>> {
-            Ok(self.resources.lock().unwrap().values().cloned().collect())
+            let mut out: Vec<_> = self.resources.lock().unwrap().values().cloned().collect();
+            out.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(out)
+        }
+
+        async fn find_projects(&self, filter: &RowFilter) -> sqlx::Result<Vec<domain::Project>> {
+            let rows: Vec<_> = self
+                .projects
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|p| row_matches(filter, p.id, &p.name))
+                .filter(|p| {
+                    filter
+                        .statuses
+                        .as_ref()
+                        .is_none_or(|s| s.contains(&p.status))
+                })
+                .filter(|p| !filter.top_level || p.parent_id.is_none())
+                .cloned()
+                .collect();
+            Ok(row_page(rows, filter, |p| (p.name.clone(), p.id)))
+        }
+
+        async fn count_projects(&self, filter: &RowFilter) -> sqlx::Result<usize> {
+            let all = RowFilter {
+                after: None,
+                limit: None,
+                ..filter.clone()
+            };
+            Ok(self.find_projects(&all).await?.len())
+        }
+
+        async fn find_resources(&self, filter: &RowFilter) -> sqlx::Result<Vec<domain::Resource>> {
+            let rows: Vec<_> = self
+                .resources
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|r| row_matches(filter, r.id, &r.name))
+                .cloned()
+                .collect();
+            Ok(row_page(rows, filter, |r| (r.name.clone(), r.id)))
+        }
+
+        async fn count_resources(&self, filter: &RowFilter) -> sqlx::Result<usize> {
+            let all = RowFilter {
+                after: None,
+                limit: None,
+                ..filter.clone()
+            };
+            Ok(self.find_resources(&all).await?.len())
+        }
+
+        async fn list_teams(&self) -> sqlx::Result<Vec<domain::Team>> {
+            let mut out: Vec<_> = self.teams.lock().unwrap().values().cloned().collect();
+            out.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(out)
         }
 
         async fn list_assignments(
@@ -184,7 +397,48 @@ mod tests {
             filter: &AssignmentFilter,
         ) -> sqlx::Result<Vec<domain::Assignment>> {
             let rows = self.assignments.lock().unwrap().clone();
-            let out = rows
+            let mut out: Vec<_> = rows
+                .into_iter()
+                .filter(|a| {
+                    (filter.resource_id.is_none_or(|id| a.resource_id == id))
+                        && (filter.project_id.is_none_or(|id| a.project_id == id))
+                        && (filter.year.is_none_or(|y| a.year == y))
+                        && (filter.month.is_none_or(|m| a.month == m))
+                })
+                .collect();
+            out.sort_by_key(|a| (a.year, a.month, a.project_id, a.resource_id));
+            Ok(out)
+        }
+
+        async fn list_week_assignments(
+            &self,
+            filter: &WeekFilter,
+        ) -> sqlx::Result<Vec<domain::WeekAssignment>> {
+            let rows = self.week_assignments.lock().unwrap().clone();
+            let mut out: Vec<_> = rows
+                .into_iter()
+                .filter(|a| {
+                    let week = (a.year, a.week);
+                    (filter.resource_id.is_none_or(|id| a.resource_id == id))
+                        && (filter.project_id.is_none_or(|id| a.project_id == id))
+                        && (filter
+                            .from
+                            .is_none_or(|w| week >= (w.year, i32::from(w.week))))
+                        && (filter
+                            .to
+                            .is_none_or(|w| week <= (w.year, i32::from(w.week))))
+                })
+                .collect();
+            out.sort_by_key(|a| (a.year, a.week, a.project_id, a.resource_id));
+            Ok(out)
+        }
+
+        async fn list_actuals(
+            &self,
+            filter: &AssignmentFilter,
+        ) -> sqlx::Result<Vec<domain::Actual>> {
+            let rows = self.actuals.lock().unwrap().clone();
+            let mut out: Vec<_> = rows
                 .into_iter()
                 .filter(|a| {
                     (filter.resource_id.is_none_or(|id| a.resource_id == id))
@@ -193,9 +447,71 @@ mod tests {
                         && (filter.month.is_none_or(|m| a.month == m))
                 })
                 .collect();
+            out.sort_by_key(|a| (a.year, a.month, a.project_id, a.resource_id));
             Ok(out)
         }
 
+        async fn utilisation(
+            &self,
+            months: &[(i32, i32)],
+            group_by: domain::UtilisationGroup,
+        ) -> sqlx::Result<Vec<domain::Utilisation>> {
+            let monthly = self.assignments.lock().unwrap().clone();
+            let weeks = self.week_assignments.lock().unwrap().clone();
+            let rows: Vec<_> = domain::roll_up(monthly, weeks)
+                .into_iter()
+                .map(|b| b.assignment)
+                .filter(|a| months.contains(&(a.year, a.month)))
+                .collect();
+            let row = |project_id, resource_id, month: Option<(i32, i32)>, count: usize| {
+                domain::Utilisation {
+                    project_id,
+                    resource_id,
+                    year: month.map(|m| m.0),
+                    month: month.map(|m| m.1),
+                    count: i64::try_from(count).unwrap(),
+                }
+            };
+            Ok(match group_by {
+                domain::UtilisationGroup::Project => {
+                    let mut groups: BTreeMap<(i32, i32, Uuid), usize> = BTreeMap::new();
+                    for a in rows {
+                        *groups.entry((a.year, a.month, a.project_id)).or_default() += 1;
+                    }
+                    groups
+                        .into_iter()
+                        .map(|((y, m, p), n)| row(Some(p), None, Some((y, m)), n))
+                        .collect()
+                }
+                domain::UtilisationGroup::Resource => {
+                    let mut groups: BTreeMap<Uuid, BTreeSet<(i32, i32)>> = BTreeMap::new();
+                    for a in rows {
+                        groups
+                            .entry(a.resource_id)
+                            .or_default()
+                            .insert((a.year, a.month));
+                    }
+                    groups
+                        .into_iter()
+                        .map(|(r, ms)| row(None, Some(r), None, ms.len()))
+                        .collect()
+                }
+                domain::UtilisationGroup::Month => {
+                    let mut groups: BTreeMap<(i32, i32), HashSet<Uuid>> = BTreeMap::new();
+                    for a in rows {
+                        groups
+                            .entry((a.year, a.month))
+                            .or_default()
+                            .insert(a.resource_id);
+                    }
+                    groups
+                        .into_iter()
+                        .map(|(m, rs)| row(None, None, Some(m), rs.len()))
+                        .collect()
+                }
+            })
+        }
+
         async fn projects(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Project>> {
             let set: HashSet<Uuid> = ids.iter().copied().collect();
             Ok(self
@@ -220,15 +536,38 @@ mod tests {
                 .collect())
         }
 
-        async fn upsert_project(&self, project: &domain::Project) -> sqlx::Result<()> {
-            self.projects
+        async fn teams(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Team>> {
+            let set: HashSet<Uuid> = ids.iter().copied().collect();
+            Ok(self
+                .teams
                 .lock()
                 .unwrap()
-                .insert(project.id, project.clone());
+                .values()
+                .filter(|t| set.contains(&t.id))
+                .cloned()
+                .collect())
+        }
+
+        async fn upsert_project(&self, project: &domain::Project) -> sqlx::Result<()> {
+            let mut projects = self.projects.lock().unwrap();
+            if project
+                .parent_id
+                .is_some_and(|p| p != project.id && !projects.contains_key(&p))
+            {
+                return Err(sqlx::Error::RowNotFound);
+            }
+            projects.insert(project.id, project.clone());
+            drop(projects);
             Ok(())
         }
 
         async fn upsert_resource(&self, resource: &domain::Resource) -> sqlx::Result<()> {
+            if resource
+                .team_id
+                .is_some_and(|t| !self.teams.lock().unwrap().contains_key(&t))
+            {
+                return Err(sqlx::Error::RowNotFound);
+            }
             self.resources
                 .lock()
                 .unwrap()
@@ -236,10 +575,20 @@ mod tests {
             Ok(())
         }
 
+        async fn upsert_team(&self, team: &domain::Team) -> sqlx::Result<()> {
+            self.teams.lock().unwrap().insert(team.id, team.clone());
+            Ok(())
+        }
+
         async fn create_project(&self, name: &str) -> sqlx::Result<domain::Project> {
             let p = domain::Project {
                 id: Uuid::new_v4(),
                 name: name.to_string(),
+                parent_id: None,
+                budget: None,
+                status: domain::ProjectStatus::default(),
+                colour: None,
+                description: None,
             };
             self.upsert_project(&p).await?;
             Ok(p)
@@ -249,34 +598,145 @@ mod tests {
             let r = domain::Resource {
                 id: Uuid::new_v4(),
                 name: name.to_string(),
+                team_id: None,
+                kind: domain::ResourceKind::Person,
+                region: None,
             };
             self.upsert_resource(&r).await?;
             Ok(r)
         }
 
+        async fn create_team(&self, name: &str) -> sqlx::Result<domain::Team> {
+            let t = domain::Team {
+                id: Uuid::new_v4(),
+                name: name.to_string(),
+            };
+            self.upsert_team(&t).await?;
+            Ok(t)
+        }
+
         async fn delete_project(&self, id: Uuid) -> sqlx::Result<u64> {
             let removed = self.projects.lock().unwrap().remove(&id).is_some();
-            // cascade assignments
+            // cascade assignments, actuals and managers
             self.assignments
                 .lock()
                 .unwrap()
                 .retain(|a| a.project_id != id);
+            self.week_assignments
+                .lock()
+                .unwrap()
+                .retain(|a| a.project_id != id);
+            self.actuals.lock().unwrap().retain(|a| a.project_id != id);
+            self.managers.lock().unwrap().retain(|(p, _)| *p != id);
+            for p in self.projects.lock().unwrap().values_mut() {
+                if p.parent_id == Some(id) {
+                    p.parent_id = None;
+                }
+            }
             Ok(u64::from(removed))
         }
 
+        async fn set_project_parent(&self, id: Uuid, parent_id: Option<Uuid>) -> sqlx::Result<u64> {
+            let mut projects = self.projects.lock().unwrap();
+            if parent_id.is_some_and(|p| !projects.contains_key(&p)) {
+                return Err(sqlx::Error::RowNotFound);
+            }
+            Ok(projects.get_mut(&id).map_or(0, |p| {
+                p.parent_id = parent_id;
+                1
+            }))
+        }
+
         async fn delete_resource(&self, id: Uuid) -> sqlx::Result<u64> {
             let removed = self.resources.lock().unwrap().remove(&id).is_some();
             self.assignments
                 .lock()
                 .unwrap()
                 .retain(|a| a.resource_id != id);
+            self.week_assignments
+                .lock()
+                .unwrap()
+                .retain(|a| a.resource_id != id);
+            self.actuals.lock().unwrap().retain(|a| a.resource_id != id);
+            self.cost_rates
+                .lock()
+                .unwrap()
+                .retain(|c| c.resource_id != id);
+            self.capacities
+                .lock()
+                .unwrap()
+                .retain(|c| c.resource_id != id);
+            Ok(u64::from(removed))
+        }
+
+        async fn delete_team(&self, id: Uuid) -> sqlx::Result<u64> {
+            let removed = self.teams.lock().unwrap().remove(&id).is_some();
+            for r in self.resources.lock().unwrap().values_mut() {
+                if r.team_id == Some(id) {
+                    r.team_id = None;
+                }
+            }
             Ok(u64::from(removed))
         }
 
+        async fn set_project_budget(&self, id: Uuid, budget: Option<i64>) -> sqlx::Result<u64> {
+            Ok(self.projects.lock().unwrap().get_mut(&id).map_or(0, |p| {
+                p.budget = budget;
+                1
+            }))
+        }
+
+        async fn set_project_details(
+            &self,
+            id: Uuid,
+            status: domain::ProjectStatus,
+            colour: Option<&str>,
+            description: Option<&str>,
+        ) -> sqlx::Result<u64> {
+            Ok(self.projects.lock().unwrap().get_mut(&id).map_or(0, |p| {
+                p.status = status;
+                p.colour = colour.map(str::to_string);
+                p.description = description.map(str::to_string);
+                1
+            }))
+        }
+
+        async fn set_resource_team(&self, id: Uuid, team_id: Option<Uuid>) -> sqlx::Result<u64> {
+            if team_id.is_some_and(|t| !self.teams.lock().unwrap().contains_key(&t)) {
+                return Err(sqlx::Error::RowNotFound);
+            }
+            Ok(self.resources.lock().unwrap().get_mut(&id).map_or(0, |r| {
+                r.team_id = team_id;
+                1
+            }))
+        }
+
+        async fn set_resource_region(&self, id: Uuid, region: Option<&str>) -> sqlx::Result<u64> {
+            Ok(self.resources.lock().unwrap().get_mut(&id).map_or(0, |r| {
+                r.region = region.map(str::to_string);
+                1
+            }))
+        }
+
         async fn assign(&self, assignment: &domain::Assignment) -> sqlx::Result<()> {
+            // mirror the foreign keys on the assignments table
+            if !self
+                .projects
+                .lock()
+                .unwrap()
+                .contains_key(&assignment.project_id)
+                || !self
+                    .resources
+                    .lock()
+                    .unwrap()
+                    .contains_key(&assignment.resource_id)
+            {
+                return Err(sqlx::Error::RowNotFound);
+            }
             let mut rows = self.assignments.lock().unwrap();
-            if !rows.iter().any(|a| a == assignment) {
-                rows.push(assignment.clone());
+            match rows.iter_mut().find(|a| a.key() == assignment.key()) {
+                Some(existing) => existing.clone_from(assignment),
+                None => rows.push(assignment.clone()),
             }
             drop(rows);
             Ok(())
@@ -285,9 +745,383 @@ mod tests {
         async fn unassign(&self, assignment: &domain::Assignment) -> sqlx::Result<u64> {
             let mut rows = self.assignments.lock().unwrap();
             let before = rows.len();
-            rows.retain(|a| a != assignment);
+            rows.retain(|a| a.key() != assignment.key());
+            Ok((before - rows.len()) as u64)
+        }
+
+        async fn assign_week(&self, assignment: &domain::WeekAssignment) -> sqlx::Result<()> {
+            // mirror the foreign keys on the week_assignments table
+            if !self
+                .projects
+                .lock()
+                .unwrap()
+                .contains_key(&assignment.project_id)
+                || !self
+                    .resources
+                    .lock()
+                    .unwrap()
+                    .contains_key(&assignment.resource_id)
+            {
+                return Err(sqlx::Error::RowNotFound);
+            }
+            let mut rows = self.week_assignments.lock().unwrap();
+            match rows.iter_mut().find(|a| a.key() == assignment.key()) {
+                Some(existing) => existing.clone_from(assignment),
+                None => rows.push(assignment.clone()),
+            }
+            drop(rows);
+            Ok(())
+        }
+
+        async fn unassign_week(&self, assignment: &domain::WeekAssignment) -> sqlx::Result<u64> {
+            let mut rows = self.week_assignments.lock().unwrap();
+            let before = rows.len();
+            rows.retain(|a| a.key() != assignment.key());
+            Ok((before - rows.len()) as u64)
+        }
+
+        async fn move_assignments(&self, from: Uuid, to: Uuid) -> sqlx::Result<u64> {
+            let mut rows = self.assignments.lock().unwrap();
+            let mut weeks = self.week_assignments.lock().unwrap();
+            // mirror the foreign key, which only trips once a row moves
+            let any_moves = rows.iter().any(|a| a.resource_id == from)
+                || weeks.iter().any(|a| a.resource_id == from);
+            if any_moves && !self.resources.lock().unwrap().contains_key(&to) {
+                return Err(sqlx::Error::RowNotFound);
+            }
+            let (moved, mut kept): (Vec<_>, Vec<_>) =
+                rows.drain(..).partition(|a| a.resource_id == from);
+            let mut count = 0;
+            for mut a in moved {
+                a.resource_id = to;
+                if !kept.iter().any(|k| k.key() == a.key()) {
+                    kept.push(a);
+                    count += 1;
+                }
+            }
+            *rows = kept;
+            drop(rows);
+            let (moved, mut kept): (Vec<_>, Vec<_>) =
+                weeks.drain(..).partition(|a| a.resource_id == from);
+            for mut a in moved {
+                a.resource_id = to;
+                if !kept.iter().any(|k| k.key() == a.key()) {
+                    kept.push(a);
+                    count += 1;
+                }
+            }
+            *weeks = kept;
+            drop(weeks);
+            Ok(count)
+        }
+
+        async fn confirm_assignments(
+            &self,
+            project_id: Uuid,
+            months: &[(i32, i32)],
+        ) -> sqlx::Result<u64> {
+            let mut count = 0;
+            for a in self.assignments.lock().unwrap().iter_mut() {
+                if a.project_id == project_id
+                    && a.status == domain::AssignmentStatus::Tentative
+                    && months.contains(&(a.year, a.month))
+                {
+                    a.status = domain::AssignmentStatus::Confirmed;
+                    count += 1;
+                }
+            }
+            for a in self.week_assignments.lock().unwrap().iter_mut() {
+                let month = a.to_month().map(|m| (m.year, m.month));
+                if a.project_id == project_id
+                    && a.status == domain::AssignmentStatus::Tentative
+                    && month.is_some_and(|m| months.contains(&m))
+                {
+                    a.status = domain::AssignmentStatus::Confirmed;
+                    count += 1;
+                }
+            }
+            Ok(count)
+        }
+
+        async fn apply_batch(&self, batch: &domain::Batch) -> sqlx::Result<()> {
+            // validate up front so a failing batch leaves nothing behind
+            let keys = batch
+                .assignments
+                .iter()
+                .map(|a| (a.project_id, a.resource_id));
+            let keys = keys
+                .chain(
+                    batch
+                        .week_assignments
+                        .iter()
+                        .map(|a| (a.project_id, a.resource_id)),
+                )
+                .chain(batch.actuals.iter().map(|a| (a.project_id, a.resource_id)));
+            for (project_id, resource_id) in keys {
+                let project_known = self.projects.lock().unwrap().contains_key(&project_id)
+                    || batch.projects.iter().any(|p| p.id == project_id);
+                let resource_known = self.resources.lock().unwrap().contains_key(&resource_id)
+                    || batch.resources.iter().any(|r| r.id == resource_id);
+                if !project_known || !resource_known {
+                    return Err(sqlx::Error::RowNotFound);
+                }
+            }
+            // parents may come later in the batch, as the deferred foreign key allows
+            for p in &batch.projects {
+                let parent_known = p.parent_id.is_none_or(|parent| {
+                    self.projects.lock().unwrap().contains_key(&parent)
+                        || batch.projects.iter().any(|q| q.id == parent)
+                });
+                if !parent_known {
+                    return Err(sqlx::Error::RowNotFound);
+                }
+            }
+            for (project_id, _) in &batch.project_managers {
+                if !self.projects.lock().unwrap().contains_key(project_id)
+                    && !batch.projects.iter().any(|p| p.id == *project_id)
+                {
+                    return Err(sqlx::Error::RowNotFound);
+                }
+            }
+            let resource_ids = batch.cost_rates.iter().map(|c| c.resource_id);
+            for resource_id in resource_ids.chain(batch.capacities.iter().map(|c| c.resource_id)) {
+                if !self.resources.lock().unwrap().contains_key(&resource_id)
+                    && !batch.resources.iter().any(|r| r.id == resource_id)
+                {
+                    return Err(sqlx::Error::RowNotFound);
+                }
+            }
+            for r in &batch.resources {
+                let team_known = r.team_id.is_none_or(|team| {
+                    self.teams.lock().unwrap().contains_key(&team)
+                        || batch.teams.iter().any(|t| t.id == team)
+                });
+                if !team_known {
+                    return Err(sqlx::Error::RowNotFound);
+                }
+            }
+            for t in &batch.teams {
+                self.upsert_team(t).await?;
+            }
+            self.projects
+                .lock()
+                .unwrap()
+                .extend(batch.projects.iter().map(|p| (p.id, p.clone())));
+            for r in &batch.resources {
+                self.upsert_resource(r).await?;
+            }
+            for rate in &batch.cost_rates {
+                self.set_cost_rate(rate).await?;
+            }
+            for capacity in &batch.capacities {
+                self.set_capacity(capacity).await?;
+            }
+            for (project_id, user_id) in &batch.project_managers {
+                self.add_project_manager(*project_id, user_id).await?;
+            }
+            for (user_id, role) in &batch.user_roles {
+                self.set_user_role(user_id, *role).await?;
+            }
+            for a in &batch.assignments {
+                self.assign(a).await?;
+            }
+            for a in &batch.week_assignments {
+                self.assign_week(a).await?;
+            }
+            let mut actuals = self.actuals.lock().unwrap();
+            for a in &batch.actuals {
+                actuals.retain(|b| {
+                    (b.resource_id, b.project_id, b.year, b.month)
+                        != (a.resource_id, a.project_id, a.year, a.month)
+                });
+                actuals.push(a.clone());
+            }
+            drop(actuals);
+            Ok(())
+        }
+
+        async fn list_cost_rates(&self) -> sqlx::Result<Vec<domain::CostRate>> {
+            let mut out = self.cost_rates.lock().unwrap().clone();
+            out.sort_by_key(|c| (c.resource_id, c.year, c.month));
+            Ok(out)
+        }
+
+        async fn set_cost_rate(&self, rate: &domain::CostRate) -> sqlx::Result<()> {
+            if !self
+                .resources
+                .lock()
+                .unwrap()
+                .contains_key(&rate.resource_id)
+            {
+                return Err(sqlx::Error::RowNotFound);
+            }
+            let key = |c: &domain::CostRate| (c.resource_id, c.year, c.month);
+            let mut rows = self.cost_rates.lock().unwrap();
+            match rows.iter_mut().find(|c| key(c) == key(rate)) {
+                Some(existing) => existing.rate = rate.rate,
+                None => rows.push(rate.clone()),
+            }
+            drop(rows);
+            Ok(())
+        }
+
+        async fn delete_cost_rate(
+            &self,
+            resource_id: Uuid,
+            year: i32,
+            month: i32,
+        ) -> sqlx::Result<u64> {
+            let mut rows = self.cost_rates.lock().unwrap();
+            let before = rows.len();
+            rows.retain(|c| (c.resource_id, c.year, c.month) != (resource_id, year, month));
+            Ok((before - rows.len()) as u64)
+        }
+
+        async fn list_capacities(&self) -> sqlx::Result<Vec<domain::Capacity>> {
+            let mut out = self.capacities.lock().unwrap().clone();
+            out.sort_by_key(|c| (c.resource_id, c.year, c.month));
+            Ok(out)
+        }
+
+        async fn set_capacity(&self, capacity: &domain::Capacity) -> sqlx::Result<()> {
+            if !self
+                .resources
+                .lock()
+                .unwrap()
+                .contains_key(&capacity.resource_id)
+            {
+                return Err(sqlx::Error::RowNotFound);
+            }
+            let key = |c: &domain::Capacity| (c.resource_id, c.year, c.month);
+            let mut rows = self.capacities.lock().unwrap();
+            match rows.iter_mut().find(|c| key(c) == key(capacity)) {
+                Some(existing) => existing.percent = capacity.percent,
+                None => rows.push(capacity.clone()),
+            }
+            drop(rows);
+            Ok(())
+        }
+
+        async fn capacities_in(
+            &self,
+            resource_ids: &[Uuid],
+            months: &[(i32, i32)],
+        ) -> sqlx::Result<Vec<domain::Capacity>> {
+            let mut out = self.list_capacities().await?;
+            out.retain(|c| {
+                resource_ids.contains(&c.resource_id) && months.contains(&(c.year, c.month))
+            });
+            Ok(out)
+        }
+
+        async fn set_capacities(
+            &self,
+            resource_id: Uuid,
+            months: &[(i32, i32)],
+            percent: Option<i32>,
+        ) -> sqlx::Result<()> {
+            // mirror the foreign key up front so that nothing is half-written
+            if percent.is_some() && !self.resources.lock().unwrap().contains_key(&resource_id) {
+                return Err(sqlx::Error::RowNotFound);
+            }
+            for &(year, month) in months {
+                match percent {
+                    Some(percent) => {
+                        self.set_capacity(&domain::Capacity {
+                            resource_id,
+                            year,
+                            month,
+                            percent,
+                        })
+                        .await?;
+                    }
+                    None => {
+                        self.delete_capacity(resource_id, year, month).await?;
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        async fn delete_capacity(
+            &self,
+            resource_id: Uuid,
+            year: i32,
+            month: i32,
+        ) -> sqlx::Result<u64> {
+            let mut rows = self.capacities.lock().unwrap();
+            let before = rows.len();
+            rows.retain(|c| (c.resource_id, c.year, c.month) != (resource_id, year, month));
             Ok((before - rows.len()) as u64)
         }
+
+        async fn record_audit(&self, entry: &domain::AuditEntry) -> sqlx::Result<()> {
+            self.audit.lock().unwrap().push(entry.clone());
+            Ok(())
+        }
+
+        async fn list_audit(&self) -> sqlx::Result<Vec<domain::AuditEntry>> {
+            Ok(self.audit.lock().unwrap().clone())
+        }
+
+        async fn user_role(&self, user_id: &str) -> sqlx::Result<Option<domain::Role>> {
+            Ok(self.roles.lock().unwrap().get(user_id).copied())
+        }
+
+        async fn set_user_role(&self, user_id: &str, role: domain::Role) -> sqlx::Result<()> {
+            self.roles.lock().unwrap().insert(user_id.to_string(), role);
+            Ok(())
+        }
+
+        async fn list_user_roles(&self) -> sqlx::Result<Vec<(String, domain::Role)>> {
+            let mut out: Vec<_> = self
+                .roles
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(u, r)| (u.clone(), *r))
+                .collect();
+            out.sort();
+            Ok(out)
+        }
+
+        async fn is_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<bool> {
+            Ok(self
+                .managers
+                .lock()
+                .unwrap()
+                .contains(&(project_id, user_id.to_string())))
+        }
+
+        async fn add_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<()> {
+            if !self.projects.lock().unwrap().contains_key(&project_id) {
+                return Err(sqlx::Error::RowNotFound);
+            }
+            self.managers
+                .lock()
+                .unwrap()
+                .insert((project_id, user_id.to_string()));
+            Ok(())
+        }
+
+        async fn remove_project_manager(
+            &self,
+            project_id: Uuid,
+            user_id: &str,
+        ) -> sqlx::Result<u64> {
+            let removed = self
+                .managers
+                .lock()
+                .unwrap()
+                .remove(&(project_id, user_id.to_string()));
+            Ok(u64::from(removed))
+        }
+
+        async fn list_project_managers(&self) -> sqlx::Result<Vec<(Uuid, String)>> {
+            let mut out: Vec<_> = self.managers.lock().unwrap().iter().cloned().collect();
+            out.sort();
+            Ok(out)
+        }
     }
 
     fn schema_with(
@@ -301,6 +1135,10 @@ mod tests {
             crate::gql::loader::ResourceLoader::new(Arc::new(repo.clone())),
             tokio::spawn,
         );
+        let team_loader = DataLoader::new(
+            crate::gql::loader::TeamLoader::new(Arc::new(repo.clone())),
+            tokio::spawn,
+        );
         Schema::build(
             QueryRoot,
             crate::gql::mutation::MutationRoot,
@@ -309,27 +1147,51 @@ mod tests {
         .data(Arc::new(repo) as Repo)
         .data(project_loader)
         .data(resource_loader)
+        .data(team_loader)
+        .data(crate::workdays::WorkingCalendars::default())
         .finish()
     }
 
     #[tokio::test]
+    async fn mem_repo_conforms() {
+        crate::testing::run_repository_conformance(&MemRepo::default()).await;
+    }
+
+    #[tokio::test]
+    #[allow(clippy::too_many_lines)]
     async fn project_and_resource_month_matrix_includes_all_rows_and_cells() {
         let repo = MemRepo::default();
         let p1 = domain::Project {
             id: Uuid::new_v4(),
             name: "P1".into(),
+            parent_id: None,
+            budget: None,
+            status: domain::ProjectStatus::default(),
+            colour: None,
+            description: None,
         };
         let p2 = domain::Project {
             id: Uuid::new_v4(),
             name: "P2".into(),
+            parent_id: None,
+            budget: None,
+            status: domain::ProjectStatus::default(),
+            colour: None,
+            description: None,
         };
         let r1 = domain::Resource {
             id: Uuid::new_v4(),
             name: "R1".into(),
+            team_id: None,
+            kind: domain::ResourceKind::Person,
+            region: None,
         };
         let r2 = domain::Resource {
             id: Uuid::new_v4(),
             name: "R2".into(),
+            team_id: None,
+            kind: domain::ResourceKind::Person,
+            region: None,
         };
         repo.upsert_project(&p1).await.unwrap();
         repo.upsert_project(&p2).await.unwrap();
@@ -340,6 +1202,9 @@ mod tests {
             project_id: p1.id,
             year: 2024,
             month: 3,
+            role: None,
+            note: None,
+            status: domain::AssignmentStatus::Confirmed,
         })
         .await
         .unwrap();
@@ -348,6 +1213,9 @@ mod tests {
             project_id: p2.id,
             year: 2024,
             month: 4,
+            role: None,
+            note: None,
+            status: domain::AssignmentStatus::Confirmed,
         })
         .await
         .unwrap();
@@ -359,7 +1227,7 @@ mod tests {
             query($ms: [Month!]!) {
               projectMonthMatrix(months: $ms) {
                 months
-                rows { project { id } cells { resources { id } } }
+                rows { nodes { project { id } cells { resources { id } } } }
               }
             }
         ";
@@ -380,7 +1248,9 @@ mod tests {
                 .len(),
             2
         );
-        let rows = data["projectMonthMatrix"]["rows"].as_array().unwrap();
+        let rows = data["projectMonthMatrix"]["rows"]["nodes"]
+            .as_array()
+            .unwrap();
         // Expect 2 projects
         assert_eq!(rows.len(), 2);
         // Each row should have exactly two cells
@@ -403,7 +1273,7 @@ mod tests {
             query($ms: [Month!]!) {
               resourceMonthMatrix(months: $ms) {
                 months
-                rows { resource { id } cells { projects { id } } }
+                rows { nodes { resource { id } cells { projects { id } } } }
               }
             }
         ";
@@ -416,10 +1286,287 @@ mod tests {
             .await;
         assert!(resp.errors.is_empty());
         let data = serde_json::to_value(resp.data).unwrap();
-        let rows = data["resourceMonthMatrix"]["rows"].as_array().unwrap();
+        let rows = data["resourceMonthMatrix"]["rows"]["nodes"]
+            .as_array()
+            .unwrap();
         assert_eq!(rows.len(), 2);
         for row in rows {
             assert_eq!(row["cells"].as_array().unwrap().len(), 2);
         }
     }
+
+    #[tokio::test]
+    async fn matrix_rows_count_assignments_just_outside_the_window() {
+        let repo = MemRepo::default();
+        let p = repo.create_project("P").await.unwrap();
+        let r = repo.create_resource("R").await.unwrap();
+        for (year, month) in [(2023, 12), (2024, 2), (2024, 3)] {
+            repo.assign(&domain::Assignment {
+                resource_id: r.id,
+                project_id: p.id,
+                year,
+                month,
+                role: None,
+                note: None,
+                status: domain::AssignmentStatus::Confirmed,
+            })
+            .await
+            .unwrap();
+        }
+        let schema = schema_with(repo);
+
+        let q = r#"
+            {
+              projectMonthMatrix(months: ["2024-01", "2024-02"]) { rows { nodes { prevCount nextCount } } }
+              resourceMonthMatrix(months: ["2024-03"]) { rows { nodes { prevCount nextCount } } }
+            }
+        "#;
+        let resp = schema.execute(q).await;
+        assert!(resp.errors.is_empty(), "{:?}", resp.errors);
+        let data = serde_json::to_value(resp.data).unwrap();
+        // 2023-12 precedes January across the year boundary; 2024-03 follows February
+        assert_eq!(
+            data["projectMonthMatrix"]["rows"]["nodes"][0],
+            serde_json::json!({ "prevCount": 1, "nextCount": 1 })
+        );
+        assert_eq!(
+            data["resourceMonthMatrix"]["rows"]["nodes"][0],
+            serde_json::json!({ "prevCount": 1, "nextCount": 0 })
+        );
+    }
+
+    #[tokio::test]
+    async fn matrix_rows_can_be_filtered_and_sorted() {
+        let repo = MemRepo::default();
+        let alpha = repo.create_project("Alpha").await.unwrap();
+        let beta = repo.create_project("Beta").await.unwrap();
+        let gamma = repo.create_project("Gamma").await.unwrap();
+        let r1 = repo.create_resource("R1").await.unwrap();
+        let r2 = repo.create_resource("R2").await.unwrap();
+        for (r, p) in [(&r1, &beta), (&r2, &beta), (&r1, &gamma)] {
+            repo.assign(&domain::Assignment {
+                resource_id: r.id,
+                project_id: p.id,
+                year: 2024,
+                month: 5,
+                role: None,
+                note: None,
+                status: domain::AssignmentStatus::Confirmed,
+            })
+            .await
+            .unwrap();
+        }
+        let schema = schema_with(repo);
+        let names = |filter: &str, sort: &str| {
+            let q = format!(
+                r#"{{ projectMonthMatrix(months: ["2024-05"], filter: {filter}, sort: {sort}) {{
+                    rows {{ nodes {{ project {{ name }} }} }}
+                }} }}"#
+            );
+            let schema = schema.clone();
+            async move {
+                let resp = schema.execute(q).await;
+                assert!(resp.errors.is_empty(), "{:?}", resp.errors);
+                let data = serde_json::to_value(resp.data).unwrap();
+                data["projectMonthMatrix"]["rows"]["nodes"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|r| r["project"]["name"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(names("{}", "{}").await, ["Alpha", "Beta", "Gamma"]);
+        assert_eq!(
+            names("{}", "{ descending: true }").await,
+            ["Gamma", "Beta", "Alpha"]
+        );
+        assert_eq!(
+            names("{}", "{ by: ALLOCATION, descending: true }").await,
+            ["Beta", "Gamma", "Alpha"]
+        );
+        assert_eq!(
+            names("{ assignedOnly: true }", "{}").await,
+            ["Beta", "Gamma"]
+        );
+        assert_eq!(names(r#"{ search: "MM" }"#, "{}").await, ["Gamma"]);
+        assert_eq!(
+            names(
+                &format!(r#"{{ ids: ["{}", "{}"] }}"#, alpha.id, gamma.id),
+                "{}"
+            )
+            .await,
+            ["Alpha", "Gamma"]
+        );
+    }
+
+    #[tokio::test]
+    async fn matrix_rows_page_forward_with_cursors() {
+        let repo = MemRepo::default();
+        for name in ["A", "C", "E"] {
+            repo.create_project(name).await.unwrap();
+        }
+        let schema = schema_with(repo.clone());
+        let page = |args: &str| {
+            let q = format!(
+                r#"{{ projectMonthMatrix(months: ["2024-01"]{args}) {{
+                    rows {{
+                        totalCount
+                        pageInfo {{ hasPreviousPage hasNextPage endCursor }}
+                        nodes {{ project {{ name }} }}
+                    }}
+                }} }}"#
+            );
+            let schema = schema.clone();
+            async move {
+                let resp = schema.execute(q).await;
+                assert!(resp.errors.is_empty(), "{:?}", resp.errors);
+                serde_json::to_value(resp.data).unwrap()["projectMonthMatrix"]["rows"].clone()
+            }
+        };
+
+        let first = page(", first: 2").await;
+        assert_eq!(first["totalCount"], 3);
+        assert_eq!(
+            first["nodes"],
+            serde_json::json!([{ "project": { "name": "A" } }, { "project": { "name": "C" } }])
+        );
+        assert_eq!(first["pageInfo"]["hasPreviousPage"], false);
+        assert_eq!(first["pageInfo"]["hasNextPage"], true);
+
+        // Rows added before the cursor don't shift the next page
+        repo.create_project("B").await.unwrap();
+        repo.create_project("D").await.unwrap();
+        let cursor = first["pageInfo"]["endCursor"].as_str().unwrap();
+        let second = page(&format!(r#", first: 2, after: "{cursor}""#)).await;
+        assert_eq!(second["totalCount"], 5);
+        assert_eq!(
+            second["nodes"],
+            serde_json::json!([{ "project": { "name": "D" } }, { "project": { "name": "E" } }])
+        );
+        assert_eq!(second["pageInfo"]["hasPreviousPage"], true);
+        assert_eq!(second["pageInfo"]["hasNextPage"], false);
+
+        // Without `first` every row is returned
+        assert_eq!(page("").await["nodes"].as_array().unwrap().len(), 5);
+
+        // A cursor only continues the sort it was issued for
+        let resp = schema
+            .execute(format!(
+                r#"{{ projectMonthMatrix(months: [], sort: {{ descending: true }}, after: "{cursor}") {{
+                    rows {{ totalCount }}
+                }} }}"#
+            ))
+            .await;
+        assert_eq!(
+            resp.errors[0].message,
+            "Cursor was issued for a different sort"
+        );
+        let resp = schema
+            .execute(r#"{ projectMonthMatrix(months: [], after: "x") { rows { totalCount } } }"#)
+            .await;
+        assert_eq!(resp.errors[0].message, "Invalid cursor");
+    }
+
+    #[tokio::test]
+    async fn matrix_rows_page_through_allocation_order() {
+        let repo = MemRepo::default();
+        let r = repo.create_resource("R").await.unwrap();
+        for (name, months) in [("A", 1), ("B", 3), ("C", 2), ("D", 2)] {
+            let p = repo.create_project(name).await.unwrap();
+            for month in 1..=months {
+                repo.assign(&domain::Assignment {
+                    resource_id: r.id,
+                    project_id: p.id,
+                    year: 2024,
+                    month,
+                    role: None,
+                    note: None,
+                    status: domain::AssignmentStatus::Confirmed,
+                })
+                .await
+                .unwrap();
+            }
+        }
+        let schema = schema_with(repo);
+        let mut names = Vec::new();
+        let mut after = String::new();
+        loop {
+            let q = format!(
+                r#"{{ projectMonthMatrix(
+                    months: ["2024-01", "2024-02", "2024-03"],
+                    sort: {{ by: ALLOCATION, descending: true }},
+                    first: 1{after}
+                ) {{
+                    rows {{ pageInfo {{ hasNextPage endCursor }} nodes {{ project {{ name }} }} }}
+                }} }}"#
+            );
+            let resp = schema.execute(q).await;
+            assert!(resp.errors.is_empty(), "{:?}", resp.errors);
+            let rows =
+                serde_json::to_value(resp.data).unwrap()["projectMonthMatrix"]["rows"].clone();
+            names.push(
+                rows["nodes"][0]["project"]["name"]
+                    .as_str()
+                    .unwrap()
+                    .to_string(),
+            );
+            if rows["pageInfo"]["hasNextPage"] == false {
+                break;
+            }
+            after = format!(
+                r#", after: "{}""#,
+                rows["pageInfo"]["endCursor"].as_str().unwrap()
+            );
+        }
+        // Ties on allocation fall back to name order
+        assert_eq!(names, ["B", "C", "D", "A"]);
+    }
+
+    #[tokio::test]
+    async fn invalid_stored_periods_are_errors_not_panics() {
+        let repo = MemRepo::default();
+        let p = repo.create_project("P").await.unwrap();
+        let r = repo.create_resource("R").await.unwrap();
+        repo.assign(&domain::Assignment {
+            resource_id: r.id,
+            project_id: p.id,
+            year: 2025,
+            month: 13,
+            role: None,
+            note: None,
+            status: domain::AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+        // 2025 has 52 ISO weeks
+        repo.assign_week(&domain::WeekAssignment {
+            resource_id: r.id,
+            project_id: p.id,
+            year: 2025,
+            week: 53,
+            role: None,
+            note: None,
+            status: domain::AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+        let schema = schema_with(repo);
+
+        let resp = schema.execute("{ assignments { id } }").await;
+        assert_eq!(resp.errors[0].message, "Assignment has invalid month 13");
+        let resp = schema
+            .execute("{ projects { weekAssignments { id } } }")
+            .await;
+        assert_eq!(
+            resp.errors[0].message,
+            "Week assignment has invalid week 2025-W53"
+        );
+        // Matrices only hold the requested periods
+        let resp = schema
+            .execute(r#"{ projectWeekMatrix(weeks: ["2025-W52"]) { rows { totalCount } } }"#)
+            .await;
+        assert!(resp.errors.is_empty(), "{:?}", resp.errors);
+    }
 }
@@ -1,62 +1,120 @@
-use crate::domain;
-use crate::gql::loader::ProjectLoader;
-use crate::repo::{AssignmentFilter as RepoAssignmentFilter, Repo};
+use crate::gql::loader::{ProjectLoader, TeamLoader};
+use crate::repo::{AssignmentFilter as RepoAssignmentFilter, Repo, WeekFilter};
+use crate::{domain, import, workdays};
+use async_graphql::connection::{Connection, CursorType};
 use async_graphql::dataloader::DataLoader;
 use async_graphql::{
-    ComplexObject, Context, InputObject, InputValueError, InputValueResult, Scalar, ScalarType,
-    SimpleObject, Value,
+    ComplexObject, Context, Enum, InputObject, InputValueError, InputValueResult, Scalar,
+    ScalarType, SimpleObject, Value,
 };
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 use uuid::Uuid;
 
-// Scalar: Month in format "YYYY-MM"
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub struct MonthScalar {
-    pub year: i32,
-    pub month: u8, // 1..=12
-}
+/// Scalar: Month in format "YYYY-MM". Input may also be relative to the
+/// current month on the server: "current", "+3" or "-1".
+pub type MonthScalar = domain::YearMonth;
+
+#[Scalar(name = "Month")]
+impl ScalarType for MonthScalar {
+    fn parse(value: async_graphql::Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(s) => Self::resolve(&s, Self::current()).map_err(InputValueError::custom),
+            other => Err(InputValueError::custom(format!(
+                "Expected string for Month, got {other:?}"
+            ))),
+        }
+    }
 
-impl MonthScalar {
-    pub fn as_str(&self) -> String {
-        format!("{:04}-{:02}", self.year, self.month)
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
     }
 }
 
-impl Display for MonthScalar {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.as_str())
+/// Scalar: inclusive window of months in format "YYYY-MM..YYYY-MM". Either
+/// end may be relative like a `Month`, so "current..+11" is the next 12
+/// months including this one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MonthRange {
+    pub from: MonthScalar,
+    pub to: MonthScalar,
+}
+
+impl MonthRange {
+    /// Resolves `value` against `current`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if either end is invalid, the end precedes the start
+    /// or the range spans more than [`domain::MAX_MONTHS`] months.
+    pub fn resolve(value: &str, current: MonthScalar) -> Result<Self, String> {
+        let (from, to) = value
+            .split_once("..")
+            .ok_or("MonthRange must be in FROM..TO format")?;
+        let from = MonthScalar::resolve(from, current)?;
+        let to = MonthScalar::resolve(to, current)?;
+        if to < from {
+            return Err("MonthRange must not end before it starts".into());
+        }
+        if to - from >= i64::try_from(domain::MAX_MONTHS).unwrap_or(i64::MAX) {
+            return Err(format!(
+                "MonthRange must not span more than {} months",
+                domain::MAX_MONTHS
+            ));
+        }
+        Ok(Self { from, to })
+    }
+
+    /// Every month in the range, in order.
+    #[must_use]
+    pub fn months(self) -> Vec<MonthScalar> {
+        MonthScalar::range_inclusive(self.from, self.to)
     }
 }
 
-#[Scalar(name = "Month")]
-impl ScalarType for MonthScalar {
+#[Scalar(name = "MonthRange")]
+impl ScalarType for MonthRange {
     fn parse(value: async_graphql::Value) -> InputValueResult<Self> {
         match value {
             Value::String(s) => {
-                // Expect YYYY-MM
-                let parts: Vec<&str> = s.split('-').collect();
-                if parts.len() != 2 {
-                    return Err(InputValueError::custom("Month must be in YYYY-MM format"));
-                }
-                let year: i32 = parts[0]
-                    .parse()
-                    .map_err(|_| InputValueError::custom("Invalid year in Month"))?;
-                let month: u8 = parts[1]
-                    .parse()
-                    .map_err(|_| InputValueError::custom("Invalid month in Month"))?;
-                if !(1..=12).contains(&month) {
-                    return Err(InputValueError::custom("Month must be between 01 and 12"));
-                }
-                Ok(Self { year, month })
+                Self::resolve(&s, MonthScalar::current()).map_err(InputValueError::custom)
             }
             other => Err(InputValueError::custom(format!(
-                "Expected string for Month, got {other:?}"
+                "Expected string for MonthRange, got {other:?}"
             ))),
         }
     }
 
     fn to_value(&self) -> Value {
-        Value::String(self.as_str())
+        Value::String(format!("{}..{}", self.from, self.to))
+    }
+}
+
+/// Scalar: ISO 8601 week in format "YYYY-Www", e.g. "2025-W07".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WeekScalar(pub domain::IsoWeek);
+
+impl Display for WeekScalar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[Scalar(name = "Week")]
+impl ScalarType for WeekScalar {
+    fn parse(value: async_graphql::Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(s) => s.parse().map(Self).map_err(InputValueError::custom),
+            other => Err(InputValueError::custom(format!(
+                "Expected string for Week, got {other:?}"
+            ))),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
     }
 }
 
@@ -65,6 +123,30 @@ mod tests {
     use super::*;
     use async_graphql::ScalarType;
 
+    #[test]
+    fn week_scalar_parses_iso_weeks_and_rolls_up_by_thursday() {
+        let w = WeekScalar::parse(Value::String("2025-W07".into())).unwrap();
+        assert_eq!(w.to_value(), Value::String("2025-W07".into()));
+        assert_eq!(w.0.month(), (2025, 2));
+        // 2024-W01 starts on 2024-01-01; 2026-W01 starts on 2025-12-29 but
+        // its Thursday is 2026-01-01.
+        let first = WeekScalar::parse(Value::String("2026-W01".into())).unwrap();
+        assert_eq!(first.0.month(), (2026, 1));
+        let weeks = |year, month| {
+            domain::IsoWeek::of_month(year, month)
+                .iter()
+                .map(|w| w.week)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(weeks(2025, 12), [49, 50, 51, 52]);
+        assert_eq!(weeks(2026, 1), [1, 2, 3, 4, 5]);
+        // 2020 has 53 weeks, 2025 only 52.
+        assert!(WeekScalar::parse(Value::String("2020-W53".into())).is_ok());
+        assert!(WeekScalar::parse(Value::String("2025-W53".into())).is_err());
+        assert!(WeekScalar::parse(Value::String("2025-W00".into())).is_err());
+        assert!(WeekScalar::parse(Value::String("2025-07".into())).is_err());
+    }
+
     #[test]
     fn month_scalar_parse_valid() {
         let m = MonthScalar::parse(Value::String("2024-03".into())).unwrap();
@@ -73,12 +155,65 @@ mod tests {
         assert_eq!(m.to_value(), Value::String("2024-03".into()));
     }
 
+    #[test]
+    fn month_scalar_range_crosses_years() {
+        let from = MonthScalar {
+            year: 2024,
+            month: 11,
+        };
+        let to = MonthScalar {
+            year: 2025,
+            month: 2,
+        };
+        let months: Vec<String> = MonthScalar::range_inclusive(from, to)
+            .into_iter()
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(months, ["2024-11", "2024-12", "2025-01", "2025-02"]);
+        assert!(MonthScalar::range_inclusive(to, from).is_empty());
+    }
+
     #[test]
     fn month_scalar_parse_invalid() {
         assert!(MonthScalar::parse(Value::String("2024".into())).is_err());
         assert!(MonthScalar::parse(Value::String("2024-13".into())).is_err());
         assert!(MonthScalar::parse(Value::String("abcd-ef".into())).is_err());
     }
+
+    #[test]
+    fn month_scalar_resolves_relative_months_and_ranges() {
+        let current = MonthScalar {
+            year: 2025,
+            month: 11,
+        };
+        let resolve = |s| MonthScalar::resolve(s, current).map(|m| m.to_string());
+        assert_eq!(resolve("current"), Ok("2025-11".into()));
+        assert_eq!(resolve("+3"), Ok("2026-02".into()));
+        assert_eq!(resolve("-11"), Ok("2024-12".into()));
+        assert!(resolve("+x").is_err());
+        assert!(resolve("-1000000").is_err());
+        assert_eq!(current - 12, MonthScalar::new(2024, 11).unwrap());
+        assert_eq!((current + 14) - current, 14);
+        assert!(current.prev() < current && current < current.next());
+
+        let next_year = MonthRange::resolve("current..+11", current).unwrap();
+        assert_eq!(next_year.months().len(), 12);
+        assert_eq!(
+            next_year.to_value(),
+            Value::String("2025-11..2026-10".into())
+        );
+        let half = MonthRange::resolve("2025-01..2025-06", current).unwrap();
+        assert_eq!(half.months().len(), 6);
+        assert!(MonthRange::resolve("2025-06..2025-01", current).is_err());
+        assert!(MonthRange::resolve("2025-01..2044-12", current).is_ok());
+        assert!(MonthRange::resolve("2025-01..2045-01", current).is_err());
+
+        // The scalars resolve against the server's clock.
+        assert_eq!(
+            MonthScalar::parse(Value::String("current".into())).unwrap(),
+            MonthScalar::current()
+        );
+    }
 }
 
 // Health removed per simplified API
@@ -88,6 +223,14 @@ mod tests {
 pub struct Project {
     pub id: Uuid,
     pub name: String,
+    #[graphql(skip)]
+    pub parent_id: Option<Uuid>,
+    /// Total budget in whole units of the planning currency.
+    pub budget: Option<i64>,
+    pub status: ProjectStatus,
+    /// Display colour as `#rrggbb`.
+    pub colour: Option<String>,
+    pub description: Option<String>,
 }
 
 impl From<domain::Project> for Project {
@@ -95,12 +238,72 @@ impl From<domain::Project> for Project {
         Self {
             id: v.id,
             name: v.name,
+            parent_id: v.parent_id,
+            budget: v.budget,
+            status: v.status.into(),
+            colour: v.colour,
+            description: v.description,
+        }
+    }
+}
+
+#[derive(Enum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProjectStatus {
+    /// Pipeline work that hasn't been won or approved yet.
+    Proposed,
+    #[default]
+    Active,
+    OnHold,
+    Done,
+}
+
+impl From<domain::ProjectStatus> for ProjectStatus {
+    fn from(v: domain::ProjectStatus) -> Self {
+        match v {
+            domain::ProjectStatus::Proposed => Self::Proposed,
+            domain::ProjectStatus::Active => Self::Active,
+            domain::ProjectStatus::OnHold => Self::OnHold,
+            domain::ProjectStatus::Done => Self::Done,
+        }
+    }
+}
+
+impl From<ProjectStatus> for domain::ProjectStatus {
+    fn from(v: ProjectStatus) -> Self {
+        match v {
+            ProjectStatus::Proposed => Self::Proposed,
+            ProjectStatus::Active => Self::Active,
+            ProjectStatus::OnHold => Self::OnHold,
+            ProjectStatus::Done => Self::Done,
         }
     }
 }
 
 #[ComplexObject]
 impl Project {
+    /// The programme this project belongs to, if any.
+    async fn parent(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Self>> {
+        let Some(parent_id) = self.parent_id else {
+            return Ok(None);
+        };
+        let dl = ctx.data_unchecked::<DataLoader<ProjectLoader>>();
+        Ok(dl.load_one(parent_id).await?.map(Into::into))
+    }
+
+    /// Projects directly beneath this one, ordered by name.
+    async fn children(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Self>> {
+        let repo = ctx.data_unchecked::<Repo>();
+        let mut out: Vec<Self> = repo
+            .list_projects()
+            .await?
+            .into_iter()
+            .filter(|p| p.parent_id == Some(self.id))
+            .map(Into::into)
+            .collect();
+        out.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(out)
+    }
+
     async fn assignments(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Assignment>> {
         let repo = ctx.data_unchecked::<Repo>();
         let f = RepoAssignmentFilter {
@@ -108,7 +311,22 @@ impl Project {
             ..Default::default()
         };
         let out = repo.list_assignments(&f).await?;
-        Ok(out.into_iter().map(Into::into).collect())
+        out.into_iter().map(Assignment::try_from).collect()
+    }
+
+    /// Assignments planned by week rather than by month.
+    #[graphql(name = "weekAssignments")]
+    async fn week_assignments(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<WeekAssignment>> {
+        let repo = ctx.data_unchecked::<Repo>();
+        let f = WeekFilter {
+            project_id: Some(self.id),
+            ..Default::default()
+        };
+        let out = repo.list_week_assignments(&f).await?;
+        out.into_iter().map(WeekAssignment::try_from).collect()
     }
 }
 
@@ -117,10 +335,75 @@ impl Project {
 pub struct Resource {
     pub id: Uuid,
     pub name: String,
+    #[graphql(skip)]
+    pub team_id: Option<Uuid>,
+    pub kind: ResourceKind,
+    /// Region whose working-day calendar applies; weekdays only if unset.
+    pub region: Option<String>,
+}
+
+#[derive(Enum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResourceKind {
+    /// A named person.
+    #[default]
+    Person,
+    /// A stand-in for a role that hasn't been filled yet.
+    Placeholder,
+}
+
+impl From<domain::ResourceKind> for ResourceKind {
+    fn from(v: domain::ResourceKind) -> Self {
+        match v {
+            domain::ResourceKind::Person => Self::Person,
+            domain::ResourceKind::Placeholder => Self::Placeholder,
+        }
+    }
+}
+
+impl From<ResourceKind> for domain::ResourceKind {
+    fn from(v: ResourceKind) -> Self {
+        match v {
+            ResourceKind::Person => Self::Person,
+            ResourceKind::Placeholder => Self::Placeholder,
+        }
+    }
 }
 
 #[ComplexObject]
 impl Resource {
+    async fn team(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Team>> {
+        let Some(team_id) = self.team_id else {
+            return Ok(None);
+        };
+        let dl = ctx.data_unchecked::<DataLoader<TeamLoader>>();
+        Ok(dl.load_one(team_id).await?.map(Into::into))
+    }
+
+    /// Cost rates, ordered by the month they take effect.
+    #[graphql(name = "costRates")]
+    async fn cost_rates(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<CostRate>> {
+        let repo = ctx.data_unchecked::<Repo>();
+        Ok(repo
+            .list_cost_rates()
+            .await?
+            .into_iter()
+            .filter(|c| c.resource_id == self.id)
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Months whose capacity differs from full time, in month order.
+    async fn capacities(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Capacity>> {
+        let repo = ctx.data_unchecked::<Repo>();
+        Ok(repo
+            .list_capacities()
+            .await?
+            .into_iter()
+            .filter(|c| c.resource_id == self.id)
+            .map(Into::into)
+            .collect())
+    }
+
     async fn assignments(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Assignment>> {
         let repo = ctx.data_unchecked::<Repo>();
         let f = RepoAssignmentFilter {
@@ -128,7 +411,7 @@ impl Resource {
             ..Default::default()
         };
         let out = repo.list_assignments(&f).await?;
-        Ok(out.into_iter().map(Into::into).collect())
+        out.into_iter().map(Assignment::try_from).collect()
     }
 }
 
@@ -137,6 +420,80 @@ impl From<domain::Resource> for Resource {
         Self {
             id: resource.id,
             name: resource.name,
+            team_id: resource.team_id,
+            kind: resource.kind.into(),
+            region: resource.region,
+        }
+    }
+}
+
+/// A resource's monthly cost, in effect from `from` until its next rate.
+#[derive(SimpleObject, Clone)]
+pub struct CostRate {
+    pub from: MonthScalar,
+    /// Whole units of the planning currency per assigned month.
+    pub rate: i64,
+}
+
+impl From<domain::CostRate> for CostRate {
+    fn from(v: domain::CostRate) -> Self {
+        Self {
+            from: MonthScalar {
+                year: v.year,
+                month: u8::try_from(v.month).unwrap_or_default(),
+            },
+            rate: v.rate,
+        }
+    }
+}
+
+/// How much of a month a resource can work, where it differs from full time.
+#[derive(SimpleObject, Clone)]
+pub struct Capacity {
+    pub month: MonthScalar,
+    /// Percentage of a full month; 100 is 1.0 FTE.
+    pub percent: i32,
+}
+
+impl From<domain::Capacity> for Capacity {
+    fn from(v: domain::Capacity) -> Self {
+        Self {
+            month: MonthScalar {
+                year: v.year,
+                month: u8::try_from(v.month).unwrap_or_default(),
+            },
+            percent: v.percent,
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone)]
+#[graphql(complex)]
+pub struct Team {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[ComplexObject]
+impl Team {
+    /// Resources in this team, ordered by name.
+    async fn members(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Resource>> {
+        let repo = ctx.data_unchecked::<Repo>();
+        Ok(repo
+            .list_resources()
+            .await?
+            .into_iter()
+            .filter(|r| r.team_id == Some(self.id))
+            .map(Into::into)
+            .collect())
+    }
+}
+
+impl From<domain::Team> for Team {
+    fn from(team: domain::Team) -> Self {
+        Self {
+            id: team.id,
+            name: team.name,
         }
     }
 }
@@ -149,6 +506,10 @@ pub struct Assignment {
     // Composite ID encoded as string (resource:project:YYYY-MM)
     pub id: String,
     pub month: MonthScalar,
+    /// What the resource does on the project, e.g. "Tech Lead".
+    pub role: Option<String>,
+    pub note: Option<String>,
+    pub status: AssignmentStatus,
     // Backing fields (not exposed) for resolvers
     #[graphql(skip)]
     pub resource_id: Uuid,
@@ -156,20 +517,53 @@ pub struct Assignment {
     pub project_id: Uuid,
 }
 
-impl From<domain::Assignment> for Assignment {
-    fn from(v: domain::Assignment) -> Self {
-        let month = MonthScalar {
-            year: v.year,
-            // domain::Assignment guarantees 1..=12
-            month: u8::try_from(v.month).expect("Assignment.month must be 1..=12"),
-        };
-        let id = format!("{}:{}:{}", v.resource_id, v.project_id, month.as_str());
-        Self {
+#[derive(Enum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AssignmentStatus {
+    /// Pencilled in, pending `confirmAssignments`.
+    Tentative,
+    #[default]
+    Confirmed,
+}
+
+impl From<domain::AssignmentStatus> for AssignmentStatus {
+    fn from(v: domain::AssignmentStatus) -> Self {
+        match v {
+            domain::AssignmentStatus::Tentative => Self::Tentative,
+            domain::AssignmentStatus::Confirmed => Self::Confirmed,
+        }
+    }
+}
+
+impl From<AssignmentStatus> for domain::AssignmentStatus {
+    fn from(v: AssignmentStatus) -> Self {
+        match v {
+            AssignmentStatus::Tentative => Self::Tentative,
+            AssignmentStatus::Confirmed => Self::Confirmed,
+        }
+    }
+}
+
+impl TryFrom<domain::Assignment> for Assignment {
+    type Error = async_graphql::Error;
+
+    /// Fails for a month outside 1..=12, which the database doesn't rule out.
+    fn try_from(v: domain::Assignment) -> Result<Self, Self::Error> {
+        let month = u8::try_from(v.month)
+            .ok()
+            .and_then(|month| MonthScalar::new(v.year, month))
+            .ok_or_else(|| {
+                async_graphql::Error::new(format!("Assignment has invalid month {}", v.month))
+            })?;
+        let id = format!("{}:{}:{month}", v.resource_id, v.project_id);
+        Ok(Self {
             id,
             month,
+            role: v.role,
+            note: v.note,
+            status: v.status.into(),
             resource_id: v.resource_id,
             project_id: v.project_id,
-        }
+        })
     }
 }
 
@@ -194,12 +588,78 @@ impl Assignment {
     }
 }
 
+/// A resource booked on a project for a single week.
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(complex)]
+pub struct WeekAssignment {
+    // Composite ID encoded as string (resource:project:YYYY-Www)
+    pub id: String,
+    pub week: WeekScalar,
+    /// What the resource does on the project, e.g. "Tech Lead".
+    pub role: Option<String>,
+    pub note: Option<String>,
+    pub status: AssignmentStatus,
+    #[graphql(skip)]
+    pub resource_id: Uuid,
+    #[graphql(skip)]
+    pub project_id: Uuid,
+}
+
+impl TryFrom<domain::WeekAssignment> for WeekAssignment {
+    type Error = async_graphql::Error;
+
+    /// Fails for a week the year doesn't have, such as week 53 of 2025, which
+    /// the database doesn't rule out.
+    fn try_from(v: domain::WeekAssignment) -> Result<Self, Self::Error> {
+        let week = WeekScalar(v.iso_week().ok_or_else(|| {
+            async_graphql::Error::new(format!(
+                "Week assignment has invalid week {}-W{:02}",
+                v.year, v.week
+            ))
+        })?);
+        let id = format!("{}:{}:{week}", v.resource_id, v.project_id);
+        Ok(Self {
+            id,
+            week,
+            role: v.role,
+            note: v.note,
+            status: v.status.into(),
+            resource_id: v.resource_id,
+            project_id: v.project_id,
+        })
+    }
+}
+
+#[ComplexObject]
+impl WeekAssignment {
+    async fn project(&self, ctx: &Context<'_>) -> async_graphql::Result<Project> {
+        let dl = ctx.data_unchecked::<DataLoader<ProjectLoader>>();
+        let p = dl
+            .load_one(self.project_id)
+            .await?
+            .ok_or_else(|| async_graphql::Error::new("Project not found"))?;
+        Ok(p.into())
+    }
+
+    async fn resource(&self, ctx: &Context<'_>) -> async_graphql::Result<Resource> {
+        let dataloader = ctx.data_unchecked::<DataLoader<crate::gql::loader::ResourceLoader>>();
+        let r = dataloader
+            .load_one(self.resource_id)
+            .await?
+            .ok_or_else(|| async_graphql::Error::new("Resource not found"))?;
+        Ok(r.into())
+    }
+}
+
 // AssignmentFilter removed from public API
 
 // Inputs per simplified API
 #[derive(InputObject, Clone, Debug)]
 pub struct CreateResourceInput {
     pub name: String,
+    /// Defaults to `PERSON`.
+    #[graphql(default)]
+    pub kind: ResourceKind,
 }
 
 #[derive(InputObject, Clone, Debug)]
@@ -207,6 +667,20 @@ pub struct CreateProjectInput {
     pub name: String,
 }
 
+/// Replaces a project's details; an omitted colour or description is cleared.
+#[derive(InputObject, Clone, Debug)]
+pub struct ProjectDetailsInput {
+    pub status: ProjectStatus,
+    /// Display colour as `#rrggbb`.
+    pub colour: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(InputObject, Clone, Debug)]
+pub struct CreateTeamInput {
+    pub name: String,
+}
+
 #[derive(InputObject, Clone, Debug)]
 pub struct AssignInput {
     #[graphql(name = "resourceId")]
@@ -214,6 +688,10 @@ pub struct AssignInput {
     #[graphql(name = "projectId")]
     pub project_id: Uuid,
     pub month: MonthScalar,
+    pub role: Option<String>,
+    pub note: Option<String>,
+    #[graphql(default)]
+    pub status: AssignmentStatus,
 }
 
 #[derive(InputObject, Clone, Debug)]
@@ -225,42 +703,526 @@ pub struct UnassignInput {
     pub month: MonthScalar,
 }
 
+#[derive(InputObject, Clone, Debug)]
+pub struct AssignWeekInput {
+    #[graphql(name = "resourceId")]
+    pub resource_id: Uuid,
+    #[graphql(name = "projectId")]
+    pub project_id: Uuid,
+    pub week: WeekScalar,
+    pub role: Option<String>,
+    pub note: Option<String>,
+    #[graphql(default)]
+    pub status: AssignmentStatus,
+}
+
+#[derive(InputObject, Clone, Debug)]
+pub struct UnassignWeekInput {
+    #[graphql(name = "resourceId")]
+    pub resource_id: Uuid,
+    #[graphql(name = "projectId")]
+    pub project_id: Uuid,
+    pub week: WeekScalar,
+}
+
+#[derive(Enum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Viewer,
+    Planner,
+    Admin,
+}
+
+impl From<domain::Role> for Role {
+    fn from(v: domain::Role) -> Self {
+        match v {
+            domain::Role::Viewer => Self::Viewer,
+            domain::Role::Planner => Self::Planner,
+            domain::Role::Admin => Self::Admin,
+        }
+    }
+}
+
+impl From<Role> for domain::Role {
+    fn from(v: Role) -> Self {
+        match v {
+            Role::Viewer => Self::Viewer,
+            Role::Planner => Self::Planner,
+            Role::Admin => Self::Admin,
+        }
+    }
+}
+
 // Table-optimized structures (legacy flat shapes) removed in favor of matrix types
 
 // ----------------------------
 // Matrix (pivoted) structures per API_DESIGN.graphql
 // ----------------------------
 
+/// Restricts which rows a month or week matrix returns; all conditions must hold.
+#[derive(InputObject, Clone, Debug, Default)]
+pub struct MatrixRowFilter {
+    /// Only rows for these project or resource ids.
+    pub ids: Option<Vec<Uuid>>,
+    /// Only rows whose name contains this text, ignoring the case of ASCII letters.
+    pub search: Option<String>,
+    /// Only projects with one of these statuses; resource rows ignore it.
+    pub statuses: Option<Vec<ProjectStatus>>,
+    /// Only rows with at least one assignment in the requested months or weeks.
+    #[graphql(name = "assignedOnly", default)]
+    pub assigned_only: bool,
+}
+
+#[derive(Enum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MatrixSortField {
+    #[default]
+    Name,
+    /// Number of assignments in the requested months or weeks.
+    Allocation,
+}
+
+/// Row order of a month or week matrix. Ties are broken by name.
+#[derive(InputObject, Clone, Debug, Default)]
+pub struct MatrixSort {
+    #[graphql(default)]
+    pub by: MatrixSortField,
+    #[graphql(default)]
+    pub descending: bool,
+}
+
+/// Position of a matrix row in its sort order, handed out as an opaque cursor.
+/// It records the sort it was issued for along with the row's sort key, so a
+/// page picks up after that row even if rows before it were added or removed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RowCursor {
+    descending: bool,
+    /// The row's allocation, when sorted by it.
+    allocation: Option<usize>,
+    name: String,
+    id: Uuid,
+}
+
+impl RowCursor {
+    #[must_use]
+    pub fn new(sort: &MatrixSort, allocation: usize, name: &str, id: Uuid) -> Self {
+        Self {
+            descending: sort.descending,
+            allocation: (sort.by == MatrixSortField::Allocation).then_some(allocation),
+            name: name.to_string(),
+            id,
+        }
+    }
+
+    /// Whether the cursor was issued for a matrix sorted by `sort`.
+    #[must_use]
+    pub fn fits(&self, sort: &MatrixSort) -> bool {
+        self.descending == sort.descending
+            && self.allocation.is_some() == (sort.by == MatrixSortField::Allocation)
+    }
+
+    /// The row's `(name, id)`, which orders rows sorted by name.
+    #[must_use]
+    pub fn name_key(&self) -> (String, Uuid) {
+        (self.name.clone(), self.id)
+    }
+
+    /// Compares the rows of two cursors issued for the same sort, in that
+    /// sort's order. Ties are broken by name, then id.
+    #[must_use]
+    pub fn cmp_rows(&self, other: &Self) -> Ordering {
+        let by_name = (self.name.as_str(), self.id).cmp(&(other.name.as_str(), other.id));
+        let ord = match (self.allocation, other.allocation) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => by_name,
+        };
+        let ord = if self.descending { ord.reverse() } else { ord };
+        ord.then(by_name)
+    }
+}
+
+impl CursorType for RowCursor {
+    type Error = &'static str;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        let json = URL_SAFE_NO_PAD.decode(s).map_err(|_| "Invalid cursor")?;
+        serde_json::from_slice(&json).map_err(|_| "Invalid cursor")
+    }
+
+    fn encode_cursor(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+}
+
+/// Fields a page of matrix rows carries besides its edges.
+#[derive(SimpleObject, Clone, Debug)]
+pub struct RowCount {
+    /// Number of rows matching the filter, across all pages.
+    #[graphql(name = "totalCount")]
+    pub total_count: usize,
+}
+
+/// A page of matrix rows.
+pub type RowConnection<Row> = Connection<RowCursor, Row, RowCount>;
+
 #[derive(SimpleObject, Clone)]
 pub struct ProjectMonthCell {
     pub resources: Vec<Resource>,
+    /// `resources` with a confirmed assignment in the cell.
+    #[graphql(name = "confirmedResources")]
+    pub confirmed_resources: Vec<Resource>,
+    /// `resources` that are only pencilled in.
+    #[graphql(name = "tentativeResources")]
+    pub tentative_resources: Vec<Resource>,
+    /// The cell's assignments, carrying each resource's role and note.
+    pub assignments: Vec<Assignment>,
 }
 
 #[derive(SimpleObject, Clone)]
 pub struct ProjectMonthMatrixRow {
     pub project: Project,
     pub cells: Vec<ProjectMonthCell>,
+    /// Resources assigned in the month before the first requested month.
+    #[graphql(name = "prevCount")]
+    pub prev_count: usize,
+    /// Resources assigned in the month after the last requested month.
+    #[graphql(name = "nextCount")]
+    pub next_count: usize,
 }
 
-#[derive(SimpleObject, Clone)]
+#[derive(SimpleObject)]
 pub struct ProjectMonthMatrix {
     pub months: Vec<MonthScalar>,
-    pub rows: Vec<ProjectMonthMatrixRow>,
+    /// The requested page of rows.
+    pub rows: RowConnection<ProjectMonthMatrixRow>,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct ProjectWeekCell {
+    pub resources: Vec<Resource>,
+    /// `resources` with a confirmed assignment in the cell.
+    #[graphql(name = "confirmedResources")]
+    pub confirmed_resources: Vec<Resource>,
+    /// `resources` that are only pencilled in.
+    #[graphql(name = "tentativeResources")]
+    pub tentative_resources: Vec<Resource>,
+    /// The cell's week assignments, carrying each resource's role and note.
+    pub assignments: Vec<WeekAssignment>,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct ProjectWeekMatrixRow {
+    pub project: Project,
+    pub cells: Vec<ProjectWeekCell>,
+}
+
+#[derive(SimpleObject)]
+pub struct ProjectWeekMatrix {
+    pub weeks: Vec<WeekScalar>,
+    /// The requested page of rows.
+    pub rows: RowConnection<ProjectWeekMatrixRow>,
 }
 
 #[derive(SimpleObject, Clone)]
 pub struct ResourceMonthCell {
     pub projects: Vec<Project>,
+    /// The cell's assignments, carrying the resource's role and note on each project.
+    pub assignments: Vec<Assignment>,
+    /// Percentage of the month the resource can work; 100 unless overridden.
+    pub capacity: i32,
+    /// `capacity` less a full month per monthly assignment and the booked share
+    /// of the month for week assignments; negative when overbooked.
+    #[graphql(name = "remainingCapacity")]
+    pub remaining_capacity: i32,
+    /// Like `remainingCapacity`, counting confirmed assignments only.
+    #[graphql(name = "remainingConfirmedCapacity")]
+    pub remaining_confirmed_capacity: i32,
+    /// Weekdays in the month that aren't holidays in the resource's region.
+    #[graphql(name = "workingDays")]
+    pub working_days: i32,
+    /// Person-days the resource can work: `capacity` of `workingDays`.
+    #[graphql(name = "availableDays")]
+    pub available_days: f64,
+    /// Person-days booked: the booked share of `workingDays` per assignment.
+    #[graphql(name = "plannedDays")]
+    pub planned_days: f64,
 }
 
 #[derive(SimpleObject, Clone)]
 pub struct ResourceMonthMatrixRow {
     pub resource: Resource,
     pub cells: Vec<ResourceMonthCell>,
+    /// Projects assigned in the month before the first requested month.
+    #[graphql(name = "prevCount")]
+    pub prev_count: usize,
+    /// Projects assigned in the month after the last requested month.
+    #[graphql(name = "nextCount")]
+    pub next_count: usize,
 }
 
-#[derive(SimpleObject, Clone)]
+#[derive(SimpleObject)]
 pub struct ResourceMonthMatrix {
     pub months: Vec<MonthScalar>,
-    pub rows: Vec<ResourceMonthMatrixRow>,
+    /// The requested page of rows.
+    pub rows: RowConnection<ResourceMonthMatrixRow>,
+}
+
+/// A team's assignments and capacity in one month, added up across its members.
+#[derive(SimpleObject, Clone, Default)]
+pub struct TeamMonthCell {
+    /// Members assigned in the month, ordered by name.
+    pub resources: Vec<Resource>,
+    /// `resources` with a confirmed assignment in the month.
+    #[graphql(name = "confirmedResources")]
+    pub confirmed_resources: Vec<Resource>,
+    /// `resources` that are only pencilled in.
+    #[graphql(name = "tentativeResources")]
+    pub tentative_resources: Vec<Resource>,
+    /// Projects any member is assigned to, ordered by name.
+    pub projects: Vec<Project>,
+    pub assignments: Vec<Assignment>,
+    /// Sum of the members' `capacity`, e.g. 300 for three full-time members.
+    pub capacity: i32,
+    /// Sum of the members' `remainingCapacity`.
+    #[graphql(name = "remainingCapacity")]
+    pub remaining_capacity: i32,
+    /// Sum of the members' `remainingConfirmedCapacity`.
+    #[graphql(name = "remainingConfirmedCapacity")]
+    pub remaining_confirmed_capacity: i32,
+    /// Person-days the members can work, by their regions' working days.
+    #[graphql(name = "availableDays")]
+    pub available_days: f64,
+    /// Person-days booked across the members.
+    #[graphql(name = "plannedDays")]
+    pub planned_days: f64,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct TeamMonthMatrixRow {
+    pub team: Team,
+    pub cells: Vec<TeamMonthCell>,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct TeamMonthMatrix {
+    pub months: Vec<MonthScalar>,
+    pub rows: Vec<TeamMonthMatrixRow>,
+}
+
+// ----------------------------
+// Aggregates
+// ----------------------------
+
+/// Planned spend on a project, from its assignments and resource cost rates.
+#[derive(SimpleObject, Clone)]
+pub struct ProjectCost {
+    pub project: Project,
+    pub budget: Option<i64>,
+    /// Planned spend across every assigned month, requested or not.
+    #[graphql(name = "totalPlanned")]
+    pub total_planned: i64,
+    pub months: Vec<ProjectCostMonth>,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct ProjectCostMonth {
+    pub month: MonthScalar,
+    /// Sum of the rates of every resource assigned in the month, prorated for
+    /// week assignments by their share of the month.
+    pub planned: i64,
+    /// Planned spend up to and including the month.
+    pub cumulative: i64,
+    /// Budget left after `cumulative`; negative once overspent.
+    pub remaining: Option<i64>,
+    /// Assignments in the month whose resource has no rate yet; they count as zero.
+    #[graphql(name = "unratedCount")]
+    pub unrated_count: usize,
+}
+
+/// Planned against actual effort on a project, per resource and month.
+#[derive(SimpleObject, Clone)]
+pub struct PlanVsActual {
+    pub project: Project,
+    pub months: Vec<MonthScalar>,
+    /// Resources planned or recorded in any of `months`, sorted by name.
+    pub rows: Vec<PlanVsActualRow>,
+    /// Sums across every row, one per month.
+    pub totals: Vec<EffortComparison>,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct PlanVsActualRow {
+    pub resource: Resource,
+    /// One per requested month, in order.
+    pub cells: Vec<EffortComparison>,
+    /// Sum of `cells`.
+    pub total: EffortComparison,
+}
+
+/// Effort as a percentage of a month; a planned assignment counts as 100.
+#[derive(SimpleObject, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EffortComparison {
+    pub planned: i32,
+    pub actual: i32,
+    /// `actual - planned`; positive when more was spent than planned.
+    pub variance: i32,
+    /// `planned` in person-days, using the resource's working-day calendar.
+    #[graphql(name = "plannedDays")]
+    pub planned_days: f64,
+    /// `actual` in person-days, using the resource's working-day calendar.
+    #[graphql(name = "actualDays")]
+    pub actual_days: f64,
+}
+
+impl EffortComparison {
+    /// Compares efforts in a month with `working_days` working days.
+    #[must_use]
+    pub fn new(planned: i32, actual: i32, working_days: i32) -> Self {
+        Self {
+            planned,
+            actual,
+            variance: actual - planned,
+            planned_days: workdays::person_days(planned, working_days),
+            actual_days: workdays::person_days(actual, working_days),
+        }
+    }
+
+    #[must_use]
+    pub fn add(self, other: &Self) -> Self {
+        Self {
+            planned: self.planned + other.planned,
+            actual: self.actual + other.actual,
+            variance: self.variance + other.variance,
+            planned_days: self.planned_days + other.planned_days,
+            actual_days: self.actual_days + other.actual_days,
+        }
+    }
+}
+
+#[derive(Enum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UtilisationGroupBy {
+    /// Resources assigned to each project in each month.
+    Project,
+    /// Months booked by each resource.
+    Resource,
+    /// Distinct resources assigned in each month.
+    Month,
+}
+
+impl From<UtilisationGroupBy> for domain::UtilisationGroup {
+    fn from(v: UtilisationGroupBy) -> Self {
+        match v {
+            UtilisationGroupBy::Project => Self::Project,
+            UtilisationGroupBy::Resource => Self::Resource,
+            UtilisationGroupBy::Month => Self::Month,
+        }
+    }
+}
+
+/// An assignment count; only the fields being grouped by are set.
+#[derive(SimpleObject, Clone)]
+#[graphql(complex)]
+pub struct Utilisation {
+    pub month: Option<MonthScalar>,
+    pub count: i64,
+    #[graphql(skip)]
+    pub project_id: Option<Uuid>,
+    #[graphql(skip)]
+    pub resource_id: Option<Uuid>,
+}
+
+impl TryFrom<domain::Utilisation> for Utilisation {
+    type Error = async_graphql::Error;
+
+    /// Fails for a month outside 1..=12, which the database doesn't rule out.
+    fn try_from(v: domain::Utilisation) -> Result<Self, Self::Error> {
+        let month = v
+            .year
+            .zip(v.month)
+            .map(|(year, month)| {
+                u8::try_from(month)
+                    .ok()
+                    .and_then(|m| MonthScalar::new(year, m))
+                    .ok_or_else(|| {
+                        async_graphql::Error::new(format!("Utilisation has invalid month {month}"))
+                    })
+            })
+            .transpose()?;
+        Ok(Self {
+            month,
+            count: v.count,
+            project_id: v.project_id,
+            resource_id: v.resource_id,
+        })
+    }
+}
+
+#[ComplexObject]
+impl Utilisation {
+    async fn project(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Project>> {
+        let Some(id) = self.project_id else {
+            return Ok(None);
+        };
+        let dl = ctx.data_unchecked::<DataLoader<ProjectLoader>>();
+        Ok(dl.load_one(id).await?.map(Into::into))
+    }
+
+    async fn resource(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Resource>> {
+        let Some(id) = self.resource_id else {
+            return Ok(None);
+        };
+        let dl = ctx.data_unchecked::<DataLoader<crate::gql::loader::ResourceLoader>>();
+        Ok(dl.load_one(id).await?.map(Into::into))
+    }
+}
+
+// ----------------------------
+// Bulk import
+// ----------------------------
+
+#[derive(SimpleObject, Clone)]
+pub struct ImportRowIssue {
+    pub line: u64,
+    pub message: String,
+}
+
+impl From<import::RowIssue> for ImportRowIssue {
+    fn from(v: import::RowIssue) -> Self {
+        Self {
+            line: v.line,
+            message: v.message,
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct ImportReport {
+    #[graphql(name = "dryRun")]
+    pub dry_run: bool,
+    pub imported: usize,
+    #[graphql(name = "createdProjects")]
+    pub created_projects: Vec<Project>,
+    #[graphql(name = "createdResources")]
+    pub created_resources: Vec<Resource>,
+    pub skipped: Vec<ImportRowIssue>,
+    pub errors: Vec<ImportRowIssue>,
+}
+
+impl ImportReport {
+    pub fn new(report: import::ImportReport, dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            imported: report.imported,
+            created_projects: report
+                .created_projects
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            created_resources: report
+                .created_resources
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            skipped: report.skipped.into_iter().map(Into::into).collect(),
+            errors: report.errors.into_iter().map(Into::into).collect(),
+        }
+    }
 }
@@ -0,0 +1,418 @@
+//! Bulk import of assignments from `project,resource,month` CSV, and of
+//! actuals from `project,resource,month,effort` CSV.
+
+use crate::{
+    domain,
+    repo::{AssignmentFilter, Repository},
+};
+use std::collections::{HashMap, HashSet, hash_map::Entry};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Default)]
+pub struct ImportOptions {
+    /// Create projects and resources referenced by name that don't exist yet.
+    /// Ignored for actuals, which must refer to existing rows.
+    pub create_missing: bool,
+    /// Validate and report without writing anything.
+    pub dry_run: bool,
+    /// Restrict rows to projects managed by this user. Projects created by the
+    /// import are made managed by them. `None` allows every project.
+    pub manager: Option<String>,
+}
+
+/// A row that was not imported, identified by its 1-based line number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowIssue {
+    pub line: u64,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Number of new assignments, or of actuals, written (or that would be,
+    /// for a dry run).
+    pub imported: usize,
+    pub created_projects: Vec<domain::Project>,
+    pub created_resources: Vec<domain::Resource>,
+    /// Rows that were valid but had nothing to do.
+    pub skipped: Vec<RowIssue>,
+    pub errors: Vec<RowIssue>,
+}
+
+/// Imports `csv` through [`Repository::apply_batch`], so that all valid rows are
+/// written in a single transaction.
+///
+/// Each row names a project and a resource by UUID or by exact name, and a
+/// month as `YYYY-MM`. An optional `project,resource,month` header is ignored.
+/// Invalid rows are reported and left out; they don't prevent the rest of the
+/// file from being imported.
+///
+/// # Errors
+///
+/// Returns an error if reading from or writing to the repository fails.
+pub async fn import_csv(
+    repo: &dyn Repository,
+    csv: &str,
+    options: &ImportOptions,
+) -> sqlx::Result<ImportReport> {
+    run(repo, csv, options, Format::Assignments).await
+}
+
+/// Imports actuals from `csv` through [`Repository::apply_batch`], like
+/// [`import_csv`].
+///
+/// Each row names an existing project and resource, a month as `YYYY-MM` and
+/// the effort spent as a whole percentage of that month. An optional
+/// `project,resource,month,effort` header is ignored. Rows for a month that
+/// already has an actual replace its effort.
+///
+/// # Errors
+///
+/// Returns an error if reading from or writing to the repository fails.
+pub async fn import_actuals_csv(
+    repo: &dyn Repository,
+    csv: &str,
+    options: &ImportOptions,
+) -> sqlx::Result<ImportReport> {
+    let options = ImportOptions {
+        create_missing: false,
+        ..options.clone()
+    };
+    run(repo, csv, &options, Format::Actuals).await
+}
+
+async fn run(
+    repo: &dyn Repository,
+    csv: &str,
+    options: &ImportOptions,
+    format: Format,
+) -> sqlx::Result<ImportReport> {
+    let mut importer = Importer::new(repo, options, format).await?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(csv.as_bytes());
+
+    for (index, record) in reader.records().enumerate() {
+        let (line, outcome) = match record {
+            Ok(record) if index == 0 && is_header(&record, format.columns()) => continue,
+            Ok(record) => {
+                let line = record.position().map_or(0, csv::Position::line);
+                (line, importer.row(line, &record).await?)
+            }
+            Err(e) => {
+                let line = e.position().map_or(0, csv::Position::line);
+                (line, Outcome::Error(e.to_string()))
+            }
+        };
+        match outcome {
+            Outcome::Assign(assignment) => importer.batch.assignments.push(assignment),
+            Outcome::Record(actual) => importer.batch.actuals.push(actual),
+            Outcome::Skip(message) => importer.report.skipped.push(RowIssue { line, message }),
+            Outcome::Error(message) => importer.report.errors.push(RowIssue { line, message }),
+        }
+    }
+
+    let Importer {
+        mut report,
+        mut batch,
+        ..
+    } = importer;
+    report.imported = batch.assignments.len() + batch.actuals.len();
+    if let Some(user) = &options.manager {
+        // Granted in the same transaction, so created projects are never left unmanaged
+        batch.project_managers = report
+            .created_projects
+            .iter()
+            .map(|p| (p.id, user.clone()))
+            .collect();
+    }
+    if !options.dry_run && batch != domain::Batch::default() {
+        repo.apply_batch(&batch).await?;
+    }
+    Ok(report)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Assignments,
+    Actuals,
+}
+
+impl Format {
+    const fn columns(self) -> &'static [&'static str] {
+        match self {
+            Self::Assignments => &["project", "resource", "month"],
+            Self::Actuals => &["project", "resource", "month", "effort"],
+        }
+    }
+}
+
+enum Outcome {
+    Assign(domain::Assignment),
+    Record(domain::Actual),
+    Skip(String),
+    Error(String),
+}
+
+/// Running state of an import: name lookups and the batch built so far.
+struct Importer<'a> {
+    repo: &'a dyn Repository,
+    options: &'a ImportOptions,
+    format: Format,
+    projects: Names,
+    resources: Names,
+    /// Whether `options.manager` manages each project checked so far.
+    managed: HashMap<Uuid, bool>,
+    /// Existing `(resource, year, month)` assignments per project checked so far.
+    existing: HashMap<Uuid, HashSet<(Uuid, i32, i32)>>,
+    /// First line each assignment or actual appeared on.
+    seen: HashMap<(Uuid, Uuid, i32, i32), u64>,
+    report: ImportReport,
+    batch: domain::Batch,
+}
+
+impl<'a> Importer<'a> {
+    async fn new(
+        repo: &'a dyn Repository,
+        options: &'a ImportOptions,
+        format: Format,
+    ) -> sqlx::Result<Self> {
+        let projects = repo.list_projects().await?;
+        let resources = repo.list_resources().await?;
+        Ok(Self {
+            repo,
+            options,
+            format,
+            projects: Names::new(projects.into_iter().map(|p| (p.id, p.name))),
+            resources: Names::new(resources.into_iter().map(|r| (r.id, r.name))),
+            managed: HashMap::new(),
+            existing: HashMap::new(),
+            seen: HashMap::new(),
+            report: ImportReport::default(),
+            batch: domain::Batch::default(),
+        })
+    }
+
+    async fn row(&mut self, line: u64, record: &csv::StringRecord) -> sqlx::Result<Outcome> {
+        let create_missing = self.options.create_missing;
+        let columns = self.format.columns();
+        let (true, Some(project), Some(resource), Some(month)) = (
+            record.len() == columns.len(),
+            record.get(0),
+            record.get(1),
+            record.get(2),
+        ) else {
+            return Ok(Outcome::Error(format!(
+                "Expected {} fields: {}",
+                columns.len(),
+                columns.join(",")
+            )));
+        };
+        let month: domain::YearMonth = match month.parse() {
+            Ok(m) => m,
+            Err(e) => return Ok(Outcome::Error(e.into())),
+        };
+        let resolved = self
+            .projects
+            .resolve(project, "project", create_missing)
+            .and_then(|p| {
+                Ok((
+                    p,
+                    self.resources
+                        .resolve(resource, "resource", create_missing)?,
+                ))
+            });
+        let (project, resource) = match resolved {
+            Ok(v) => v,
+            Err(e) => return Ok(Outcome::Error(e)),
+        };
+        if let Resolved::Existing(project_id) = project
+            && !self.can_manage(project_id).await?
+        {
+            return Ok(Outcome::Error(
+                match self.format {
+                    Format::Assignments => "Only the project's managers can change its assignments",
+                    Format::Actuals => "Only the project's managers can record its actuals",
+                }
+                .into(),
+            ));
+        }
+
+        let project_id = self.projects.commit(project, |id, name| {
+            let p = domain::Project {
+                id,
+                name,
+                parent_id: None,
+                budget: None,
+                status: domain::ProjectStatus::default(),
+                colour: None,
+                description: None,
+            };
+            self.batch.projects.push(p.clone());
+            self.report.created_projects.push(p);
+        });
+        let resource_id = self.resources.commit(resource, |id, name| {
+            let r = domain::Resource {
+                id,
+                name,
+                team_id: None,
+                kind: domain::ResourceKind::Person,
+                region: None,
+            };
+            self.batch.resources.push(r.clone());
+            self.report.created_resources.push(r);
+        });
+        let key = (resource_id, project_id, month.year, i32::from(month.month));
+        if let Some(first) = self.seen.get(&key) {
+            return Ok(Outcome::Skip(format!("Duplicate of line {first}")));
+        }
+        if self.format == Format::Actuals {
+            // The field count was checked above.
+            let outcome = actual(key, &record[3]);
+            if matches!(outcome, Outcome::Record(_)) {
+                self.seen.insert(key, line);
+            }
+            return Ok(outcome);
+        }
+        self.seen.insert(key, line);
+
+        let assignment = domain::Assignment {
+            resource_id,
+            project_id,
+            year: month.year,
+            month: i32::from(month.month),
+            role: None,
+            note: None,
+            status: domain::AssignmentStatus::Confirmed,
+        };
+        if self.already_assigned(&assignment).await? {
+            return Ok(Outcome::Skip("Already assigned".into()));
+        }
+        Ok(Outcome::Assign(assignment))
+    }
+
+    async fn can_manage(&mut self, project_id: Uuid) -> sqlx::Result<bool> {
+        let Some(user) = &self.options.manager else {
+            return Ok(true);
+        };
+        if let Some(allowed) = self.managed.get(&project_id) {
+            return Ok(*allowed);
+        }
+        let allowed = self.repo.is_project_manager(project_id, user).await?;
+        self.managed.insert(project_id, allowed);
+        Ok(allowed)
+    }
+
+    async fn already_assigned(&mut self, a: &domain::Assignment) -> sqlx::Result<bool> {
+        if self.projects.is_new(a.project_id) {
+            return Ok(false);
+        }
+        let existing = match self.existing.entry(a.project_id) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let filter = AssignmentFilter {
+                    project_id: Some(a.project_id),
+                    ..Default::default()
+                };
+                let rows = self.repo.list_assignments(&filter).await?;
+                e.insert(
+                    rows.into_iter()
+                        .map(|a| (a.resource_id, a.year, a.month))
+                        .collect(),
+                )
+            }
+        };
+        Ok(existing.contains(&(a.resource_id, a.year, a.month)))
+    }
+}
+
+fn is_header(record: &csv::StringRecord, columns: &[&str]) -> bool {
+    record.len() == columns.len()
+        && record
+            .iter()
+            .zip(columns)
+            .all(|(field, name)| field.eq_ignore_ascii_case(name))
+}
+
+fn actual((resource_id, project_id, year, month): (Uuid, Uuid, i32, i32), effort: &str) -> Outcome {
+    match effort.parse::<i32>() {
+        Ok(effort) if effort >= 0 => Outcome::Record(domain::Actual {
+            resource_id,
+            project_id,
+            year,
+            month,
+            effort,
+        }),
+        _ => Outcome::Error(format!(
+            "Effort '{effort}' is not a whole, non-negative percentage"
+        )),
+    }
+}
+
+enum Resolved {
+    Existing(Uuid),
+    New(String),
+}
+
+/// Lookup of a table's rows by id and by name, including rows created so far.
+struct Names {
+    ids: HashSet<Uuid>,
+    by_name: HashMap<String, Vec<Uuid>>,
+    created: HashSet<Uuid>,
+}
+
+impl Names {
+    fn new(rows: impl Iterator<Item = (Uuid, String)>) -> Self {
+        let mut names = Self {
+            ids: HashSet::new(),
+            by_name: HashMap::new(),
+            created: HashSet::new(),
+        };
+        for (id, name) in rows {
+            names.ids.insert(id);
+            names.by_name.entry(name).or_default().push(id);
+        }
+        names
+    }
+
+    fn resolve(&self, value: &str, kind: &str, create_missing: bool) -> Result<Resolved, String> {
+        if value.is_empty() {
+            return Err(format!("Missing {kind}"));
+        }
+        if let Ok(id) = Uuid::parse_str(value) {
+            return if self.ids.contains(&id) {
+                Ok(Resolved::Existing(id))
+            } else {
+                Err(format!("Unknown {kind} id {id}"))
+            };
+        }
+        match self.by_name.get(value).map(Vec::as_slice) {
+            Some([id]) => Ok(Resolved::Existing(*id)),
+            Some(_) => Err(format!("Ambiguous {kind} name '{value}'; use its id")),
+            None if create_missing => Ok(Resolved::New(value.to_string())),
+            None => Err(format!("Unknown {kind} '{value}'")),
+        }
+    }
+
+    /// Returns the id for `resolved`, allocating one and calling `create` for new rows.
+    fn commit(&mut self, resolved: Resolved, create: impl FnOnce(Uuid, String)) -> Uuid {
+        match resolved {
+            Resolved::Existing(id) => id,
+            Resolved::New(name) => {
+                let id = Uuid::new_v4();
+                self.ids.insert(id);
+                self.created.insert(id);
+                self.by_name.insert(name.clone(), vec![id]);
+                create(id, name);
+                id
+            }
+        }
+    }
+
+    fn is_new(&self, id: Uuid) -> bool {
+        self.created.contains(&id)
+    }
+}
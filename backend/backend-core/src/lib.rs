@@ -1,7 +1,24 @@
+mod auth;
+pub mod backup;
+pub mod calendar;
 mod domain;
+pub mod export;
 mod gql;
+pub mod import;
 mod repo;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod workdays;
 
-pub use domain::Assignment as DomainAssignment;
-pub use gql::build_schema;
-pub use repo::{AssignmentFilter, Repository, SqliteRepository};
+pub use auth::CurrentUser;
+pub use domain::{
+    Actual, Assignment as DomainAssignment, AssignmentStatus, AuditEntry, Batch, Capacity,
+    CostRate, IsoWeek, ProjectStatus, ResourceKind, Role, Team, WeekAssignment, YearMonth,
+};
+pub use gql::{
+    build_read_only_schema, build_read_only_schema_with_calendars, build_schema,
+    build_schema_with_calendars,
+};
+pub use repo::{
+    AssignmentFilter, CachedRepository, Repository, RowFilter, SqliteRepository, WeekFilter,
+};
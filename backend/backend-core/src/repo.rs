@@ -4,13 +4,17 @@ use crate::domain;
 use async_trait::async_trait;
 use sqlx::{
     QueryBuilder, Sqlite, SqlitePool,
-    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    query::Query,
+    sqlite::{SqliteArguments, SqliteConnectOptions, SqlitePoolOptions},
 };
 use std::{str::FromStr, sync::Arc};
 use uuid::Uuid;
 
+mod cached;
 mod dto;
 
+pub use cached::CachedRepository;
+
 pub type Repo = Arc<dyn Repository>;
 
 // Domain-level filtering inputs for assignment queries
@@ -22,30 +26,186 @@ pub struct AssignmentFilter {
     pub month: Option<i32>, // 1..=12
 }
 
+/// Filtering inputs for week assignment queries. `from` and `to` bound the
+/// weeks inclusively.
+#[derive(Clone, Debug, Default)]
+pub struct WeekFilter {
+    pub resource_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub from: Option<domain::IsoWeek>,
+    pub to: Option<domain::IsoWeek>,
+}
+
+/// Filtering, ordering and limits for project and resource row queries. Rows
+/// come ordered by name then id, or the reverse for `descending`.
+#[derive(Clone, Debug, Default)]
+pub struct RowFilter {
+    pub ids: Option<Vec<Uuid>>,
+    /// Only rows whose name contains this text, ignoring the case of ASCII letters.
+    pub search: Option<String>,
+    /// Only projects with one of these statuses; resources ignore it.
+    pub statuses: Option<Vec<domain::ProjectStatus>>,
+    /// Only projects without a parent; resources ignore it.
+    pub top_level: bool,
+    pub descending: bool,
+    /// Only rows after this `(name, id)` in the order above.
+    pub after: Option<(String, Uuid)>,
+    pub limit: Option<usize>,
+}
+
 #[async_trait]
 pub trait Repository: Send + Sync {
     async fn list_projects(&self) -> sqlx::Result<Vec<domain::Project>>;
     async fn list_resources(&self) -> sqlx::Result<Vec<domain::Resource>>;
+    /// Returns the projects matching `filter`, in its order.
+    async fn find_projects(&self, filter: &RowFilter) -> sqlx::Result<Vec<domain::Project>>;
+    /// Counts the projects matching `filter`, ignoring its `after` and `limit`.
+    async fn count_projects(&self, filter: &RowFilter) -> sqlx::Result<usize>;
+    /// Returns the resources matching `filter`, in its order.
+    async fn find_resources(&self, filter: &RowFilter) -> sqlx::Result<Vec<domain::Resource>>;
+    /// Counts the resources matching `filter`, ignoring its `after` and `limit`.
+    async fn count_resources(&self, filter: &RowFilter) -> sqlx::Result<usize>;
+    /// Returns every team, ordered by name.
+    async fn list_teams(&self) -> sqlx::Result<Vec<domain::Team>>;
     async fn list_assignments(
         &self,
         filter: &AssignmentFilter,
     ) -> sqlx::Result<Vec<domain::Assignment>>;
+    /// Returns week assignments matching `filter`, ordered by week, project and
+    /// resource.
+    async fn list_week_assignments(
+        &self,
+        filter: &WeekFilter,
+    ) -> sqlx::Result<Vec<domain::WeekAssignment>>;
+    /// Returns recorded actuals matching `filter`, ordered like assignments.
+    async fn list_actuals(&self, filter: &AssignmentFilter) -> sqlx::Result<Vec<domain::Actual>>;
+    /// Counts assignments in `months` (as `(year, month)` pairs) grouped by
+    /// `group_by`, ordered by month then id. Week assignments count towards the
+    /// month holding their Thursday, once per resource, project and month.
+    /// Groups with no assignments are omitted.
+    async fn utilisation(
+        &self,
+        months: &[(i32, i32)],
+        group_by: domain::UtilisationGroup,
+    ) -> sqlx::Result<Vec<domain::Utilisation>>;
 
     async fn projects(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Project>>;
 
     async fn resources(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Resource>>;
 
+    async fn teams(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Team>>;
+
+    /// Creates or replaces `project`, including its parent, budget and details. Fails if
+    /// the parent doesn't exist.
     async fn upsert_project(&self, project: &domain::Project) -> sqlx::Result<()>;
+    /// Creates or replaces `resource`, including its team and kind. Fails if
+    /// the team doesn't exist.
     async fn upsert_resource(&self, resource: &domain::Resource) -> sqlx::Result<()>;
+    async fn upsert_team(&self, team: &domain::Team) -> sqlx::Result<()>;
 
     // Simplified API operations
     async fn create_project(&self, name: &str) -> sqlx::Result<domain::Project>;
+    /// Creates a [`domain::ResourceKind::Person`] named `name`.
     async fn create_resource(&self, name: &str) -> sqlx::Result<domain::Resource>;
+    async fn create_team(&self, name: &str) -> sqlx::Result<domain::Team>;
+    /// Deletes a project; its children become top-level projects.
     async fn delete_project(&self, id: Uuid) -> sqlx::Result<u64>;
     async fn delete_resource(&self, id: Uuid) -> sqlx::Result<u64>;
+    /// Deletes a team; its members are left without a team.
+    async fn delete_team(&self, id: Uuid) -> sqlx::Result<u64>;
+    /// Moves project `id` under `parent_id`, or to the top level for `None`.
+    /// Callers are responsible for rejecting cycles.
+    async fn set_project_parent(&self, id: Uuid, parent_id: Option<Uuid>) -> sqlx::Result<u64>;
+    /// Sets or clears the budget of project `id`.
+    async fn set_project_budget(&self, id: Uuid, budget: Option<i64>) -> sqlx::Result<u64>;
+    /// Replaces the status, colour and description of project `id`.
+    async fn set_project_details(
+        &self,
+        id: Uuid,
+        status: domain::ProjectStatus,
+        colour: Option<&str>,
+        description: Option<&str>,
+    ) -> sqlx::Result<u64>;
+    /// Moves resource `id` into `team_id`, or out of any team for `None`.
+    async fn set_resource_team(&self, id: Uuid, team_id: Option<Uuid>) -> sqlx::Result<u64>;
+    /// Sets the working-day calendar region of resource `id`, or clears it for `None`.
+    async fn set_resource_region(&self, id: Uuid, region: Option<&str>) -> sqlx::Result<u64>;
 
+    /// Creates the assignment, or replaces the role, note and status of an existing one.
     async fn assign(&self, assignment: &domain::Assignment) -> sqlx::Result<()>;
+    /// Removes the assignment with the same key, whatever its role, note and status.
     async fn unassign(&self, assignment: &domain::Assignment) -> sqlx::Result<u64>;
+    /// Creates the week assignment, or replaces the role, note and status of an
+    /// existing one.
+    async fn assign_week(&self, assignment: &domain::WeekAssignment) -> sqlx::Result<()>;
+    /// Removes the week assignment with the same key, whatever its role, note and
+    /// status.
+    async fn unassign_week(&self, assignment: &domain::WeekAssignment) -> sqlx::Result<u64>;
+    /// Moves every monthly and weekly assignment of resource `from` to resource
+    /// `to` in one transaction, returning how many `to` gained. Where `to` is
+    /// already assigned to the same project and period, its own assignment is
+    /// kept and `from`'s is dropped without being counted.
+    async fn move_assignments(&self, from: Uuid, to: Uuid) -> sqlx::Result<u64>;
+    /// Confirms the tentative assignments of `project_id` in `months` (as
+    /// `(year, month)` pairs), including weeks rolling up into them, returning
+    /// how many were confirmed.
+    async fn confirm_assignments(
+        &self,
+        project_id: Uuid,
+        months: &[(i32, i32)],
+    ) -> sqlx::Result<u64>;
+
+    /// Upserts the batch's teams, projects and resources, sets their cost rates
+    /// and capacities, grants its project managers and user roles, then assigns
+    /// its monthly and weekly assignments and records its actuals (replacing the
+    /// effort of existing ones), all or nothing.
+    async fn apply_batch(&self, batch: &domain::Batch) -> sqlx::Result<()>;
+
+    /// Returns every cost rate, ordered by resource then effective month.
+    async fn list_cost_rates(&self) -> sqlx::Result<Vec<domain::CostRate>>;
+    /// Creates the rate, or replaces the amount of the resource's rate taking
+    /// effect in the same month.
+    async fn set_cost_rate(&self, rate: &domain::CostRate) -> sqlx::Result<()>;
+    async fn delete_cost_rate(&self, resource_id: Uuid, year: i32, month: i32)
+    -> sqlx::Result<u64>;
+
+    /// Returns every capacity override, ordered by resource, year and month.
+    async fn list_capacities(&self) -> sqlx::Result<Vec<domain::Capacity>>;
+    /// Returns the overrides of `resource_ids` in `months` (as `(year, month)`
+    /// pairs), ordered like [`Self::list_capacities`].
+    async fn capacities_in(
+        &self,
+        resource_ids: &[Uuid],
+        months: &[(i32, i32)],
+    ) -> sqlx::Result<Vec<domain::Capacity>>;
+    /// Creates the override, or replaces the capacity already set for the month.
+    async fn set_capacity(&self, capacity: &domain::Capacity) -> sqlx::Result<()>;
+    /// Sets the capacity of `resource_id` in every month of `months`, or returns
+    /// them to full time for `None`, all or nothing.
+    async fn set_capacities(
+        &self,
+        resource_id: Uuid,
+        months: &[(i32, i32)],
+        percent: Option<i32>,
+    ) -> sqlx::Result<()>;
+    /// Removes a month's override, returning the resource to full time.
+    async fn delete_capacity(&self, resource_id: Uuid, year: i32, month: i32) -> sqlx::Result<u64>;
+
+    async fn record_audit(&self, entry: &domain::AuditEntry) -> sqlx::Result<()>;
+    /// Returns the audit trail, oldest entry first.
+    async fn list_audit(&self) -> sqlx::Result<Vec<domain::AuditEntry>>;
+
+    /// Returns the role granted to `user_id`, if any.
+    async fn user_role(&self, user_id: &str) -> sqlx::Result<Option<domain::Role>>;
+    async fn set_user_role(&self, user_id: &str, role: domain::Role) -> sqlx::Result<()>;
+    /// Returns every explicit role grant, ordered by user id.
+    async fn list_user_roles(&self) -> sqlx::Result<Vec<(String, domain::Role)>>;
+
+    async fn is_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<bool>;
+    async fn add_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<()>;
+    async fn remove_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<u64>;
+    /// Returns every `(project_id, user_id)` manager pair, ordered by project then user.
+    async fn list_project_managers(&self) -> sqlx::Result<Vec<(Uuid, String)>>;
 }
 
 #[derive(Clone)]
@@ -60,13 +220,40 @@ impl SqliteRepository {
     ///
     /// Returns an error if the connection cannot be established or migrations fail.
     pub async fn connect(url: &str) -> sqlx::Result<Self> {
+        let connect_opts = Self::connect_options(url)?.create_if_missing(true);
+        let pool = Self::pool(connect_opts).await?;
+
+        // Run database migrations on startup for the provided DATABASE_URL.
+        // This ensures dynamically created databases (e.g. for tests) are initialized.
+        sqlx::migrate!().run(&pool).await?;
+
+        let repo = Self { pool };
+        Ok(repo)
+    }
+
+    /// Opens an existing `SQLite` database at `url` without write access.
+    ///
+    /// Migrations are not run, so the database must already be initialised.
+    /// Every mutating repository call fails with a database error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database does not exist or cannot be opened.
+    pub async fn connect_read_only(url: &str) -> sqlx::Result<Self> {
+        let connect_opts = Self::connect_options(url)?.read_only(true);
+        let pool = Self::pool(connect_opts).await?;
+        Ok(Self { pool })
+    }
+
+    fn connect_options(url: &str) -> sqlx::Result<SqliteConnectOptions> {
         // Use connect options to ensure pragma foreign_keys=ON for every connection.
-        let connect_opts = SqliteConnectOptions::from_str(url)
+        Ok(SqliteConnectOptions::from_str(url)
             .map_err(|e| sqlx::Error::Configuration(Box::new(e)))?
-            .create_if_missing(true)
-            .foreign_keys(true);
+            .foreign_keys(true))
+    }
 
-        let pool = SqlitePoolOptions::new()
+    async fn pool(connect_opts: SqliteConnectOptions) -> sqlx::Result<SqlitePool> {
+        SqlitePoolOptions::new()
             .max_connections(5)
             .after_connect(|conn, _meta| {
                 Box::pin(async move {
@@ -78,49 +265,309 @@ impl SqliteRepository {
                 })
             })
             .connect_with(connect_opts)
-            .await?;
+            .await
+    }
+}
 
-        // Run database migrations on startup for the provided DATABASE_URL.
-        // This ensures dynamically created databases (e.g. for tests) are initialized.
-        sqlx::migrate!().run(&pool).await?;
+// Write statements shared by single-row methods and `apply_batch`
 
-        let repo = Self { pool };
-        Ok(repo)
+fn upsert_project_query(project: &domain::Project) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        "INSERT INTO projects (id, name, parent_id, budget, status, colour, description) \
+         VALUES (?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET name=excluded.name, parent_id=excluded.parent_id, \
+         budget=excluded.budget, status=excluded.status, colour=excluded.colour, \
+         description=excluded.description",
+    )
+    .bind(project.id)
+    .bind(&project.name)
+    .bind(project.parent_id)
+    .bind(project.budget)
+    .bind(project.status.as_str())
+    .bind(&project.colour)
+    .bind(&project.description)
+}
+
+fn upsert_resource_query(resource: &domain::Resource) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        "INSERT INTO resources (id, name, team_id, kind, region) VALUES (?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET name=excluded.name, team_id=excluded.team_id, \
+         kind=excluded.kind, region=excluded.region",
+    )
+    .bind(resource.id)
+    .bind(&resource.name)
+    .bind(resource.team_id)
+    .bind(resource.kind.as_str())
+    .bind(&resource.region)
+}
+
+fn upsert_team_query(team: &domain::Team) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        "INSERT INTO teams (id, name) VALUES (?, ?) \
+         ON CONFLICT(id) DO UPDATE SET name=excluded.name",
+    )
+    .bind(team.id)
+    .bind(&team.name)
+}
+
+fn assign_query(assignment: &domain::Assignment) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        "INSERT INTO assignments (resource_id, project_id, year, month, role, note, status) \
+         VALUES (?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(resource_id, project_id, year, month) \
+         DO UPDATE SET role=excluded.role, note=excluded.note, status=excluded.status",
+    )
+    .bind(assignment.resource_id)
+    .bind(assignment.project_id)
+    .bind(assignment.year)
+    .bind(assignment.month)
+    .bind(&assignment.role)
+    .bind(&assignment.note)
+    .bind(assignment.status.as_str())
+}
+
+fn assign_week_query(
+    assignment: &domain::WeekAssignment,
+) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        "INSERT INTO week_assignments (resource_id, project_id, year, week, role, note, status) \
+         VALUES (?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(resource_id, project_id, year, week) \
+         DO UPDATE SET role=excluded.role, note=excluded.note, status=excluded.status",
+    )
+    .bind(assignment.resource_id)
+    .bind(assignment.project_id)
+    .bind(assignment.year)
+    .bind(assignment.week)
+    .bind(&assignment.role)
+    .bind(&assignment.note)
+    .bind(assignment.status.as_str())
+}
+
+fn add_project_manager_query(
+    project_id: Uuid,
+    user_id: &str,
+) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        "INSERT INTO project_managers (project_id, user_id) VALUES (?, ?) \
+         ON CONFLICT(project_id, user_id) DO NOTHING",
+    )
+    .bind(project_id)
+    .bind(user_id)
+}
+
+fn set_cost_rate_query(rate: &domain::CostRate) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        "INSERT INTO cost_rates (resource_id, year, month, rate) VALUES (?, ?, ?, ?) \
+         ON CONFLICT(resource_id, year, month) DO UPDATE SET rate=excluded.rate",
+    )
+    .bind(rate.resource_id)
+    .bind(rate.year)
+    .bind(rate.month)
+    .bind(rate.rate)
+}
+
+fn set_user_role_query(
+    user_id: &str,
+    role: domain::Role,
+) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        "INSERT INTO user_roles (user_id, role) VALUES (?, ?) \
+         ON CONFLICT(user_id) DO UPDATE SET role=excluded.role",
+    )
+    .bind(user_id)
+    .bind(role.as_str())
+}
+
+fn set_capacity_query(capacity: &domain::Capacity) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        "INSERT INTO capacities (resource_id, year, month, percent) VALUES (?, ?, ?, ?) \
+         ON CONFLICT(resource_id, year, month) DO UPDATE SET percent=excluded.percent",
+    )
+    .bind(capacity.resource_id)
+    .bind(capacity.year)
+    .bind(capacity.month)
+    .bind(capacity.percent)
+}
+
+fn delete_capacity_query(
+    resource_id: Uuid,
+    year: i32,
+    month: i32,
+) -> Query<'static, Sqlite, SqliteArguments<'static>> {
+    sqlx::query("DELETE FROM capacities WHERE resource_id = ? AND year = ? AND month = ?")
+        .bind(resource_id)
+        .bind(year)
+        .bind(month)
+}
+
+fn record_actual_query(actual: &domain::Actual) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        "INSERT INTO actuals (resource_id, project_id, year, month, effort) \
+         VALUES (?, ?, ?, ?, ?) \
+         ON CONFLICT(resource_id, project_id, year, month) DO UPDATE SET effort=excluded.effort",
+    )
+    .bind(actual.resource_id)
+    .bind(actual.project_id)
+    .bind(actual.year)
+    .bind(actual.month)
+    .bind(actual.effort)
+}
+
+/// Appends `(VALUES (a, b), ...)` listing `pairs`, for matching row values
+/// such as `(year, month) IN`.
+fn push_pairs(qb: &mut QueryBuilder<'_, Sqlite>, pairs: &[(i32, i32)]) {
+    qb.push("(VALUES ");
+    let mut values = qb.separated(", ");
+    for (a, b) in pairs {
+        values
+            .push("(")
+            .push_bind_unseparated(*a)
+            .push_unseparated(", ")
+            .push_bind_unseparated(*b)
+            .push_unseparated(")");
     }
+    qb.push(")");
+}
 
-    /// Fetches `(id, name)` pairs from the given `table` for `ids`.
-    async fn fetch_named_rows(
-        &self,
-        table: &str,
-        ids: &[Uuid],
-    ) -> sqlx::Result<Vec<(Uuid, String)>> {
+/// Appends `filter`'s conditions to a query ending in `WHERE 1=1`.
+fn push_filter(qb: &mut QueryBuilder<'_, Sqlite>, filter: &AssignmentFilter) {
+    if let Some(v) = filter.resource_id {
+        qb.push(" AND resource_id = ").push_bind(v);
+    }
+    if let Some(v) = filter.project_id {
+        qb.push(" AND project_id = ").push_bind(v);
+    }
+    if let Some(v) = filter.year {
+        qb.push(" AND year = ").push_bind(v);
+    }
+    if let Some(v) = filter.month {
+        qb.push(" AND month = ").push_bind(v);
+    }
+}
+
+/// Appends the conditions of `filter` to a query on `projects` or `resources`
+/// ending in `WHERE 1=1`, leaving out its `after` and `limit`.
+fn push_row_filter(qb: &mut QueryBuilder<'_, Sqlite>, filter: &RowFilter, projects: bool) {
+    if let Some(ids) = &filter.ids {
         if ids.is_empty() {
-            return Ok(vec![]);
+            qb.push(" AND 0");
+        } else {
+            qb.push(" AND id IN (");
+            let mut separated = qb.separated(", ");
+            for id in ids {
+                separated.push_bind(*id);
+            }
+            qb.push(")");
         }
-
-        let placeholders = vec!["?"; ids.len()].join(", ");
-        let sql = format!("SELECT id, name FROM {table} WHERE id IN ({placeholders})");
-        let mut query = sqlx::query_as::<_, (Uuid, String)>(&sql);
-        for id in ids {
-            query = query.bind(id);
+    }
+    if let Some(search) = &filter.search {
+        qb.push(" AND instr(lower(name), lower(")
+            .push_bind(search.clone())
+            .push(")) > 0");
+    }
+    if !projects {
+        return;
+    }
+    if let Some(statuses) = &filter.statuses {
+        if statuses.is_empty() {
+            qb.push(" AND 0");
+        } else {
+            qb.push(" AND status IN (");
+            let mut separated = qb.separated(", ");
+            for status in statuses {
+                separated.push_bind(status.as_str());
+            }
+            qb.push(")");
         }
-        query.fetch_all(&self.pool).await
+    }
+    if filter.top_level {
+        qb.push(" AND parent_id IS NULL");
+    }
+}
+
+/// Appends `filter`'s `after`, order and `limit` to a query built by [`push_row_filter`].
+fn push_row_page(qb: &mut QueryBuilder<'_, Sqlite>, filter: &RowFilter) {
+    let (op, order) = if filter.descending {
+        ("<", " ORDER BY name DESC, id DESC")
+    } else {
+        (">", " ORDER BY name, id")
+    };
+    if let Some((name, id)) = &filter.after {
+        qb.push(format!(" AND (name {op} "))
+            .push_bind(name.clone())
+            .push(" OR (name = ")
+            .push_bind(name.clone())
+            .push(format!(" AND id {op} "))
+            .push_bind(*id)
+            .push("))");
+    }
+    qb.push(order);
+    if let Some(limit) = filter.limit {
+        qb.push(" LIMIT ")
+            .push_bind(i64::try_from(limit).unwrap_or(i64::MAX));
     }
 }
 
 #[async_trait]
 impl Repository for SqliteRepository {
     async fn list_projects(&self) -> sqlx::Result<Vec<domain::Project>> {
-        let rows: Vec<dto::Project> =
-            sqlx::query_as::<_, dto::Project>("SELECT id, name FROM projects ORDER BY name")
-                .fetch_all(&self.pool)
-                .await?;
+        let rows: Vec<dto::Project> = sqlx::query_as::<_, dto::Project>(
+            "SELECT id, name, parent_id, budget, status, colour, description FROM projects \
+             ORDER BY name",
+        )
+        .fetch_all(&self.pool)
+        .await?;
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
     async fn list_resources(&self) -> sqlx::Result<Vec<domain::Resource>> {
-        let rows: Vec<dto::Resource> =
-            sqlx::query_as::<_, dto::Resource>("SELECT id, name FROM resources ORDER BY name")
+        let rows: Vec<dto::Resource> = sqlx::query_as::<_, dto::Resource>(
+            "SELECT id, name, team_id, kind, region FROM resources ORDER BY name",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn find_projects(&self, filter: &RowFilter) -> sqlx::Result<Vec<domain::Project>> {
+        let mut qb = QueryBuilder::<Sqlite>::new(
+            "SELECT id, name, parent_id, budget, status, colour, description FROM projects \
+             WHERE 1=1",
+        );
+        push_row_filter(&mut qb, filter, true);
+        push_row_page(&mut qb, filter);
+        let rows: Vec<dto::Project> = qb.build_query_as().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn count_projects(&self, filter: &RowFilter) -> sqlx::Result<usize> {
+        let mut qb = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM projects WHERE 1=1");
+        push_row_filter(&mut qb, filter, true);
+        let (count,): (i64,) = qb.build_query_as().fetch_one(&self.pool).await?;
+        Ok(usize::try_from(count).unwrap_or_default())
+    }
+
+    async fn find_resources(&self, filter: &RowFilter) -> sqlx::Result<Vec<domain::Resource>> {
+        let mut qb = QueryBuilder::<Sqlite>::new(
+            "SELECT id, name, team_id, kind, region FROM resources WHERE 1=1",
+        );
+        push_row_filter(&mut qb, filter, false);
+        push_row_page(&mut qb, filter);
+        let rows: Vec<dto::Resource> = qb.build_query_as().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn count_resources(&self, filter: &RowFilter) -> sqlx::Result<usize> {
+        let mut qb = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM resources WHERE 1=1");
+        push_row_filter(&mut qb, filter, false);
+        let (count,): (i64,) = qb.build_query_as().fetch_one(&self.pool).await?;
+        Ok(usize::try_from(count).unwrap_or_default())
+    }
+
+    async fn list_teams(&self) -> sqlx::Result<Vec<domain::Team>> {
+        let rows: Vec<dto::Team> =
+            sqlx::query_as::<_, dto::Team>("SELECT id, name FROM teams ORDER BY name")
                 .fetch_all(&self.pool)
                 .await?;
         Ok(rows.into_iter().map(Into::into).collect())
@@ -131,50 +578,146 @@ impl Repository for SqliteRepository {
         filter: &AssignmentFilter,
     ) -> sqlx::Result<Vec<domain::Assignment>> {
         let mut qb = QueryBuilder::<Sqlite>::new(
-            "SELECT resource_id, project_id, year, month FROM assignments WHERE 1=1",
+            "SELECT resource_id, project_id, year, month, role, note, status FROM assignments \
+             WHERE 1=1",
         );
 
-        if let Some(ref v) = filter.resource_id {
+        push_filter(&mut qb, filter);
+        qb.push(" ORDER BY year, month, project_id, resource_id");
+
+        let rows: Vec<dto::Assignment> = qb.build_query_as().fetch_all(&self.pool).await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn list_week_assignments(
+        &self,
+        filter: &WeekFilter,
+    ) -> sqlx::Result<Vec<domain::WeekAssignment>> {
+        let mut qb = QueryBuilder::<Sqlite>::new(
+            "SELECT resource_id, project_id, year, week, role, note, status FROM week_assignments \
+             WHERE 1=1",
+        );
+        if let Some(v) = filter.resource_id {
             qb.push(" AND resource_id = ").push_bind(v);
         }
-        if let Some(ref v) = filter.project_id {
+        if let Some(v) = filter.project_id {
             qb.push(" AND project_id = ").push_bind(v);
         }
-        if let Some(v) = filter.year {
-            qb.push(" AND year = ").push_bind(v);
+        if let Some(v) = filter.from {
+            qb.push(" AND (year > ")
+                .push_bind(v.year)
+                .push(" OR (year = ")
+                .push_bind(v.year)
+                .push(" AND week >= ")
+                .push_bind(i32::from(v.week))
+                .push("))");
         }
-        if let Some(v) = filter.month {
-            qb.push(" AND month = ").push_bind(v);
+        if let Some(v) = filter.to {
+            qb.push(" AND (year < ")
+                .push_bind(v.year)
+                .push(" OR (year = ")
+                .push_bind(v.year)
+                .push(" AND week <= ")
+                .push_bind(i32::from(v.week))
+                .push("))");
         }
+        qb.push(" ORDER BY year, week, project_id, resource_id");
+
+        let rows: Vec<dto::WeekAssignment> = qb.build_query_as().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
 
+    async fn list_actuals(&self, filter: &AssignmentFilter) -> sqlx::Result<Vec<domain::Actual>> {
+        let mut qb = QueryBuilder::<Sqlite>::new(
+            "SELECT resource_id, project_id, year, month, effort FROM actuals WHERE 1=1",
+        );
+        push_filter(&mut qb, filter);
         qb.push(" ORDER BY year, month, project_id, resource_id");
 
-        let rows: Vec<dto::Assignment> = qb.build_query_as().fetch_all(&self.pool).await?;
+        let rows: Vec<dto::Actual> = qb.build_query_as().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn utilisation(
+        &self,
+        months: &[(i32, i32)],
+        group_by: domain::UtilisationGroup,
+    ) -> sqlx::Result<Vec<domain::Utilisation>> {
+        if months.is_empty() {
+            return Ok(vec![]);
+        }
+        // Assignments are unique per (resource, project, month), so plain
+        // counts are distinct except when collapsing projects.
+        let (select, group) = match group_by {
+            domain::UtilisationGroup::Project => (
+                "SELECT project_id, NULL AS resource_id, year, month, COUNT(*) AS count",
+                " GROUP BY project_id, year, month ORDER BY year, month, project_id",
+            ),
+            domain::UtilisationGroup::Resource => (
+                "SELECT NULL AS project_id, resource_id, NULL AS year, NULL AS month, \
+                 COUNT(DISTINCT year * 12 + month) AS count",
+                " GROUP BY resource_id ORDER BY resource_id",
+            ),
+            domain::UtilisationGroup::Month => (
+                "SELECT NULL AS project_id, NULL AS resource_id, year, month, \
+                 COUNT(DISTINCT resource_id) AS count",
+                " GROUP BY year, month ORDER BY year, month",
+            ),
+        };
+
+        // Weeks of the window with the month each rolls up into
+        let weeks: Vec<(domain::IsoWeek, (i32, u8))> = months
+            .iter()
+            .filter_map(|&(year, month)| Some((year, u8::try_from(month).ok()?)))
+            .flat_map(|(year, month)| domain::IsoWeek::of_month(year, month))
+            .map(|w| (w, w.month()))
+            .collect();
+
+        // UNION keeps one row per resource, project and month
+        let mut qb = QueryBuilder::<Sqlite>::new(
+            "WITH booked AS (SELECT resource_id, project_id, year, month \
+             FROM assignments WHERE (year, month) IN ",
+        );
+        push_pairs(&mut qb, months);
+        if !weeks.is_empty() {
+            qb.push(
+                " UNION SELECT w.resource_id, w.project_id, m.column3, m.column4 \
+                 FROM week_assignments w JOIN (VALUES ",
+            );
+            let mut values = qb.separated(", ");
+            for (week, (year, month)) in &weeks {
+                values
+                    .push("(")
+                    .push_bind_unseparated(week.year)
+                    .push_unseparated(", ")
+                    .push_bind_unseparated(i32::from(week.week))
+                    .push_unseparated(", ")
+                    .push_bind_unseparated(*year)
+                    .push_unseparated(", ")
+                    .push_bind_unseparated(i32::from(*month))
+                    .push_unseparated(")");
+            }
+            qb.push(") AS m ON w.year = m.column1 AND w.week = m.column2");
+        }
+        qb.push(") ").push(select).push(" FROM booked").push(group);
 
+        let rows: Vec<dto::Utilisation> = qb.build_query_as().fetch_all(&self.pool).await?;
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
     async fn upsert_project(&self, project: &domain::Project) -> sqlx::Result<()> {
-        sqlx::query(
-            "INSERT INTO projects (id, name) VALUES (?, ?) \
-             ON CONFLICT(id) DO UPDATE SET name=excluded.name",
-        )
-        .bind(project.id)
-        .bind(&project.name)
-        .execute(&self.pool)
-        .await?;
+        upsert_project_query(project).execute(&self.pool).await?;
         Ok(())
     }
 
     async fn upsert_resource(&self, resource: &domain::Resource) -> sqlx::Result<()> {
-        sqlx::query(
-            "INSERT INTO resources (id, name) VALUES (?, ?) \
-             ON CONFLICT(id) DO UPDATE SET name=excluded.name",
-        )
-        .bind(resource.id)
-        .bind(&resource.name)
-        .execute(&self.pool)
-        .await?;
+        upsert_resource_query(resource).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn upsert_team(&self, team: &domain::Team) -> sqlx::Result<()> {
+        upsert_team_query(team).execute(&self.pool).await?;
         Ok(())
     }
 
@@ -188,6 +731,11 @@ impl Repository for SqliteRepository {
         Ok(domain::Project {
             id,
             name: name.to_string(),
+            parent_id: None,
+            budget: None,
+            status: domain::ProjectStatus::default(),
+            colour: None,
+            description: None,
         })
     }
 
@@ -201,6 +749,22 @@ impl Repository for SqliteRepository {
         Ok(domain::Resource {
             id,
             name: name.to_string(),
+            team_id: None,
+            kind: domain::ResourceKind::Person,
+            region: None,
+        })
+    }
+
+    async fn create_team(&self, name: &str) -> sqlx::Result<domain::Team> {
+        let id = Uuid::new_v4();
+        sqlx::query("INSERT INTO teams (id, name) VALUES (?, ?)")
+            .bind(id)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(domain::Team {
+            id,
+            name: name.to_string(),
         })
     }
 
@@ -212,6 +776,15 @@ impl Repository for SqliteRepository {
         Ok(res.rows_affected())
     }
 
+    async fn set_project_parent(&self, id: Uuid, parent_id: Option<Uuid>) -> sqlx::Result<u64> {
+        let res = sqlx::query("UPDATE projects SET parent_id = ? WHERE id = ?")
+            .bind(parent_id)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(res.rows_affected())
+    }
+
     async fn delete_resource(&self, id: Uuid) -> sqlx::Result<u64> {
         let res = sqlx::query("DELETE FROM resources WHERE id = ?")
             .bind(id)
@@ -220,10 +793,67 @@ impl Repository for SqliteRepository {
         Ok(res.rows_affected())
     }
 
+    async fn delete_team(&self, id: Uuid) -> sqlx::Result<u64> {
+        let res = sqlx::query("DELETE FROM teams WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn set_project_budget(&self, id: Uuid, budget: Option<i64>) -> sqlx::Result<u64> {
+        let res = sqlx::query("UPDATE projects SET budget = ? WHERE id = ?")
+            .bind(budget)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn set_project_details(
+        &self,
+        id: Uuid,
+        status: domain::ProjectStatus,
+        colour: Option<&str>,
+        description: Option<&str>,
+    ) -> sqlx::Result<u64> {
+        let res =
+            sqlx::query("UPDATE projects SET status = ?, colour = ?, description = ? WHERE id = ?")
+                .bind(status.as_str())
+                .bind(colour)
+                .bind(description)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn set_resource_team(&self, id: Uuid, team_id: Option<Uuid>) -> sqlx::Result<u64> {
+        let res = sqlx::query("UPDATE resources SET team_id = ? WHERE id = ?")
+            .bind(team_id)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn set_resource_region(&self, id: Uuid, region: Option<&str>) -> sqlx::Result<u64> {
+        let res = sqlx::query("UPDATE resources SET region = ? WHERE id = ?")
+            .bind(region)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(res.rows_affected())
+    }
+
     async fn assign(&self, assignment: &domain::Assignment) -> sqlx::Result<()> {
-        sqlx::query(
-            "INSERT INTO assignments (resource_id, project_id, year, month) VALUES (?, ?, ?, ?) \
-             ON CONFLICT(resource_id, project_id, year, month) DO NOTHING",
+        assign_query(assignment).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn unassign(&self, assignment: &domain::Assignment) -> sqlx::Result<u64> {
+        let res = sqlx::query(
+            "DELETE FROM assignments WHERE resource_id=? AND project_id=? AND year=? AND month=?",
         )
         .bind(assignment.resource_id)
         .bind(assignment.project_id)
@@ -231,35 +861,366 @@ impl Repository for SqliteRepository {
         .bind(assignment.month)
         .execute(&self.pool)
         .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn assign_week(&self, assignment: &domain::WeekAssignment) -> sqlx::Result<()> {
+        assign_week_query(assignment).execute(&self.pool).await?;
         Ok(())
     }
 
-    async fn unassign(&self, assignment: &domain::Assignment) -> sqlx::Result<u64> {
+    async fn unassign_week(&self, assignment: &domain::WeekAssignment) -> sqlx::Result<u64> {
         let res = sqlx::query(
-            "DELETE FROM assignments WHERE resource_id=? AND project_id=? AND year=? AND month=?",
+            "DELETE FROM week_assignments \
+             WHERE resource_id=? AND project_id=? AND year=? AND week=?",
         )
         .bind(assignment.resource_id)
         .bind(assignment.project_id)
         .bind(assignment.year)
-        .bind(assignment.month)
+        .bind(assignment.week)
         .execute(&self.pool)
         .await?;
         Ok(res.rows_affected())
     }
 
+    async fn move_assignments(&self, from: Uuid, to: Uuid) -> sqlx::Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let months = sqlx::query(
+            "INSERT INTO assignments (resource_id, project_id, year, month, role, note, status) \
+             SELECT ?, project_id, year, month, role, note, status FROM assignments \
+             WHERE resource_id = ? \
+             ON CONFLICT(resource_id, project_id, year, month) DO NOTHING",
+        )
+        .bind(to)
+        .bind(from)
+        .execute(&mut *tx)
+        .await?;
+        let weeks = sqlx::query(
+            "INSERT INTO week_assignments (resource_id, project_id, year, week, role, note, status) \
+             SELECT ?, project_id, year, week, role, note, status FROM week_assignments \
+             WHERE resource_id = ? \
+             ON CONFLICT(resource_id, project_id, year, week) DO NOTHING",
+        )
+        .bind(to)
+        .bind(from)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM assignments WHERE resource_id = ?")
+            .bind(from)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM week_assignments WHERE resource_id = ?")
+            .bind(from)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(months.rows_affected() + weeks.rows_affected())
+    }
+
+    async fn confirm_assignments(
+        &self,
+        project_id: Uuid,
+        months: &[(i32, i32)],
+    ) -> sqlx::Result<u64> {
+        let weeks: Vec<(i32, i32)> = months
+            .iter()
+            .filter_map(|&(year, month)| Some((year, u8::try_from(month).ok()?)))
+            .flat_map(|(year, month)| domain::IsoWeek::of_month(year, month))
+            .map(|w| (w.year, i32::from(w.week)))
+            .collect();
+        let mut confirmed = 0;
+        let mut tx = self.pool.begin().await?;
+        for (table, period, pairs) in [
+            ("assignments", "month", months),
+            ("week_assignments", "week", &weeks[..]),
+        ] {
+            if pairs.is_empty() {
+                continue;
+            }
+            let mut qb = QueryBuilder::<Sqlite>::new(format!(
+                "UPDATE {table} SET status = 'confirmed' \
+                 WHERE status = 'tentative' AND project_id = "
+            ));
+            qb.push_bind(project_id)
+                .push(format!(" AND (year, {period}) IN "));
+            push_pairs(&mut qb, pairs);
+            confirmed += qb.build().execute(&mut *tx).await?.rows_affected();
+        }
+        tx.commit().await?;
+        Ok(confirmed)
+    }
+
+    async fn apply_batch(&self, batch: &domain::Batch) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for team in &batch.teams {
+            upsert_team_query(team).execute(&mut *tx).await?;
+        }
+        for project in &batch.projects {
+            upsert_project_query(project).execute(&mut *tx).await?;
+        }
+        for resource in &batch.resources {
+            upsert_resource_query(resource).execute(&mut *tx).await?;
+        }
+        for rate in &batch.cost_rates {
+            set_cost_rate_query(rate).execute(&mut *tx).await?;
+        }
+        for capacity in &batch.capacities {
+            set_capacity_query(capacity).execute(&mut *tx).await?;
+        }
+        for (project_id, user_id) in &batch.project_managers {
+            add_project_manager_query(*project_id, user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for (user_id, role) in &batch.user_roles {
+            set_user_role_query(user_id, *role)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for assignment in &batch.assignments {
+            assign_query(assignment).execute(&mut *tx).await?;
+        }
+        for assignment in &batch.week_assignments {
+            assign_week_query(assignment).execute(&mut *tx).await?;
+        }
+        for actual in &batch.actuals {
+            record_actual_query(actual).execute(&mut *tx).await?;
+        }
+        tx.commit().await
+    }
+
+    async fn list_cost_rates(&self) -> sqlx::Result<Vec<domain::CostRate>> {
+        let rows: Vec<dto::CostRate> = sqlx::query_as::<_, dto::CostRate>(
+            "SELECT resource_id, year, month, rate FROM cost_rates \
+             ORDER BY resource_id, year, month",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn set_cost_rate(&self, rate: &domain::CostRate) -> sqlx::Result<()> {
+        set_cost_rate_query(rate).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn delete_cost_rate(
+        &self,
+        resource_id: Uuid,
+        year: i32,
+        month: i32,
+    ) -> sqlx::Result<u64> {
+        let res =
+            sqlx::query("DELETE FROM cost_rates WHERE resource_id = ? AND year = ? AND month = ?")
+                .bind(resource_id)
+                .bind(year)
+                .bind(month)
+                .execute(&self.pool)
+                .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn list_capacities(&self) -> sqlx::Result<Vec<domain::Capacity>> {
+        let rows: Vec<dto::Capacity> = sqlx::query_as::<_, dto::Capacity>(
+            "SELECT resource_id, year, month, percent FROM capacities \
+             ORDER BY resource_id, year, month",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn capacities_in(
+        &self,
+        resource_ids: &[Uuid],
+        months: &[(i32, i32)],
+    ) -> sqlx::Result<Vec<domain::Capacity>> {
+        if resource_ids.is_empty() || months.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut qb = QueryBuilder::<Sqlite>::new(
+            "SELECT resource_id, year, month, percent FROM capacities WHERE resource_id IN (",
+        );
+        let mut separated = qb.separated(", ");
+        for id in resource_ids {
+            separated.push_bind(id);
+        }
+        qb.push(") AND (year, month) IN ");
+        push_pairs(&mut qb, months);
+        qb.push(" ORDER BY resource_id, year, month");
+        let rows: Vec<dto::Capacity> = qb.build_query_as().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn set_capacity(&self, capacity: &domain::Capacity) -> sqlx::Result<()> {
+        set_capacity_query(capacity).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn set_capacities(
+        &self,
+        resource_id: Uuid,
+        months: &[(i32, i32)],
+        percent: Option<i32>,
+    ) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for &(year, month) in months {
+            match percent {
+                Some(percent) => {
+                    let capacity = domain::Capacity {
+                        resource_id,
+                        year,
+                        month,
+                        percent,
+                    };
+                    set_capacity_query(&capacity).execute(&mut *tx).await?;
+                }
+                None => {
+                    delete_capacity_query(resource_id, year, month)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+        }
+        tx.commit().await
+    }
+
+    async fn delete_capacity(&self, resource_id: Uuid, year: i32, month: i32) -> sqlx::Result<u64> {
+        let res = delete_capacity_query(resource_id, year, month)
+            .execute(&self.pool)
+            .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn record_audit(&self, entry: &domain::AuditEntry) -> sqlx::Result<()> {
+        sqlx::query("INSERT INTO audit_log (user_id, action, detail) VALUES (?, ?, ?)")
+            .bind(&entry.user_id)
+            .bind(&entry.action)
+            .bind(&entry.detail)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn list_audit(&self) -> sqlx::Result<Vec<domain::AuditEntry>> {
+        let rows: Vec<dto::AuditEntry> = sqlx::query_as::<_, dto::AuditEntry>(
+            "SELECT user_id, action, detail FROM audit_log ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn user_role(&self, user_id: &str) -> sqlx::Result<Option<domain::Role>> {
+        let role: Option<String> =
+            sqlx::query_scalar("SELECT role FROM user_roles WHERE user_id = ?")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        role.map(|r| r.parse().map_err(|e: String| sqlx::Error::Decode(e.into())))
+            .transpose()
+    }
+
+    async fn set_user_role(&self, user_id: &str, role: domain::Role) -> sqlx::Result<()> {
+        set_user_role_query(user_id, role)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn list_user_roles(&self) -> sqlx::Result<Vec<(String, domain::Role)>> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT user_id, role FROM user_roles ORDER BY user_id")
+                .fetch_all(&self.pool)
+                .await?;
+        rows.into_iter()
+            .map(|(user_id, role)| {
+                let role = role
+                    .parse()
+                    .map_err(|e: String| sqlx::Error::Decode(e.into()))?;
+                Ok((user_id, role))
+            })
+            .collect()
+    }
+
+    async fn is_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<bool> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM project_managers WHERE project_id = ? AND user_id = ?)",
+        )
+        .bind(project_id)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn add_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<()> {
+        add_project_manager_query(project_id, user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<u64> {
+        let res = sqlx::query("DELETE FROM project_managers WHERE project_id = ? AND user_id = ?")
+            .bind(project_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn list_project_managers(&self) -> sqlx::Result<Vec<(Uuid, String)>> {
+        sqlx::query_as(
+            "SELECT project_id, user_id FROM project_managers ORDER BY project_id, user_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
     async fn projects(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Project>> {
-        let rows = self.fetch_named_rows("projects", ids).await?;
-        Ok(rows
-            .into_iter()
-            .map(|(id, name)| domain::Project { id, name })
-            .collect())
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut qb = QueryBuilder::<Sqlite>::new(
+            "SELECT id, name, parent_id, budget, status, colour, description FROM projects \
+             WHERE id IN (",
+        );
+        let mut separated = qb.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        qb.push(")");
+        let rows: Vec<dto::Project> = qb.build_query_as().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Into::into).collect())
     }
 
     async fn resources(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Resource>> {
-        let rows = self.fetch_named_rows("resources", ids).await?;
-        Ok(rows
-            .into_iter()
-            .map(|(id, name)| domain::Resource { id, name })
-            .collect())
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut qb = QueryBuilder::<Sqlite>::new(
+            "SELECT id, name, team_id, kind, region FROM resources WHERE id IN (",
+        );
+        let mut separated = qb.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        qb.push(")");
+        let rows: Vec<dto::Resource> = qb.build_query_as().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn teams(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Team>> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut qb = QueryBuilder::<Sqlite>::new("SELECT id, name FROM teams WHERE id IN (");
+        let mut separated = qb.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        qb.push(")");
+        let rows: Vec<dto::Team> = qb.build_query_as().fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Into::into).collect())
     }
 }
@@ -0,0 +1,443 @@
+//! Read-through caching decorator for [`Repository`].
+
+use super::{AssignmentFilter, Repository, RowFilter, WeekFilter};
+use crate::domain;
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
+use uuid::Uuid;
+
+/// Memoises project and resource reads from the wrapped repository.
+///
+/// `list_projects`/`list_resources` and the by-id lookups are served from memory
+/// until a mutation touching that table invalidates them. Team and assignment
+/// reads are always passed through.
+///
+/// The cache only sees writes made through this wrapper; changes made to the
+/// underlying database by other processes are not observed.
+pub struct CachedRepository<R> {
+    inner: R,
+    projects: Mutex<Table<domain::Project>>,
+    resources: Mutex<Table<domain::Resource>>,
+}
+
+impl<R: Repository> CachedRepository<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            projects: Mutex::default(),
+            resources: Mutex::default(),
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+trait Keyed: Clone {
+    fn key(&self) -> Uuid;
+}
+
+impl Keyed for domain::Project {
+    fn key(&self) -> Uuid {
+        self.id
+    }
+}
+
+impl Keyed for domain::Resource {
+    fn key(&self) -> Uuid {
+        self.id
+    }
+}
+
+/// Cached rows for a single table.
+///
+/// `generation` is bumped on every invalidation so that a read which raced with
+/// a write doesn't repopulate the cache with stale rows.
+struct Table<T> {
+    generation: u64,
+    all: Option<Vec<T>>,
+    by_id: HashMap<Uuid, T>,
+}
+
+impl<T> Default for Table<T> {
+    fn default() -> Self {
+        Self {
+            generation: 0,
+            all: None,
+            by_id: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Keyed> Table<T> {
+    fn invalidate(&mut self) {
+        self.generation += 1;
+        self.all = None;
+        self.by_id.clear();
+    }
+
+    /// Returns cached rows for `ids`, plus the ids that still need fetching.
+    fn lookup(&self, ids: &[Uuid]) -> (Vec<T>, Vec<Uuid>) {
+        if let Some(all) = &self.all {
+            let found = all.iter().filter(|v| ids.contains(&v.key())).cloned();
+            return (found.collect(), vec![]);
+        }
+        let mut found = Vec::new();
+        let mut missing = Vec::new();
+        for id in ids {
+            match self.by_id.get(id) {
+                Some(v) => found.push(v.clone()),
+                None => missing.push(*id),
+            }
+        }
+        (found, missing)
+    }
+
+    fn fill_all(&mut self, generation: u64, rows: &[T]) {
+        if self.generation == generation {
+            self.all = Some(rows.to_vec());
+        }
+    }
+
+    fn fill_by_id(&mut self, generation: u64, rows: &[T]) {
+        if self.generation == generation {
+            self.by_id.extend(rows.iter().map(|v| (v.key(), v.clone())));
+        }
+    }
+}
+
+fn lock<T>(table: &Mutex<Table<T>>) -> MutexGuard<'_, Table<T>> {
+    // The cache holds no invariants worth poisoning over; recover and carry on.
+    table
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+async fn cached_list<T, F>(table: &Mutex<Table<T>>, fetch: F) -> sqlx::Result<Vec<T>>
+where
+    T: Keyed,
+    F: Future<Output = sqlx::Result<Vec<T>>>,
+{
+    let generation = {
+        let guard = lock(table);
+        if let Some(all) = &guard.all {
+            return Ok(all.clone());
+        }
+        guard.generation
+    };
+    let rows = fetch.await?;
+    lock(table).fill_all(generation, &rows);
+    Ok(rows)
+}
+
+async fn cached_by_id<T, F, Fut>(
+    table: &Mutex<Table<T>>,
+    ids: &[Uuid],
+    fetch: F,
+) -> sqlx::Result<Vec<T>>
+where
+    T: Keyed,
+    F: FnOnce(Vec<Uuid>) -> Fut,
+    Fut: Future<Output = sqlx::Result<Vec<T>>>,
+{
+    let (mut found, missing, generation) = {
+        let guard = lock(table);
+        let (found, missing) = guard.lookup(ids);
+        (found, missing, guard.generation)
+    };
+    if missing.is_empty() {
+        return Ok(found);
+    }
+    let rows = fetch(missing).await?;
+    lock(table).fill_by_id(generation, &rows);
+    found.extend(rows);
+    Ok(found)
+}
+
+#[async_trait]
+impl<R: Repository> Repository for CachedRepository<R> {
+    async fn list_projects(&self) -> sqlx::Result<Vec<domain::Project>> {
+        cached_list(&self.projects, self.inner.list_projects()).await
+    }
+
+    async fn list_resources(&self) -> sqlx::Result<Vec<domain::Resource>> {
+        cached_list(&self.resources, self.inner.list_resources()).await
+    }
+
+    async fn find_projects(&self, filter: &RowFilter) -> sqlx::Result<Vec<domain::Project>> {
+        self.inner.find_projects(filter).await
+    }
+
+    async fn count_projects(&self, filter: &RowFilter) -> sqlx::Result<usize> {
+        self.inner.count_projects(filter).await
+    }
+
+    async fn find_resources(&self, filter: &RowFilter) -> sqlx::Result<Vec<domain::Resource>> {
+        self.inner.find_resources(filter).await
+    }
+
+    async fn count_resources(&self, filter: &RowFilter) -> sqlx::Result<usize> {
+        self.inner.count_resources(filter).await
+    }
+
+    async fn list_teams(&self) -> sqlx::Result<Vec<domain::Team>> {
+        self.inner.list_teams().await
+    }
+
+    async fn list_assignments(
+        &self,
+        filter: &AssignmentFilter,
+    ) -> sqlx::Result<Vec<domain::Assignment>> {
+        self.inner.list_assignments(filter).await
+    }
+
+    async fn list_week_assignments(
+        &self,
+        filter: &WeekFilter,
+    ) -> sqlx::Result<Vec<domain::WeekAssignment>> {
+        self.inner.list_week_assignments(filter).await
+    }
+
+    async fn list_actuals(&self, filter: &AssignmentFilter) -> sqlx::Result<Vec<domain::Actual>> {
+        self.inner.list_actuals(filter).await
+    }
+
+    async fn utilisation(
+        &self,
+        months: &[(i32, i32)],
+        group_by: domain::UtilisationGroup,
+    ) -> sqlx::Result<Vec<domain::Utilisation>> {
+        self.inner.utilisation(months, group_by).await
+    }
+
+    async fn projects(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Project>> {
+        cached_by_id(&self.projects, ids, |missing| async move {
+            self.inner.projects(&missing).await
+        })
+        .await
+    }
+
+    async fn resources(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Resource>> {
+        cached_by_id(&self.resources, ids, |missing| async move {
+            self.inner.resources(&missing).await
+        })
+        .await
+    }
+
+    async fn teams(&self, ids: &[Uuid]) -> sqlx::Result<Vec<domain::Team>> {
+        self.inner.teams(ids).await
+    }
+
+    async fn upsert_project(&self, project: &domain::Project) -> sqlx::Result<()> {
+        let res = self.inner.upsert_project(project).await;
+        lock(&self.projects).invalidate();
+        res
+    }
+
+    async fn upsert_resource(&self, resource: &domain::Resource) -> sqlx::Result<()> {
+        let res = self.inner.upsert_resource(resource).await;
+        lock(&self.resources).invalidate();
+        res
+    }
+
+    async fn upsert_team(&self, team: &domain::Team) -> sqlx::Result<()> {
+        self.inner.upsert_team(team).await
+    }
+
+    async fn create_project(&self, name: &str) -> sqlx::Result<domain::Project> {
+        let res = self.inner.create_project(name).await;
+        lock(&self.projects).invalidate();
+        res
+    }
+
+    async fn create_resource(&self, name: &str) -> sqlx::Result<domain::Resource> {
+        let res = self.inner.create_resource(name).await;
+        lock(&self.resources).invalidate();
+        res
+    }
+
+    async fn create_team(&self, name: &str) -> sqlx::Result<domain::Team> {
+        self.inner.create_team(name).await
+    }
+
+    async fn delete_project(&self, id: Uuid) -> sqlx::Result<u64> {
+        let res = self.inner.delete_project(id).await;
+        lock(&self.projects).invalidate();
+        res
+    }
+
+    async fn set_project_parent(&self, id: Uuid, parent_id: Option<Uuid>) -> sqlx::Result<u64> {
+        let res = self.inner.set_project_parent(id, parent_id).await;
+        lock(&self.projects).invalidate();
+        res
+    }
+
+    async fn delete_resource(&self, id: Uuid) -> sqlx::Result<u64> {
+        let res = self.inner.delete_resource(id).await;
+        lock(&self.resources).invalidate();
+        res
+    }
+
+    async fn delete_team(&self, id: Uuid) -> sqlx::Result<u64> {
+        // Members lose their team, so cached resources go stale too
+        let res = self.inner.delete_team(id).await;
+        lock(&self.resources).invalidate();
+        res
+    }
+
+    async fn set_project_budget(&self, id: Uuid, budget: Option<i64>) -> sqlx::Result<u64> {
+        let res = self.inner.set_project_budget(id, budget).await;
+        lock(&self.projects).invalidate();
+        res
+    }
+
+    async fn set_project_details(
+        &self,
+        id: Uuid,
+        status: domain::ProjectStatus,
+        colour: Option<&str>,
+        description: Option<&str>,
+    ) -> sqlx::Result<u64> {
+        let res = self
+            .inner
+            .set_project_details(id, status, colour, description)
+            .await;
+        lock(&self.projects).invalidate();
+        res
+    }
+
+    async fn set_resource_team(&self, id: Uuid, team_id: Option<Uuid>) -> sqlx::Result<u64> {
+        let res = self.inner.set_resource_team(id, team_id).await;
+        lock(&self.resources).invalidate();
+        res
+    }
+
+    async fn set_resource_region(&self, id: Uuid, region: Option<&str>) -> sqlx::Result<u64> {
+        let res = self.inner.set_resource_region(id, region).await;
+        lock(&self.resources).invalidate();
+        res
+    }
+
+    async fn assign(&self, assignment: &domain::Assignment) -> sqlx::Result<()> {
+        self.inner.assign(assignment).await
+    }
+
+    async fn unassign(&self, assignment: &domain::Assignment) -> sqlx::Result<u64> {
+        self.inner.unassign(assignment).await
+    }
+
+    async fn assign_week(&self, assignment: &domain::WeekAssignment) -> sqlx::Result<()> {
+        self.inner.assign_week(assignment).await
+    }
+
+    async fn unassign_week(&self, assignment: &domain::WeekAssignment) -> sqlx::Result<u64> {
+        self.inner.unassign_week(assignment).await
+    }
+
+    async fn move_assignments(&self, from: Uuid, to: Uuid) -> sqlx::Result<u64> {
+        self.inner.move_assignments(from, to).await
+    }
+
+    async fn confirm_assignments(
+        &self,
+        project_id: Uuid,
+        months: &[(i32, i32)],
+    ) -> sqlx::Result<u64> {
+        self.inner.confirm_assignments(project_id, months).await
+    }
+
+    async fn apply_batch(&self, batch: &domain::Batch) -> sqlx::Result<()> {
+        let res = self.inner.apply_batch(batch).await;
+        lock(&self.projects).invalidate();
+        lock(&self.resources).invalidate();
+        res
+    }
+
+    async fn list_cost_rates(&self) -> sqlx::Result<Vec<domain::CostRate>> {
+        self.inner.list_cost_rates().await
+    }
+
+    async fn set_cost_rate(&self, rate: &domain::CostRate) -> sqlx::Result<()> {
+        self.inner.set_cost_rate(rate).await
+    }
+
+    async fn delete_cost_rate(
+        &self,
+        resource_id: Uuid,
+        year: i32,
+        month: i32,
+    ) -> sqlx::Result<u64> {
+        self.inner.delete_cost_rate(resource_id, year, month).await
+    }
+
+    async fn list_capacities(&self) -> sqlx::Result<Vec<domain::Capacity>> {
+        self.inner.list_capacities().await
+    }
+
+    async fn capacities_in(
+        &self,
+        resource_ids: &[Uuid],
+        months: &[(i32, i32)],
+    ) -> sqlx::Result<Vec<domain::Capacity>> {
+        self.inner.capacities_in(resource_ids, months).await
+    }
+
+    async fn set_capacity(&self, capacity: &domain::Capacity) -> sqlx::Result<()> {
+        self.inner.set_capacity(capacity).await
+    }
+
+    async fn set_capacities(
+        &self,
+        resource_id: Uuid,
+        months: &[(i32, i32)],
+        percent: Option<i32>,
+    ) -> sqlx::Result<()> {
+        self.inner
+            .set_capacities(resource_id, months, percent)
+            .await
+    }
+
+    async fn delete_capacity(&self, resource_id: Uuid, year: i32, month: i32) -> sqlx::Result<u64> {
+        self.inner.delete_capacity(resource_id, year, month).await
+    }
+
+    async fn record_audit(&self, entry: &domain::AuditEntry) -> sqlx::Result<()> {
+        self.inner.record_audit(entry).await
+    }
+
+    async fn list_audit(&self) -> sqlx::Result<Vec<domain::AuditEntry>> {
+        self.inner.list_audit().await
+    }
+
+    async fn user_role(&self, user_id: &str) -> sqlx::Result<Option<domain::Role>> {
+        self.inner.user_role(user_id).await
+    }
+
+    async fn set_user_role(&self, user_id: &str, role: domain::Role) -> sqlx::Result<()> {
+        self.inner.set_user_role(user_id, role).await
+    }
+
+    async fn list_user_roles(&self) -> sqlx::Result<Vec<(String, domain::Role)>> {
+        self.inner.list_user_roles().await
+    }
+
+    async fn is_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<bool> {
+        self.inner.is_project_manager(project_id, user_id).await
+    }
+
+    async fn add_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<()> {
+        self.inner.add_project_manager(project_id, user_id).await
+    }
+
+    async fn remove_project_manager(&self, project_id: Uuid, user_id: &str) -> sqlx::Result<u64> {
+        self.inner.remove_project_manager(project_id, user_id).await
+    }
+
+    async fn list_project_managers(&self) -> sqlx::Result<Vec<(Uuid, String)>> {
+        self.inner.list_project_managers().await
+    }
+}
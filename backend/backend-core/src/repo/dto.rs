@@ -7,12 +7,42 @@ use crate::domain;
 pub(super) struct Project {
     pub id: Uuid,
     pub name: String,
+    pub parent_id: Option<Uuid>,
+    pub budget: Option<i64>,
+    pub status: ProjectStatus,
+    pub colour: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, sqlx::Type)]
+#[sqlx(rename_all = "kebab-case")]
+pub(super) enum ProjectStatus {
+    Proposed,
+    Active,
+    OnHold,
+    Done,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, sqlx::FromRow)]
 pub(super) struct Resource {
     pub id: Uuid,
     pub name: String,
+    pub team_id: Option<Uuid>,
+    pub kind: ResourceKind,
+    pub region: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, sqlx::Type)]
+#[sqlx(rename_all = "lowercase")]
+pub(super) enum ResourceKind {
+    Person,
+    Placeholder,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, sqlx::FromRow)]
+pub(super) struct Team {
+    pub id: Uuid,
+    pub name: String,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, sqlx::FromRow)]
@@ -21,6 +51,68 @@ pub(super) struct Assignment {
     pub project_id: Uuid,
     pub year: i32,
     pub month: i32,
+    pub role: Option<String>,
+    pub note: Option<String>,
+    pub status: AssignmentStatus,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, sqlx::FromRow)]
+pub(super) struct WeekAssignment {
+    pub resource_id: Uuid,
+    pub project_id: Uuid,
+    pub year: i32,
+    pub week: i32,
+    pub role: Option<String>,
+    pub note: Option<String>,
+    pub status: AssignmentStatus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, sqlx::Type)]
+#[sqlx(rename_all = "lowercase")]
+pub(super) enum AssignmentStatus {
+    Tentative,
+    Confirmed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, sqlx::FromRow)]
+pub(super) struct Actual {
+    pub resource_id: Uuid,
+    pub project_id: Uuid,
+    pub year: i32,
+    pub month: i32,
+    pub effort: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, sqlx::FromRow)]
+pub(super) struct Capacity {
+    pub resource_id: Uuid,
+    pub year: i32,
+    pub month: i32,
+    pub percent: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, sqlx::FromRow)]
+pub(super) struct CostRate {
+    pub resource_id: Uuid,
+    pub year: i32,
+    pub month: i32,
+    pub rate: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, sqlx::FromRow)]
+pub(super) struct AuditEntry {
+    pub user_id: String,
+    pub action: String,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, sqlx::FromRow)]
+pub(super) struct Utilisation {
+    pub project_id: Option<Uuid>,
+    pub resource_id: Option<Uuid>,
+    pub year: Option<i32>,
+    pub month: Option<i32>,
+    pub count: i64,
 }
 
 impl From<Project> for domain::Project {
@@ -28,11 +120,45 @@ impl From<Project> for domain::Project {
         Self {
             id: v.id,
             name: v.name,
+            parent_id: v.parent_id,
+            budget: v.budget,
+            status: v.status.into(),
+            colour: v.colour,
+            description: v.description,
+        }
+    }
+}
+impl From<ProjectStatus> for domain::ProjectStatus {
+    fn from(v: ProjectStatus) -> Self {
+        match v {
+            ProjectStatus::Proposed => Self::Proposed,
+            ProjectStatus::Active => Self::Active,
+            ProjectStatus::OnHold => Self::OnHold,
+            ProjectStatus::Done => Self::Done,
         }
     }
 }
 impl From<Resource> for domain::Resource {
     fn from(v: Resource) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+            team_id: v.team_id,
+            kind: v.kind.into(),
+            region: v.region,
+        }
+    }
+}
+impl From<ResourceKind> for domain::ResourceKind {
+    fn from(v: ResourceKind) -> Self {
+        match v {
+            ResourceKind::Person => Self::Person,
+            ResourceKind::Placeholder => Self::Placeholder,
+        }
+    }
+}
+impl From<Team> for domain::Team {
+    fn from(v: Team) -> Self {
         Self {
             id: v.id,
             name: v.name,
@@ -46,6 +172,81 @@ impl From<Assignment> for domain::Assignment {
             project_id: v.project_id,
             year: v.year,
             month: v.month,
+            role: v.role,
+            note: v.note,
+            status: v.status.into(),
+        }
+    }
+}
+impl From<WeekAssignment> for domain::WeekAssignment {
+    fn from(v: WeekAssignment) -> Self {
+        Self {
+            resource_id: v.resource_id,
+            project_id: v.project_id,
+            year: v.year,
+            week: v.week,
+            role: v.role,
+            note: v.note,
+            status: v.status.into(),
+        }
+    }
+}
+impl From<AssignmentStatus> for domain::AssignmentStatus {
+    fn from(v: AssignmentStatus) -> Self {
+        match v {
+            AssignmentStatus::Tentative => Self::Tentative,
+            AssignmentStatus::Confirmed => Self::Confirmed,
+        }
+    }
+}
+impl From<Actual> for domain::Actual {
+    fn from(v: Actual) -> Self {
+        Self {
+            resource_id: v.resource_id,
+            project_id: v.project_id,
+            year: v.year,
+            month: v.month,
+            effort: v.effort,
+        }
+    }
+}
+impl From<Capacity> for domain::Capacity {
+    fn from(v: Capacity) -> Self {
+        Self {
+            resource_id: v.resource_id,
+            year: v.year,
+            month: v.month,
+            percent: v.percent,
+        }
+    }
+}
+impl From<CostRate> for domain::CostRate {
+    fn from(v: CostRate) -> Self {
+        Self {
+            resource_id: v.resource_id,
+            year: v.year,
+            month: v.month,
+            rate: v.rate,
+        }
+    }
+}
+impl From<AuditEntry> for domain::AuditEntry {
+    fn from(v: AuditEntry) -> Self {
+        Self {
+            user_id: v.user_id,
+            action: v.action,
+            detail: v.detail,
+        }
+    }
+}
+impl From<Utilisation> for domain::Utilisation {
+    fn from(v: Utilisation) -> Self {
+        Self {
+            project_id: v.project_id,
+            resource_id: v.resource_id,
+            year: v.year,
+            month: v.month,
+            count: v.count,
         }
     }
 }
@@ -0,0 +1,1324 @@
+//! Reusable checks shared by every [`Repository`] implementation.
+//!
+//! Each backend runs the same scenarios so that in-memory fakes used in unit
+//! tests cannot silently drift from the `SQLite` repository.
+
+use crate::{
+    domain,
+    repo::{AssignmentFilter, Repository, RowFilter, WeekFilter},
+};
+use uuid::Uuid;
+
+/// Runs the full repository conformance suite against `repo`.
+///
+/// The scenarios only assert on the entities they create, so `repo` does not
+/// need to be empty, but it must not be shared with concurrently running tests.
+///
+/// # Panics
+///
+/// Panics if `repo` deviates from the behaviour of [`crate::SqliteRepository`].
+pub async fn run_repository_conformance(repo: &dyn Repository) {
+    ordering(repo).await;
+    upsert_and_lookup(repo).await;
+    idempotent_assign(repo).await;
+    assignment_details(repo).await;
+    unassign_counts(repo).await;
+    cascading_delete(repo).await;
+    unknown_ids(repo).await;
+    atomic_batches(repo).await;
+    audit_trail(repo).await;
+    roles_and_managers(repo).await;
+    project_hierarchy(repo).await;
+    project_details(repo).await;
+    row_queries(repo).await;
+    teams(repo).await;
+    placeholders(repo).await;
+    costs(repo).await;
+    actuals(repo).await;
+    capacities(repo).await;
+    regions(repo).await;
+    week_assignments(repo).await;
+    assignment_status(repo).await;
+    utilisation(repo).await;
+}
+
+async fn ordering(repo: &dyn Repository) {
+    // Insert out of order so that insertion order can't pass for sorting.
+    let p_b = repo.create_project("conformance-order-b").await.unwrap();
+    let p_a = repo.create_project("conformance-order-a").await.unwrap();
+    let r_b = repo.create_resource("conformance-order-b").await.unwrap();
+    let r_a = repo.create_resource("conformance-order-a").await.unwrap();
+
+    let projects = repo.list_projects().await.unwrap();
+    assert!(
+        projects.is_sorted_by(|a, b| a.name <= b.name),
+        "list_projects must be ordered by name"
+    );
+    let resources = repo.list_resources().await.unwrap();
+    assert!(
+        resources.is_sorted_by(|a, b| a.name <= b.name),
+        "list_resources must be ordered by name"
+    );
+
+    for (year, month) in [(2025, 2), (2024, 12), (2025, 1)] {
+        for (resource, project) in [(&r_b, &p_b), (&r_a, &p_a), (&r_a, &p_b)] {
+            repo.assign(&assignment(resource.id, project.id, year, month))
+                .await
+                .unwrap();
+        }
+    }
+    let rows = repo
+        .list_assignments(&AssignmentFilter::default())
+        .await
+        .unwrap();
+    assert!(
+        rows.is_sorted_by_key(|a| (a.year, a.month, a.project_id, a.resource_id)),
+        "list_assignments must be ordered by year, month, project and resource"
+    );
+
+    repo.delete_project(p_a.id).await.unwrap();
+    repo.delete_project(p_b.id).await.unwrap();
+    repo.delete_resource(r_a.id).await.unwrap();
+    repo.delete_resource(r_b.id).await.unwrap();
+}
+
+async fn upsert_and_lookup(repo: &dyn Repository) {
+    let project = domain::Project {
+        id: Uuid::new_v4(),
+        name: "conformance-upsert".into(),
+        parent_id: None,
+        budget: None,
+        status: domain::ProjectStatus::Proposed,
+        colour: Some("#1f77b4".into()),
+        description: Some("Pipeline work".into()),
+    };
+    repo.upsert_project(&project).await.unwrap();
+    let renamed = domain::Project {
+        name: "conformance-upsert-renamed".into(),
+        ..project.clone()
+    };
+    repo.upsert_project(&renamed).await.unwrap();
+    assert_eq!(repo.projects(&[project.id]).await.unwrap(), vec![renamed]);
+
+    let resource = domain::Resource {
+        id: Uuid::new_v4(),
+        name: "conformance-upsert".into(),
+        team_id: None,
+        kind: domain::ResourceKind::Person,
+        region: None,
+    };
+    repo.upsert_resource(&resource).await.unwrap();
+    let renamed = domain::Resource {
+        name: "conformance-upsert-renamed".into(),
+        ..resource.clone()
+    };
+    repo.upsert_resource(&renamed).await.unwrap();
+    assert_eq!(repo.resources(&[resource.id]).await.unwrap(), vec![renamed]);
+
+    assert!(repo.projects(&[]).await.unwrap().is_empty());
+    assert!(repo.resources(&[]).await.unwrap().is_empty());
+
+    repo.delete_project(project.id).await.unwrap();
+    repo.delete_resource(resource.id).await.unwrap();
+}
+
+async fn idempotent_assign(repo: &dyn Repository) {
+    let p = repo.create_project("conformance-assign").await.unwrap();
+    let r = repo.create_resource("conformance-assign").await.unwrap();
+    let a = assignment(r.id, p.id, 2024, 3);
+
+    repo.assign(&a).await.unwrap();
+    repo.assign(&a).await.unwrap();
+
+    let rows = repo.list_assignments(&by_project(p.id)).await.unwrap();
+    assert_eq!(rows, vec![a], "assigning twice must not duplicate rows");
+
+    repo.delete_project(p.id).await.unwrap();
+    repo.delete_resource(r.id).await.unwrap();
+}
+
+async fn assignment_details(repo: &dyn Repository) {
+    let p = repo.create_project("conformance-details").await.unwrap();
+    let r = repo.create_resource("conformance-details").await.unwrap();
+    let mut a = assignment(r.id, p.id, 2024, 3);
+    a.role = Some("Tech Lead".into());
+    a.note = Some("Mornings only".into());
+    repo.assign(&a).await.unwrap();
+    assert_eq!(
+        repo.list_assignments(&by_project(p.id)).await.unwrap(),
+        vec![a.clone()]
+    );
+
+    // Assigning again replaces the details rather than adding a row.
+    a.role = Some("Reviewer".into());
+    a.note = None;
+    repo.assign(&a).await.unwrap();
+    assert_eq!(
+        repo.list_assignments(&by_project(p.id)).await.unwrap(),
+        vec![a],
+        "re-assigning must update role and note in place"
+    );
+
+    // Unassigning matches on the key alone.
+    assert_eq!(
+        repo.unassign(&assignment(r.id, p.id, 2024, 3))
+            .await
+            .unwrap(),
+        1
+    );
+
+    repo.delete_project(p.id).await.unwrap();
+    repo.delete_resource(r.id).await.unwrap();
+}
+
+async fn unassign_counts(repo: &dyn Repository) {
+    let p = repo.create_project("conformance-unassign").await.unwrap();
+    let r = repo.create_resource("conformance-unassign").await.unwrap();
+    let a = assignment(r.id, p.id, 2024, 3);
+
+    repo.assign(&a).await.unwrap();
+    assert_eq!(repo.unassign(&a).await.unwrap(), 1);
+    assert_eq!(repo.unassign(&a).await.unwrap(), 0);
+    assert!(
+        repo.list_assignments(&by_project(p.id))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    repo.delete_project(p.id).await.unwrap();
+    repo.delete_resource(r.id).await.unwrap();
+}
+
+async fn cascading_delete(repo: &dyn Repository) {
+    let p1 = repo.create_project("conformance-cascade-1").await.unwrap();
+    let p2 = repo.create_project("conformance-cascade-2").await.unwrap();
+    let r1 = repo.create_resource("conformance-cascade-1").await.unwrap();
+    let r2 = repo.create_resource("conformance-cascade-2").await.unwrap();
+    for (r, p) in [(&r1, &p1), (&r1, &p2), (&r2, &p1), (&r2, &p2)] {
+        repo.assign(&assignment(r.id, p.id, 2024, 5)).await.unwrap();
+    }
+
+    assert_eq!(repo.delete_project(p1.id).await.unwrap(), 1);
+    assert!(
+        repo.list_assignments(&by_project(p1.id))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        repo.list_assignments(&by_project(p2.id))
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    assert_eq!(repo.delete_resource(r1.id).await.unwrap(), 1);
+    let remaining = repo.list_assignments(&by_project(p2.id)).await.unwrap();
+    assert_eq!(remaining, vec![assignment(r2.id, p2.id, 2024, 5)]);
+
+    repo.delete_project(p2.id).await.unwrap();
+    assert!(
+        repo.list_assignments(&AssignmentFilter {
+            resource_id: Some(r2.id),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .is_empty()
+    );
+    repo.delete_resource(r2.id).await.unwrap();
+}
+
+async fn unknown_ids(repo: &dyn Repository) {
+    let unknown = Uuid::new_v4();
+
+    assert!(repo.projects(&[unknown]).await.unwrap().is_empty());
+    assert!(repo.resources(&[unknown]).await.unwrap().is_empty());
+    assert_eq!(repo.delete_project(unknown).await.unwrap(), 0);
+    assert_eq!(repo.delete_resource(unknown).await.unwrap(), 0);
+    assert_eq!(
+        repo.unassign(&assignment(unknown, unknown, 2024, 1))
+            .await
+            .unwrap(),
+        0
+    );
+
+    // Assignments must reference existing rows on both sides.
+    let p = repo.create_project("conformance-unknown").await.unwrap();
+    let r = repo.create_resource("conformance-unknown").await.unwrap();
+    assert!(
+        repo.assign(&assignment(unknown, p.id, 2024, 1))
+            .await
+            .is_err()
+    );
+    assert!(
+        repo.assign(&assignment(r.id, unknown, 2024, 1))
+            .await
+            .is_err()
+    );
+    assert!(
+        repo.list_assignments(&by_project(p.id))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    repo.delete_project(p.id).await.unwrap();
+    repo.delete_resource(r.id).await.unwrap();
+}
+
+#[allow(clippy::too_many_lines)]
+async fn atomic_batches(repo: &dyn Repository) {
+    let project = domain::Project {
+        id: Uuid::new_v4(),
+        name: "conformance-batch".into(),
+        parent_id: None,
+        budget: None,
+        status: domain::ProjectStatus::default(),
+        colour: None,
+        description: None,
+    };
+    let resource = domain::Resource {
+        id: Uuid::new_v4(),
+        name: "conformance-batch".into(),
+        team_id: None,
+        kind: domain::ResourceKind::Person,
+        region: None,
+    };
+    let a = assignment(resource.id, project.id, 2024, 6);
+    let managers = vec![(project.id, "conformance-batch-user".to_string())];
+    let rate = domain::CostRate {
+        resource_id: resource.id,
+        year: 2024,
+        month: 6,
+        rate: 5000,
+    };
+    let capacity = domain::Capacity {
+        resource_id: resource.id,
+        year: 2024,
+        month: 6,
+        percent: 50,
+    };
+    let roles = vec![("conformance-batch-user".to_string(), domain::Role::Planner)];
+    let own = |resource_id| resource_id == resource.id;
+
+    // A dangling assignment fails the whole batch.
+    let failing = domain::Batch {
+        projects: vec![project.clone()],
+        resources: vec![resource.clone()],
+        assignments: vec![a.clone(), assignment(Uuid::new_v4(), project.id, 2024, 7)],
+        cost_rates: vec![rate.clone()],
+        capacities: vec![capacity.clone()],
+        project_managers: managers.clone(),
+        user_roles: roles.clone(),
+        ..Default::default()
+    };
+    assert!(repo.apply_batch(&failing).await.is_err());
+    assert!(repo.projects(&[project.id]).await.unwrap().is_empty());
+    assert!(repo.resources(&[resource.id]).await.unwrap().is_empty());
+    assert!(
+        !repo
+            .is_project_manager(project.id, "conformance-batch-user")
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        repo.user_role("conformance-batch-user").await.unwrap(),
+        None
+    );
+    let rates = repo.list_cost_rates().await.unwrap();
+    assert!(!rates.iter().any(|c| own(c.resource_id)));
+    let capacities = repo.list_capacities().await.unwrap();
+    assert!(!capacities.iter().any(|c| own(c.resource_id)));
+
+    let batch = domain::Batch {
+        projects: vec![project.clone()],
+        resources: vec![resource.clone()],
+        assignments: vec![a.clone()],
+        cost_rates: vec![rate.clone()],
+        capacities: vec![capacity.clone()],
+        project_managers: managers,
+        user_roles: roles,
+        ..Default::default()
+    };
+    repo.apply_batch(&batch).await.unwrap();
+    // Re-applying is a no-op.
+    repo.apply_batch(&batch).await.unwrap();
+    assert_eq!(
+        repo.projects(&[project.id]).await.unwrap(),
+        vec![project.clone()]
+    );
+    assert_eq!(
+        repo.resources(&[resource.id]).await.unwrap(),
+        vec![resource.clone()]
+    );
+    assert_eq!(
+        repo.list_assignments(&by_project(project.id))
+            .await
+            .unwrap(),
+        vec![a]
+    );
+    assert!(
+        repo.is_project_manager(project.id, "conformance-batch-user")
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        repo.user_role("conformance-batch-user").await.unwrap(),
+        Some(domain::Role::Planner)
+    );
+    let rates = repo.list_cost_rates().await.unwrap();
+    assert_eq!(
+        rates
+            .into_iter()
+            .filter(|c| own(c.resource_id))
+            .collect::<Vec<_>>(),
+        vec![rate]
+    );
+    let capacities = repo.list_capacities().await.unwrap();
+    assert_eq!(
+        capacities
+            .into_iter()
+            .filter(|c| own(c.resource_id))
+            .collect::<Vec<_>>(),
+        vec![capacity]
+    );
+
+    repo.delete_project(project.id).await.unwrap();
+    repo.delete_resource(resource.id).await.unwrap();
+}
+
+async fn audit_trail(repo: &dyn Repository) {
+    let entries: Vec<_> = ["first", "second"]
+        .into_iter()
+        .map(|detail| domain::AuditEntry {
+            user_id: "conformance-user".into(),
+            action: "conformance".into(),
+            detail: detail.into(),
+        })
+        .collect();
+    for entry in &entries {
+        repo.record_audit(entry).await.unwrap();
+    }
+
+    let trail = repo.list_audit().await.unwrap();
+    assert!(
+        trail.ends_with(&entries),
+        "list_audit must return entries in the order they were recorded"
+    );
+}
+
+async fn roles_and_managers(repo: &dyn Repository) {
+    let user = format!("conformance-{}", Uuid::new_v4());
+    assert_eq!(repo.user_role(&user).await.unwrap(), None);
+    repo.set_user_role(&user, domain::Role::Planner)
+        .await
+        .unwrap();
+    repo.set_user_role(&user, domain::Role::Admin)
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.user_role(&user).await.unwrap(),
+        Some(domain::Role::Admin)
+    );
+    let roles = repo.list_user_roles().await.unwrap();
+    assert!(roles.contains(&(user.clone(), domain::Role::Admin)));
+    assert!(roles.is_sorted(), "list_user_roles must be ordered by user");
+
+    let p = repo.create_project("conformance-managers").await.unwrap();
+    assert!(!repo.is_project_manager(p.id, &user).await.unwrap());
+    repo.add_project_manager(p.id, &user).await.unwrap();
+    repo.add_project_manager(p.id, &user).await.unwrap();
+    assert!(repo.is_project_manager(p.id, &user).await.unwrap());
+    let managers = repo.list_project_managers().await.unwrap();
+    assert_eq!(
+        managers
+            .iter()
+            .filter(|m| **m == (p.id, user.clone()))
+            .count(),
+        1
+    );
+    assert!(
+        managers.is_sorted(),
+        "list_project_managers must be ordered by project and user"
+    );
+    assert_eq!(repo.remove_project_manager(p.id, &user).await.unwrap(), 1);
+    assert_eq!(repo.remove_project_manager(p.id, &user).await.unwrap(), 0);
+    assert!(
+        repo.add_project_manager(Uuid::new_v4(), &user)
+            .await
+            .is_err()
+    );
+
+    // Managers go away with their project.
+    repo.add_project_manager(p.id, &user).await.unwrap();
+    repo.delete_project(p.id).await.unwrap();
+    assert!(!repo.is_project_manager(p.id, &user).await.unwrap());
+}
+
+async fn project_hierarchy(repo: &dyn Repository) {
+    let programme = domain::Project {
+        id: Uuid::new_v4(),
+        name: "conformance-programme".into(),
+        parent_id: None,
+        budget: None,
+        status: domain::ProjectStatus::default(),
+        colour: None,
+        description: None,
+    };
+    let child = domain::Project {
+        id: Uuid::new_v4(),
+        name: "conformance-child".into(),
+        parent_id: Some(programme.id),
+        budget: None,
+        status: domain::ProjectStatus::default(),
+        colour: None,
+        description: None,
+    };
+    // Children may precede their parent within a batch.
+    let batch = domain::Batch {
+        projects: vec![child.clone(), programme.clone()],
+        ..Default::default()
+    };
+    repo.apply_batch(&batch).await.unwrap();
+    assert_eq!(
+        repo.projects(&[child.id]).await.unwrap(),
+        vec![child.clone()]
+    );
+    assert!(
+        repo.list_projects().await.unwrap().contains(&child),
+        "list_projects must include parent ids"
+    );
+
+    let orphan = domain::Project {
+        id: Uuid::new_v4(),
+        name: "conformance-orphan".into(),
+        parent_id: Some(Uuid::new_v4()),
+        budget: None,
+        status: domain::ProjectStatus::default(),
+        colour: None,
+        description: None,
+    };
+    assert!(repo.upsert_project(&orphan).await.is_err());
+    assert!(
+        repo.set_project_parent(child.id, Some(Uuid::new_v4()))
+            .await
+            .is_err()
+    );
+
+    assert_eq!(repo.set_project_parent(child.id, None).await.unwrap(), 1);
+    assert_eq!(repo.projects(&[child.id]).await.unwrap()[0].parent_id, None);
+    assert_eq!(
+        repo.set_project_parent(Uuid::new_v4(), None).await.unwrap(),
+        0
+    );
+
+    // Deleting a programme leaves its children at the top level.
+    repo.set_project_parent(child.id, Some(programme.id))
+        .await
+        .unwrap();
+    repo.delete_project(programme.id).await.unwrap();
+    assert_eq!(repo.projects(&[child.id]).await.unwrap()[0].parent_id, None);
+
+    repo.delete_project(child.id).await.unwrap();
+}
+
+async fn teams(repo: &dyn Repository) {
+    let b = repo.create_team("conformance-team-b").await.unwrap();
+    let a = repo.create_team("conformance-team-a").await.unwrap();
+    let teams = repo.list_teams().await.unwrap();
+    assert!(
+        teams.is_sorted_by(|x, y| x.name <= y.name),
+        "list_teams must be ordered by name"
+    );
+    let renamed = domain::Team {
+        name: "conformance-team-renamed".into(),
+        ..b.clone()
+    };
+    repo.upsert_team(&renamed).await.unwrap();
+    assert_eq!(repo.teams(&[b.id]).await.unwrap(), vec![renamed]);
+    assert!(repo.teams(&[]).await.unwrap().is_empty());
+
+    let r = repo.create_resource("conformance-team").await.unwrap();
+    assert_eq!(repo.set_resource_team(r.id, Some(a.id)).await.unwrap(), 1);
+    assert_eq!(
+        repo.resources(&[r.id]).await.unwrap()[0].team_id,
+        Some(a.id)
+    );
+    assert!(
+        repo.list_resources()
+            .await
+            .unwrap()
+            .iter()
+            .any(|x| x.id == r.id && x.team_id == Some(a.id)),
+        "list_resources must include team ids"
+    );
+    assert!(
+        repo.set_resource_team(r.id, Some(Uuid::new_v4()))
+            .await
+            .is_err()
+    );
+    assert_eq!(
+        repo.set_resource_team(Uuid::new_v4(), None).await.unwrap(),
+        0
+    );
+
+    // A batch may create a team alongside its members.
+    let team = domain::Team {
+        id: Uuid::new_v4(),
+        name: "conformance-team-batch".into(),
+    };
+    let member = domain::Resource {
+        id: Uuid::new_v4(),
+        name: "conformance-team-batch".into(),
+        team_id: Some(team.id),
+        kind: domain::ResourceKind::Person,
+        region: None,
+    };
+    let batch = domain::Batch {
+        teams: vec![team.clone()],
+        resources: vec![member.clone()],
+        ..Default::default()
+    };
+    repo.apply_batch(&batch).await.unwrap();
+    assert_eq!(
+        repo.resources(&[member.id]).await.unwrap(),
+        vec![member.clone()]
+    );
+
+    // Deleting a team leaves its members without one.
+    assert_eq!(repo.delete_team(a.id).await.unwrap(), 1);
+    assert_eq!(repo.delete_team(a.id).await.unwrap(), 0);
+    assert_eq!(repo.resources(&[r.id]).await.unwrap()[0].team_id, None);
+
+    repo.delete_resource(r.id).await.unwrap();
+    repo.delete_resource(member.id).await.unwrap();
+    repo.delete_team(b.id).await.unwrap();
+    repo.delete_team(team.id).await.unwrap();
+}
+
+async fn placeholders(repo: &dyn Repository) {
+    let placeholder = domain::Resource {
+        id: Uuid::new_v4(),
+        name: "conformance-placeholder".into(),
+        team_id: None,
+        kind: domain::ResourceKind::Placeholder,
+        region: None,
+    };
+    repo.upsert_resource(&placeholder).await.unwrap();
+    assert_eq!(
+        repo.resources(&[placeholder.id]).await.unwrap(),
+        vec![placeholder.clone()]
+    );
+    assert!(
+        repo.list_resources().await.unwrap().contains(&placeholder),
+        "list_resources must include resource kinds"
+    );
+
+    let person = repo.create_resource("conformance-hire").await.unwrap();
+    assert_eq!(person.kind, domain::ResourceKind::Person);
+    let p = repo
+        .create_project("conformance-placeholder")
+        .await
+        .unwrap();
+    let mut planned = assignment(placeholder.id, p.id, 2024, 1);
+    planned.role = Some("Backend Engineer".into());
+    repo.assign(&planned).await.unwrap();
+    repo.assign(&assignment(placeholder.id, p.id, 2024, 2))
+        .await
+        .unwrap();
+    // The person's own assignment wins where both are booked, and the
+    // placeholder's is dropped without counting as moved.
+    let mut existing = assignment(person.id, p.id, 2024, 2);
+    existing.note = Some("Already booked".into());
+    repo.assign(&existing).await.unwrap();
+
+    assert_eq!(
+        repo.move_assignments(placeholder.id, person.id)
+            .await
+            .unwrap(),
+        1
+    );
+    let rows = repo.list_assignments(&by_project(p.id)).await.unwrap();
+    let moved = domain::Assignment {
+        resource_id: person.id,
+        ..planned
+    };
+    assert_eq!(rows, vec![moved, existing]);
+    assert_eq!(
+        repo.move_assignments(placeholder.id, person.id)
+            .await
+            .unwrap(),
+        0
+    );
+
+    repo.delete_project(p.id).await.unwrap();
+    repo.delete_resource(placeholder.id).await.unwrap();
+    repo.delete_resource(person.id).await.unwrap();
+}
+
+async fn project_details(repo: &dyn Repository) {
+    let p = repo.create_project("conformance-details").await.unwrap();
+    assert_eq!(p.status, domain::ProjectStatus::Active);
+    assert_eq!(
+        repo.set_project_details(
+            p.id,
+            domain::ProjectStatus::OnHold,
+            Some("#ff7f0e"),
+            Some("Waiting on sign-off")
+        )
+        .await
+        .unwrap(),
+        1
+    );
+    let listed = repo.list_projects().await.unwrap();
+    let got = listed.iter().find(|x| x.id == p.id).unwrap();
+    assert_eq!(got.status, domain::ProjectStatus::OnHold);
+    assert_eq!(got.colour.as_deref(), Some("#ff7f0e"));
+    assert_eq!(got.description.as_deref(), Some("Waiting on sign-off"));
+    assert_eq!(repo.projects(&[p.id]).await.unwrap(), vec![got.clone()]);
+
+    repo.set_project_details(p.id, domain::ProjectStatus::Done, None, None)
+        .await
+        .unwrap();
+    let got = repo.projects(&[p.id]).await.unwrap().remove(0);
+    assert_eq!(
+        (got.status, got.colour, got.description),
+        (domain::ProjectStatus::Done, None, None)
+    );
+    assert_eq!(
+        repo.set_project_details(Uuid::new_v4(), domain::ProjectStatus::Done, None, None)
+            .await
+            .unwrap(),
+        0
+    );
+    repo.delete_project(p.id).await.unwrap();
+}
+
+async fn row_queries(repo: &dyn Repository) {
+    // Names share a prefix no other scenario uses; "b" appears twice so that
+    // ids break the tie.
+    let mut projects = Vec::new();
+    for name in [
+        "conformance-rows-c",
+        "conformance-rows-b",
+        "conformance-ROWS-a",
+    ] {
+        projects.push(repo.create_project(name).await.unwrap());
+    }
+    projects.push(repo.create_project("conformance-rows-b").await.unwrap());
+    repo.set_project_parent(projects[0].id, Some(projects[1].id))
+        .await
+        .unwrap();
+    repo.set_project_details(projects[2].id, domain::ProjectStatus::Done, None, None)
+        .await
+        .unwrap();
+    let resource = repo.create_resource("conformance-rows-r").await.unwrap();
+
+    let search = RowFilter {
+        search: Some("Conformance-Rows".into()),
+        ..Default::default()
+    };
+    let names = |rows: Vec<domain::Project>| -> Vec<(String, Uuid)> {
+        rows.into_iter().map(|p| (p.name, p.id)).collect()
+    };
+    let mut expected: Vec<_> = projects.iter().map(|p| (p.name.clone(), p.id)).collect();
+    expected.sort();
+    assert_eq!(
+        names(repo.find_projects(&search).await.unwrap()),
+        expected,
+        "find_projects must be ordered by name then id"
+    );
+    assert_eq!(repo.count_projects(&search).await.unwrap(), 4);
+
+    let page = RowFilter {
+        after: Some(expected[1].clone()),
+        limit: Some(2),
+        ..search.clone()
+    };
+    assert_eq!(
+        names(repo.find_projects(&page).await.unwrap()),
+        expected[2..4]
+    );
+    assert_eq!(repo.count_projects(&page).await.unwrap(), 4);
+    let descending = RowFilter {
+        descending: true,
+        after: Some(expected[2].clone()),
+        ..search.clone()
+    };
+    assert_eq!(
+        names(repo.find_projects(&descending).await.unwrap()),
+        [expected[1].clone(), expected[0].clone()]
+    );
+
+    let narrowed = RowFilter {
+        ids: Some(vec![projects[0].id, projects[2].id, projects[3].id]),
+        statuses: Some(vec![domain::ProjectStatus::Active]),
+        top_level: true,
+        ..search.clone()
+    };
+    assert_eq!(
+        repo.find_projects(&narrowed).await.unwrap(),
+        vec![projects[3].clone()]
+    );
+    let none = RowFilter {
+        ids: Some(vec![]),
+        ..Default::default()
+    };
+    assert_eq!(repo.count_projects(&none).await.unwrap(), 0);
+
+    // Resources ignore the project-only conditions.
+    let resources = RowFilter {
+        statuses: Some(vec![]),
+        top_level: true,
+        ..search
+    };
+    assert_eq!(
+        repo.find_resources(&resources).await.unwrap(),
+        vec![resource.clone()]
+    );
+    assert_eq!(repo.count_resources(&resources).await.unwrap(), 1);
+
+    for p in &projects {
+        repo.delete_project(p.id).await.unwrap();
+    }
+    repo.delete_resource(resource.id).await.unwrap();
+}
+
+async fn costs(repo: &dyn Repository) {
+    let p = repo.create_project("conformance-costs").await.unwrap();
+    assert_eq!(repo.set_project_budget(p.id, Some(5000)).await.unwrap(), 1);
+    assert_eq!(repo.projects(&[p.id]).await.unwrap()[0].budget, Some(5000));
+    assert!(
+        repo.list_projects()
+            .await
+            .unwrap()
+            .iter()
+            .any(|x| x.id == p.id && x.budget == Some(5000)),
+        "list_projects must include budgets"
+    );
+    repo.set_project_budget(p.id, None).await.unwrap();
+    assert_eq!(repo.projects(&[p.id]).await.unwrap()[0].budget, None);
+    assert_eq!(
+        repo.set_project_budget(Uuid::new_v4(), Some(1))
+            .await
+            .unwrap(),
+        0
+    );
+
+    let r = repo.create_resource("conformance-costs").await.unwrap();
+    let rate = |year, month, rate| domain::CostRate {
+        resource_id: r.id,
+        year,
+        month,
+        rate,
+    };
+    // Insert out of order so that insertion order can't pass for sorting.
+    repo.set_cost_rate(&rate(2025, 1, 900)).await.unwrap();
+    repo.set_cost_rate(&rate(2024, 7, 800)).await.unwrap();
+    repo.set_cost_rate(&rate(2025, 1, 950)).await.unwrap();
+    let own = |all: Vec<domain::CostRate>| -> Vec<domain::CostRate> {
+        all.into_iter().filter(|c| c.resource_id == r.id).collect()
+    };
+    assert_eq!(
+        own(repo.list_cost_rates().await.unwrap()),
+        vec![rate(2024, 7, 800), rate(2025, 1, 950)]
+    );
+    let all = repo.list_cost_rates().await.unwrap();
+    assert!(
+        all.is_sorted_by_key(|c| (c.resource_id, c.year, c.month)),
+        "list_cost_rates must be ordered by resource and month"
+    );
+    assert!(
+        repo.set_cost_rate(&domain::CostRate {
+            resource_id: Uuid::new_v4(),
+            ..rate(2025, 1, 1)
+        })
+        .await
+        .is_err()
+    );
+
+    assert_eq!(repo.delete_cost_rate(r.id, 2024, 7).await.unwrap(), 1);
+    assert_eq!(repo.delete_cost_rate(r.id, 2024, 7).await.unwrap(), 0);
+    // Rates go with their resource.
+    repo.delete_resource(r.id).await.unwrap();
+    assert!(own(repo.list_cost_rates().await.unwrap()).is_empty());
+
+    repo.delete_project(p.id).await.unwrap();
+}
+
+async fn actuals(repo: &dyn Repository) {
+    let p = repo.create_project("conformance-actuals").await.unwrap();
+    let r = repo.create_resource("conformance-actuals").await.unwrap();
+    let actual = |year, month, effort| domain::Actual {
+        resource_id: r.id,
+        project_id: p.id,
+        year,
+        month,
+        effort,
+    };
+    let batch = |actuals| domain::Batch {
+        actuals,
+        ..Default::default()
+    };
+    // Insert out of order so that insertion order can't pass for sorting.
+    repo.apply_batch(&batch(vec![actual(2025, 3, 50), actual(2025, 1, 100)]))
+        .await
+        .unwrap();
+    // Recording the same month again replaces the effort.
+    repo.apply_batch(&batch(vec![actual(2025, 3, 80)]))
+        .await
+        .unwrap();
+    let filter = AssignmentFilter {
+        project_id: Some(p.id),
+        ..Default::default()
+    };
+    assert_eq!(
+        repo.list_actuals(&filter).await.unwrap(),
+        vec![actual(2025, 1, 100), actual(2025, 3, 80)]
+    );
+    let march = AssignmentFilter {
+        month: Some(3),
+        ..filter.clone()
+    };
+    assert_eq!(
+        repo.list_actuals(&march).await.unwrap(),
+        vec![actual(2025, 3, 80)]
+    );
+    assert!(
+        repo.list_assignments(&filter).await.unwrap().is_empty(),
+        "actuals are not assignments"
+    );
+
+    // An actual for an unknown resource fails the whole batch.
+    let bad = batch(vec![
+        actual(2025, 4, 10),
+        domain::Actual {
+            resource_id: Uuid::new_v4(),
+            ..actual(2025, 4, 10)
+        },
+    ]);
+    assert!(repo.apply_batch(&bad).await.is_err());
+    assert_eq!(repo.list_actuals(&filter).await.unwrap().len(), 2);
+
+    // Actuals go with their project.
+    repo.delete_project(p.id).await.unwrap();
+    assert!(repo.list_actuals(&filter).await.unwrap().is_empty());
+    repo.delete_resource(r.id).await.unwrap();
+}
+
+async fn capacities(repo: &dyn Repository) {
+    let r = repo
+        .create_resource("conformance-capacities")
+        .await
+        .unwrap();
+    let capacity = |year, month, percent| domain::Capacity {
+        resource_id: r.id,
+        year,
+        month,
+        percent,
+    };
+    // Insert out of order so that insertion order can't pass for sorting.
+    repo.set_capacity(&capacity(2025, 8, 0)).await.unwrap();
+    repo.set_capacity(&capacity(2025, 2, 50)).await.unwrap();
+    repo.set_capacity(&capacity(2025, 8, 20)).await.unwrap();
+    let own = |all: Vec<domain::Capacity>| -> Vec<domain::Capacity> {
+        all.into_iter().filter(|c| c.resource_id == r.id).collect()
+    };
+    assert_eq!(
+        own(repo.list_capacities().await.unwrap()),
+        vec![capacity(2025, 2, 50), capacity(2025, 8, 20)]
+    );
+    assert!(
+        repo.list_capacities().await.unwrap().is_sorted_by_key(|c| (
+            c.resource_id,
+            c.year,
+            c.month
+        )),
+        "list_capacities must be ordered by resource and month"
+    );
+    assert!(
+        repo.set_capacity(&domain::Capacity {
+            resource_id: Uuid::new_v4(),
+            ..capacity(2025, 1, 100)
+        })
+        .await
+        .is_err()
+    );
+
+    assert_eq!(
+        repo.capacities_in(&[r.id], &[(2025, 8), (2025, 9)])
+            .await
+            .unwrap(),
+        vec![capacity(2025, 8, 20)]
+    );
+    assert!(
+        repo.capacities_in(&[Uuid::new_v4()], &[(2025, 8)])
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(repo.capacities_in(&[r.id], &[]).await.unwrap().is_empty());
+
+    assert_eq!(repo.delete_capacity(r.id, 2025, 2).await.unwrap(), 1);
+    assert_eq!(repo.delete_capacity(r.id, 2025, 2).await.unwrap(), 0);
+
+    // Ranges are written whole, and `None` clears them.
+    let range = [(2026, 1), (2026, 2), (2026, 3)];
+    repo.set_capacities(r.id, &range, Some(60)).await.unwrap();
+    assert_eq!(
+        repo.capacities_in(&[r.id], &range).await.unwrap(),
+        vec![
+            capacity(2026, 1, 60),
+            capacity(2026, 2, 60),
+            capacity(2026, 3, 60)
+        ]
+    );
+    repo.set_capacities(r.id, &range[1..], None).await.unwrap();
+    assert_eq!(
+        repo.capacities_in(&[r.id], &range).await.unwrap(),
+        vec![capacity(2026, 1, 60)]
+    );
+    assert!(
+        repo.set_capacities(Uuid::new_v4(), &range, Some(60))
+            .await
+            .is_err()
+    );
+    // Capacities go with their resource.
+    repo.delete_resource(r.id).await.unwrap();
+    assert!(own(repo.list_capacities().await.unwrap()).is_empty());
+}
+
+async fn regions(repo: &dyn Repository) {
+    let r = repo.create_resource("conformance-regions").await.unwrap();
+    assert_eq!(r.region, None);
+    assert_eq!(repo.set_resource_region(r.id, Some("DE")).await.unwrap(), 1);
+    assert_eq!(
+        repo.resources(&[r.id]).await.unwrap()[0].region.as_deref(),
+        Some("DE")
+    );
+    assert!(
+        repo.list_resources()
+            .await
+            .unwrap()
+            .iter()
+            .any(|x| x.id == r.id && x.region.as_deref() == Some("DE")),
+        "list_resources must include regions"
+    );
+
+    // Upserts write the region like any other column.
+    let moved = domain::Resource {
+        region: Some("UK".into()),
+        ..r.clone()
+    };
+    repo.upsert_resource(&moved).await.unwrap();
+    assert_eq!(repo.resources(&[r.id]).await.unwrap(), vec![moved]);
+
+    repo.set_resource_region(r.id, None).await.unwrap();
+    assert_eq!(repo.resources(&[r.id]).await.unwrap()[0].region, None);
+    assert_eq!(
+        repo.set_resource_region(Uuid::new_v4(), Some("UK"))
+            .await
+            .unwrap(),
+        0
+    );
+    repo.delete_resource(r.id).await.unwrap();
+}
+
+async fn week_assignments(repo: &dyn Repository) {
+    let p = repo.create_project("conformance-weeks").await.unwrap();
+    let r = repo.create_resource("conformance-weeks").await.unwrap();
+    let week = |year, week| domain::WeekAssignment {
+        resource_id: r.id,
+        project_id: p.id,
+        year,
+        week,
+        role: None,
+        note: None,
+        status: domain::AssignmentStatus::Confirmed,
+    };
+    let filter = WeekFilter {
+        project_id: Some(p.id),
+        ..Default::default()
+    };
+    // Insert out of order so that insertion order can't pass for sorting.
+    repo.assign_week(&week(2026, 2)).await.unwrap();
+    repo.assign_week(&week(2025, 52)).await.unwrap();
+    let mut lead = week(2026, 1);
+    lead.role = Some("Tech Lead".into());
+    repo.assign_week(&week(2026, 1)).await.unwrap();
+    // Assigning the same week again replaces the role.
+    repo.assign_week(&lead).await.unwrap();
+    assert_eq!(
+        repo.list_week_assignments(&filter).await.unwrap(),
+        vec![week(2025, 52), lead.clone(), week(2026, 2)]
+    );
+    let around_new_year = WeekFilter {
+        from: domain::IsoWeek::new(2025, 52),
+        to: domain::IsoWeek::new(2026, 1),
+        ..filter.clone()
+    };
+    assert_eq!(
+        repo.list_week_assignments(&around_new_year).await.unwrap(),
+        vec![week(2025, 52), lead.clone()]
+    );
+    assert!(
+        repo.list_assignments(&by_project(p.id))
+            .await
+            .unwrap()
+            .is_empty(),
+        "week assignments are not monthly assignments"
+    );
+    assert_eq!(repo.unassign_week(&week(2026, 2)).await.unwrap(), 1);
+    assert_eq!(repo.unassign_week(&week(2026, 2)).await.unwrap(), 0);
+    assert!(
+        repo.assign_week(&domain::WeekAssignment {
+            resource_id: Uuid::new_v4(),
+            ..week(2026, 3)
+        })
+        .await
+        .is_err()
+    );
+
+    // Weeks move with the rest of a resource's assignments.
+    let other = repo.create_resource("conformance-weeks-2").await.unwrap();
+    let batch = domain::Batch {
+        week_assignments: vec![domain::WeekAssignment {
+            resource_id: other.id,
+            ..week(2026, 1)
+        }],
+        ..Default::default()
+    };
+    repo.apply_batch(&batch).await.unwrap();
+    // Only 2025-W52 is new to the target.
+    assert_eq!(repo.move_assignments(r.id, other.id).await.unwrap(), 1);
+    let moved: Vec<_> = repo
+        .list_week_assignments(&filter)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|a| (a.resource_id, a.year, a.week, a.role))
+        .collect();
+    assert_eq!(
+        moved,
+        vec![(other.id, 2025, 52, None), (other.id, 2026, 1, None)],
+        "the target's own week is kept"
+    );
+
+    // Week assignments go with their project.
+    repo.delete_project(p.id).await.unwrap();
+    assert!(
+        repo.list_week_assignments(&filter)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    repo.delete_resource(r.id).await.unwrap();
+    repo.delete_resource(other.id).await.unwrap();
+}
+
+async fn utilisation(repo: &dyn Repository) {
+    use domain::UtilisationGroup::{Month, Project, Resource};
+
+    // An unusual year keeps month-level counts clear of other scenarios' rows.
+    let p1 = repo.create_project("conformance-util-1").await.unwrap();
+    let p2 = repo.create_project("conformance-util-2").await.unwrap();
+    let r1 = repo.create_resource("conformance-util-1").await.unwrap();
+    let r2 = repo.create_resource("conformance-util-2").await.unwrap();
+    for a in [
+        assignment(r1.id, p1.id, 1901, 1),
+        assignment(r1.id, p2.id, 1901, 1),
+        assignment(r2.id, p1.id, 1901, 1),
+        assignment(r2.id, p1.id, 1901, 2),
+        // Outside the window
+        assignment(r2.id, p2.id, 1901, 3),
+    ] {
+        repo.assign(&a).await.unwrap();
+    }
+    // W02 falls in January, where r1 already has p1 for the month; W06 and W07
+    // both roll up into one February assignment of r1 to p2.
+    for (project_id, week) in [(p1.id, 2), (p2.id, 6), (p2.id, 7)] {
+        repo.assign_week(&domain::WeekAssignment {
+            resource_id: r1.id,
+            project_id,
+            year: 1901,
+            week,
+            role: None,
+            note: None,
+            status: domain::AssignmentStatus::Tentative,
+        })
+        .await
+        .unwrap();
+    }
+    let window = [(1901, 1), (1901, 2)];
+    let count = |project_id, resource_id, month: Option<i32>, count| domain::Utilisation {
+        project_id,
+        resource_id,
+        year: month.map(|_| 1901),
+        month,
+        count,
+    };
+
+    let by_project: Vec<_> = repo
+        .utilisation(&window, Project)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|u| u.project_id == Some(p1.id) || u.project_id == Some(p2.id))
+        .collect();
+    let mut expected = vec![
+        count(Some(p1.id), None, Some(1), 2),
+        count(Some(p2.id), None, Some(1), 1),
+        count(Some(p1.id), None, Some(2), 1),
+        count(Some(p2.id), None, Some(2), 1),
+    ];
+    expected[..2].sort_by_key(|u| u.project_id);
+    expected[2..].sort_by_key(|u| u.project_id);
+    assert_eq!(by_project, expected, "resources per project and month");
+
+    let by_resource: Vec<_> = repo
+        .utilisation(&window, Resource)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|u| u.resource_id == Some(r1.id) || u.resource_id == Some(r2.id))
+        .collect();
+    let mut expected = vec![
+        count(None, Some(r1.id), None, 2),
+        count(None, Some(r2.id), None, 2),
+    ];
+    expected.sort_by_key(|u| u.resource_id);
+    assert_eq!(by_resource, expected, "distinct months booked per resource");
+
+    assert_eq!(
+        repo.utilisation(&window, Month).await.unwrap(),
+        vec![count(None, None, Some(1), 2), count(None, None, Some(2), 2)],
+        "distinct resources per month"
+    );
+    assert!(repo.utilisation(&[], Month).await.unwrap().is_empty());
+
+    repo.delete_project(p1.id).await.unwrap();
+    repo.delete_project(p2.id).await.unwrap();
+    repo.delete_resource(r1.id).await.unwrap();
+    repo.delete_resource(r2.id).await.unwrap();
+}
+
+async fn assignment_status(repo: &dyn Repository) {
+    let p = repo.create_project("conformance-status").await.unwrap();
+    let other = repo.create_project("conformance-status-2").await.unwrap();
+    let r = repo.create_resource("conformance-status").await.unwrap();
+    let tentative = |project_id, month| domain::Assignment {
+        status: domain::AssignmentStatus::Tentative,
+        ..assignment(r.id, project_id, 2025, month)
+    };
+    for a in [
+        tentative(p.id, 3),
+        tentative(p.id, 4),
+        tentative(other.id, 3),
+    ] {
+        repo.assign(&a).await.unwrap();
+    }
+    // 2025-W10 has its Thursday on 6 March, 2025-W14 on 3 April.
+    for week in [10, 14] {
+        repo.assign_week(&domain::WeekAssignment {
+            resource_id: r.id,
+            project_id: p.id,
+            year: 2025,
+            week,
+            role: None,
+            note: None,
+            status: domain::AssignmentStatus::Tentative,
+        })
+        .await
+        .unwrap();
+    }
+    let statuses = |rows: Vec<domain::Assignment>| -> Vec<_> {
+        rows.into_iter().map(|a| (a.month, a.status)).collect()
+    };
+    assert_eq!(
+        statuses(repo.list_assignments(&by_project(p.id)).await.unwrap()),
+        vec![
+            (3, domain::AssignmentStatus::Tentative),
+            (4, domain::AssignmentStatus::Tentative)
+        ]
+    );
+
+    assert_eq!(
+        repo.confirm_assignments(p.id, &[(2025, 3)]).await.unwrap(),
+        2,
+        "the month and the week rolling up into it"
+    );
+    assert_eq!(
+        repo.confirm_assignments(p.id, &[(2025, 3)]).await.unwrap(),
+        0
+    );
+    assert_eq!(repo.confirm_assignments(p.id, &[]).await.unwrap(), 0);
+    assert_eq!(
+        statuses(repo.list_assignments(&by_project(p.id)).await.unwrap()),
+        vec![
+            (3, domain::AssignmentStatus::Confirmed),
+            (4, domain::AssignmentStatus::Tentative)
+        ]
+    );
+    let weeks: Vec<_> = repo
+        .list_week_assignments(&WeekFilter {
+            project_id: Some(p.id),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|a| (a.week, a.status))
+        .collect();
+    assert_eq!(
+        weeks,
+        vec![
+            (10, domain::AssignmentStatus::Confirmed),
+            (14, domain::AssignmentStatus::Tentative)
+        ]
+    );
+    assert_eq!(
+        statuses(repo.list_assignments(&by_project(other.id)).await.unwrap()),
+        vec![(3, domain::AssignmentStatus::Tentative)],
+        "other projects are left alone"
+    );
+
+    // Assigning again replaces the status, as it does the role and note.
+    repo.assign(&assignment(r.id, other.id, 2025, 3))
+        .await
+        .unwrap();
+    assert_eq!(
+        statuses(repo.list_assignments(&by_project(other.id)).await.unwrap()),
+        vec![(3, domain::AssignmentStatus::Confirmed)]
+    );
+
+    repo.delete_project(p.id).await.unwrap();
+    repo.delete_project(other.id).await.unwrap();
+    repo.delete_resource(r.id).await.unwrap();
+}
+
+const fn assignment(
+    resource_id: Uuid,
+    project_id: Uuid,
+    year: i32,
+    month: i32,
+) -> domain::Assignment {
+    domain::Assignment {
+        resource_id,
+        project_id,
+        year,
+        month,
+        role: None,
+        note: None,
+        status: domain::AssignmentStatus::Confirmed,
+    }
+}
+
+fn by_project(project_id: Uuid) -> AssignmentFilter {
+    AssignmentFilter {
+        project_id: Some(project_id),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,184 @@
+//! Working-day calendars per region, loaded from a local JSON file.
+//!
+//! A calendar file lists each region's public holidays:
+//!
+//! ```json
+//! { "regions": { "UK": { "holidays": [{ "date": "2025-12-25", "name": "Christmas Day" }] } } }
+//! ```
+//!
+//! Working days are Monday to Friday, less the region's holidays. Resources
+//! without a region, or whose region isn't in the file, work every weekday.
+//! Holiday names are for readers of the file and are ignored.
+
+use crate::domain;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::{fmt, path::Path};
+use time::{Date, Month, Weekday};
+
+#[derive(Deserialize)]
+struct CalendarFile {
+    #[serde(default)]
+    regions: BTreeMap<String, RegionFile>,
+}
+
+#[derive(Deserialize)]
+struct RegionFile {
+    #[serde(default)]
+    holidays: Vec<HolidayFile>,
+}
+
+#[derive(Deserialize)]
+struct HolidayFile {
+    date: String,
+}
+
+#[derive(Debug)]
+pub enum WorkdaysError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    /// The file is well-formed JSON but holds an impossible date.
+    Invalid(String),
+}
+
+impl fmt::Display for WorkdaysError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "cannot read calendar file: {e}"),
+            Self::Json(e) => write!(f, "malformed calendar file: {e}"),
+            Self::Invalid(msg) => write!(f, "invalid calendar file: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for WorkdaysError {}
+
+impl From<std::io::Error> for WorkdaysError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for WorkdaysError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// Public holidays per region. The default has no regions, so every month has
+/// only its weekdays as working days.
+#[derive(Clone, Debug, Default)]
+pub struct WorkingCalendars {
+    regions: BTreeMap<String, HashSet<Date>>,
+}
+
+impl WorkingCalendars {
+    /// Parses a calendar file's contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for malformed JSON or a date that isn't `YYYY-MM-DD`.
+    pub fn from_json(json: &str) -> Result<Self, WorkdaysError> {
+        let file: CalendarFile = serde_json::from_str(json)?;
+        let mut regions = BTreeMap::new();
+        for (region, calendar) in file.regions {
+            let holidays = calendar
+                .holidays
+                .iter()
+                .map(|h| {
+                    parse_date(&h.date).ok_or_else(|| {
+                        WorkdaysError::Invalid(format!("{region}: '{}' is not a date", h.date))
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            regions.insert(region, holidays);
+        }
+        Ok(Self { regions })
+    }
+
+    /// Reads and parses the calendar file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WorkdaysError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// The configured regions, in name order.
+    pub fn regions(&self) -> impl Iterator<Item = &str> {
+        self.regions.keys().map(String::as_str)
+    }
+
+    #[must_use]
+    pub fn contains(&self, region: &str) -> bool {
+        self.regions.contains_key(region)
+    }
+
+    /// Weekdays in `month` of `year` that aren't public holidays in `region`.
+    /// Returns 0 for a month outside 1..=12.
+    #[must_use]
+    pub fn working_days(&self, region: Option<&str>, year: i32, month: u8) -> i32 {
+        let Ok(month) = Month::try_from(month) else {
+            return 0;
+        };
+        let holidays = region.and_then(|r| self.regions.get(r));
+        let mut days = 0;
+        for day in 1..=month.length(year) {
+            let Ok(date) = Date::from_calendar_date(year, month, day) else {
+                continue;
+            };
+            let weekend = matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday);
+            if !weekend && !holidays.is_some_and(|h| h.contains(&date)) {
+                days += 1;
+            }
+        }
+        days
+    }
+}
+
+/// Converts `percent` of a month with `working_days` into person-days.
+#[must_use]
+pub fn person_days(percent: i32, working_days: i32) -> f64 {
+    f64::from(percent) * f64::from(working_days) / f64::from(domain::FULL_MONTH)
+}
+
+fn parse_date(value: &str) -> Option<Date> {
+    let mut parts = value.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month: u8 = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holidays_only_remove_weekdays_in_their_region() {
+        // 2025-12-25/26 fall on Thursday and Friday, 2026-12-26 on a Saturday.
+        let calendars = WorkingCalendars::from_json(
+            r#"{ "regions": { "UK": { "holidays": [
+                { "date": "2025-12-25", "name": "Christmas Day" },
+                { "date": "2025-12-26" },
+                { "date": "2026-12-26" }
+            ] }, "DE": {} } }"#,
+        )
+        .unwrap();
+        assert_eq!(calendars.regions().collect::<Vec<_>>(), ["DE", "UK"]);
+        assert_eq!(calendars.working_days(None, 2025, 12), 23);
+        assert_eq!(calendars.working_days(Some("DE"), 2025, 12), 23);
+        assert_eq!(calendars.working_days(Some("UK"), 2025, 12), 21);
+        assert_eq!(calendars.working_days(Some("UK"), 2026, 12), 23);
+        assert_eq!(calendars.working_days(Some("FR"), 2025, 12), 23);
+        assert_eq!(calendars.working_days(None, 2024, 2), 21);
+        assert!((person_days(50, 21) - 10.5).abs() < f64::EPSILON);
+
+        let err = WorkingCalendars::from_json(
+            r#"{ "regions": { "UK": { "holidays": [{ "date": "2025-02-30" }] } } }"#,
+        )
+        .unwrap_err();
+        assert!(matches!(err, WorkdaysError::Invalid(_)), "{err}");
+    }
+}
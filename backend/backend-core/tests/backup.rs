@@ -0,0 +1,201 @@
+use backend_core::{
+    AssignmentStatus, DomainAssignment, ProjectStatus, Repository, Role, SqliteRepository,
+    backup::{self, Backup, BackupError, FORMAT_VERSION},
+};
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn backups_round_trip_and_restore_idempotently() {
+    let source = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    let alpha = source.create_project("Alpha").await.unwrap();
+    let alice = source.create_resource("Alice").await.unwrap();
+    source.create_resource("Bob").await.unwrap();
+    let team = source.create_team("Platform").await.unwrap();
+    source
+        .set_resource_team(alice.id, Some(team.id))
+        .await
+        .unwrap();
+    source
+        .assign(&DomainAssignment {
+            resource_id: alice.id,
+            project_id: alpha.id,
+            year: 2025,
+            month: 3,
+            role: Some("Tech Lead".into()),
+            note: Some("Part time".into()),
+            status: AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+    source
+        .apply_batch(&backend_core::Batch {
+            actuals: vec![backend_core::Actual {
+                resource_id: alice.id,
+                project_id: alpha.id,
+                year: 2025,
+                month: 3,
+                effort: 80,
+            }],
+            week_assignments: vec![backend_core::WeekAssignment {
+                resource_id: alice.id,
+                project_id: alpha.id,
+                year: 2026,
+                week: 53,
+                role: Some("QA".into()),
+                note: None,
+                status: AssignmentStatus::Tentative,
+            }],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    source
+        .set_resource_region(alice.id, Some("UK"))
+        .await
+        .unwrap();
+    source
+        .set_capacity(&backend_core::Capacity {
+            resource_id: alice.id,
+            year: 2025,
+            month: 8,
+            percent: 0,
+        })
+        .await
+        .unwrap();
+    source.set_user_role("carol", Role::Planner).await.unwrap();
+    source.add_project_manager(alpha.id, "carol").await.unwrap();
+    source
+        .set_project_budget(alpha.id, Some(10_000))
+        .await
+        .unwrap();
+    source
+        .set_project_details(
+            alpha.id,
+            ProjectStatus::OnHold,
+            Some("#2ca02c"),
+            Some("Paused until Q3"),
+        )
+        .await
+        .unwrap();
+    source
+        .set_cost_rate(&backend_core::CostRate {
+            resource_id: alice.id,
+            year: 2025,
+            month: 1,
+            rate: 900,
+        })
+        .await
+        .unwrap();
+
+    let exported = backup::export(&source).await.unwrap();
+    assert_eq!(exported.version, FORMAT_VERSION);
+    assert_eq!(exported.resources[0].team_id, Some(team.id));
+    assert_eq!(exported.resources[0].region.as_deref(), Some("UK"));
+    assert_eq!(exported.projects[0].status, "on-hold");
+    assert_eq!(exported.actuals[0].effort, 80);
+    assert_eq!(exported.week_assignments[0].week, 53);
+    assert_eq!(exported.week_assignments[0].status, "tentative");
+    assert_eq!(exported.assignments[0].status, "confirmed");
+    assert_eq!(exported.capacities[0].percent, 0);
+    let json = exported.to_json().unwrap();
+    let parsed = Backup::from_json(&json).unwrap();
+    assert_eq!(parsed, exported);
+
+    let target = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    backup::restore(&target, &parsed).await.unwrap();
+    backup::restore(&target, &parsed).await.unwrap();
+    assert_eq!(backup::export(&target).await.unwrap(), exported);
+    assert_eq!(
+        target.user_role("carol").await.unwrap(),
+        Some(Role::Planner)
+    );
+    assert!(target.is_project_manager(alpha.id, "carol").await.unwrap());
+}
+
+#[tokio::test]
+async fn backups_reject_unknown_versions_and_bad_data() {
+    let err = Backup::from_json(r#"{"version": 99}"#).unwrap_err();
+    assert!(matches!(err, BackupError::UnsupportedVersion(99)));
+
+    // Collections default to empty, so a bare document restores nothing.
+    let empty = Backup::from_json(&format!(r#"{{"version": {FORMAT_VERSION}}}"#)).unwrap();
+    let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    backup::restore(&repo, &empty).await.unwrap();
+    assert!(repo.list_projects().await.unwrap().is_empty());
+
+    let bad_role = Backup::from_json(&format!(
+        r#"{{"version": {FORMAT_VERSION}, "user_roles": [{{"user_id": "x", "role": "owner"}}]}}"#
+    ))
+    .unwrap();
+    let err = backup::restore(&repo, &bad_role).await.unwrap_err();
+    assert!(matches!(err, BackupError::Invalid(_)), "{err}");
+    assert_eq!(repo.user_role("x").await.unwrap(), None);
+
+    let bad_status = Backup::from_json(&format!(
+        r#"{{"version": {FORMAT_VERSION}, "projects": [{{"id": "{}", "name": "P2", "status": "paused"}}]}}"#,
+        uuid::Uuid::new_v4()
+    ))
+    .unwrap();
+    let err = backup::restore(&repo, &bad_status).await.unwrap_err();
+    assert!(matches!(err, BackupError::Invalid(_)), "{err}");
+    assert!(repo.list_projects().await.unwrap().is_empty());
+
+    let (a, b) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+    let cycle = Backup::from_json(&format!(
+        r#"{{"version": {FORMAT_VERSION}, "projects": [
+            {{"id": "{a}", "name": "A", "parent_id": "{b}"}},
+            {{"id": "{b}", "name": "B", "parent_id": "{a}"}}
+        ]}}"#
+    ))
+    .unwrap();
+    let err = backup::restore(&repo, &cycle).await.unwrap_err();
+    assert!(matches!(err, BackupError::Invalid(_)), "{err}");
+    assert!(repo.list_projects().await.unwrap().is_empty());
+
+    let bad_status = Backup::from_json(&format!(
+        r#"{{"version": {FORMAT_VERSION}, "assignments": [{{"resource_id": "{}", "project_id": "{}", "year": 2025, "month": 1, "status": "maybe"}}]}}"#,
+        uuid::Uuid::new_v4(),
+        uuid::Uuid::new_v4()
+    ))
+    .unwrap();
+    let err = backup::restore(&repo, &bad_status).await.unwrap_err();
+    assert!(matches!(err, BackupError::Invalid(_)), "{err}");
+
+    let bad_kind = Backup::from_json(&format!(
+        r#"{{"version": {FORMAT_VERSION}, "resources": [{{"id": "{}", "name": "R2", "kind": "robot"}}]}}"#,
+        uuid::Uuid::new_v4()
+    ))
+    .unwrap();
+    let err = backup::restore(&repo, &bad_kind).await.unwrap_err();
+    assert!(matches!(err, BackupError::Invalid(_)), "{err}");
+    assert!(repo.list_resources().await.unwrap().is_empty());
+
+    // 2025 has only 52 ISO weeks.
+    let bad_week = Backup::from_json(&format!(
+        r#"{{"version": {FORMAT_VERSION}, "week_assignments": [{{"resource_id": "{}", "project_id": "{}", "year": 2025, "week": 53}}]}}"#,
+        uuid::Uuid::new_v4(),
+        uuid::Uuid::new_v4()
+    ))
+    .unwrap();
+    let err = backup::restore(&repo, &bad_week).await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid backup: assignment week 53 is not a week of 2025"
+    );
+}
+
+#[tokio::test]
+async fn failed_restores_leave_nothing_behind() {
+    // The rate's resource is missing, so the role granted alongside it must not stick.
+    let dangling = Backup::from_json(&format!(
+        r#"{{"version": {FORMAT_VERSION},
+            "user_roles": [{{"user_id": "x", "role": "admin"}}],
+            "cost_rates": [{{"resource_id": "{}", "year": 2025, "month": 1, "rate": 100}}]}}"#,
+        uuid::Uuid::new_v4()
+    ))
+    .unwrap();
+    let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    assert!(backup::restore(&repo, &dangling).await.is_err());
+    assert_eq!(repo.user_role("x").await.unwrap(), None);
+    assert!(repo.list_cost_rates().await.unwrap().is_empty());
+}
@@ -0,0 +1,66 @@
+use backend_core::{
+    AssignmentStatus, DomainAssignment, Repository, SqliteRepository, calendar::resource_calendar,
+};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn resource_calendars_have_one_event_per_assigned_month() {
+    let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    let alpha = repo.create_project("Alpha, phase 1").await.unwrap();
+    let long = repo.create_project(&"Long name ".repeat(10)).await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let bob = repo.create_resource("Bob").await.unwrap();
+    for (r, p, year, month, status) in [
+        (&alice, &long, 2025, 1, AssignmentStatus::Confirmed),
+        (&alice, &alpha, 2024, 12, AssignmentStatus::Tentative),
+        (&bob, &alpha, 2024, 11, AssignmentStatus::Confirmed),
+    ] {
+        repo.assign(&DomainAssignment {
+            resource_id: r.id,
+            project_id: p.id,
+            year,
+            month,
+            role: None,
+            note: None,
+            status,
+        })
+        .await
+        .unwrap();
+    }
+
+    let ics = resource_calendar(&repo, alice.id).await.unwrap().unwrap();
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert!(ics.lines().all(|l| l.trim_end_matches('\r').len() <= 75));
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+
+    // Events are ordered by month and cover the whole month.
+    let december = ics.find("DTSTART;VALUE=DATE:20241201").unwrap();
+    let january = ics.find("DTSTART;VALUE=DATE:20250101").unwrap();
+    assert!(december < january);
+    assert!(ics.contains("DTEND;VALUE=DATE:20250101\r\n"));
+    assert!(ics.contains("DTEND;VALUE=DATE:20250201\r\n"));
+    assert!(ics.contains("SUMMARY:Alpha\\, phase 1\r\n"));
+
+    // The pencilled-in December booking is tentative; January is confirmed.
+    let status = |event: &str| {
+        event
+            .lines()
+            .find_map(|l| l.strip_prefix("STATUS:"))
+            .map(|s| s.trim_end_matches('\r').to_string())
+    };
+    assert_eq!(
+        status(&ics[december..january]).as_deref(),
+        Some("TENTATIVE")
+    );
+    assert_eq!(status(&ics[january..]).as_deref(), Some("CONFIRMED"));
+
+    // Folded lines unfold back to the original value.
+    let unfolded = ics.replace("\r\n ", "");
+    assert!(unfolded.contains(&format!("SUMMARY:{}\r\n", "Long name ".repeat(10))));
+
+    assert_eq!(
+        resource_calendar(&repo, Uuid::new_v4()).await.unwrap(),
+        None
+    );
+}
@@ -0,0 +1,61 @@
+use backend_core::{
+    AssignmentStatus, DomainAssignment, Repository, SqliteRepository,
+    export::{ExportError, Matrix, matrix_table},
+};
+
+#[tokio::test]
+async fn exports_render_the_month_matrices() {
+    let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let beta = repo.create_project("Beta").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let bob = repo.create_resource("Bob").await.unwrap();
+    for (r, p, month) in [(&alice, &alpha, 12), (&bob, &alpha, 12), (&bob, &beta, 12)] {
+        repo.assign(&DomainAssignment {
+            resource_id: r.id,
+            project_id: p.id,
+            year: 2024,
+            month,
+            role: None,
+            note: None,
+            status: AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+    }
+
+    let table = matrix_table(&repo, Matrix::Project, "2024-12", "2025-01")
+        .await
+        .unwrap();
+    let csv = String::from_utf8(table.to_csv().unwrap()).unwrap();
+    assert_eq!(
+        csv,
+        "Project,2024-12,2025-01\nAlpha,Alice; Bob,\nBeta,Bob,\n"
+    );
+
+    let table = matrix_table(&repo, Matrix::Resource, "2024-12", "2024-12")
+        .await
+        .unwrap();
+    assert_eq!(table.header, ["Resource", "2024-12"]);
+    assert_eq!(table.rows, [["Alice", "Alpha"], ["Bob", "Alpha; Beta"]]);
+
+    // XLSX files are zip archives
+    let xlsx = table.to_xlsx().unwrap();
+    assert!(xlsx.starts_with(b"PK"));
+}
+
+#[tokio::test]
+async fn exports_reject_bad_windows() {
+    let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    for (from, to) in [
+        ("2025-02", "2025-01"),
+        ("2025-13", "2025-12"),
+        ("2000-01", "2030-01"),
+    ] {
+        let res = matrix_table(&repo, Matrix::Project, from, to).await;
+        assert!(
+            matches!(res, Err(ExportError::InvalidWindow(_))),
+            "{from}..{to}"
+        );
+    }
+}
@@ -1,16 +1,38 @@
 use async_graphql::{Request, Variables};
-use backend_core::{SqliteRepository, build_schema};
+use backend_core::{
+    AssignmentStatus, AuditEntry, CurrentUser, Repository, Role, SqliteRepository, YearMonth,
+    build_read_only_schema, build_schema, build_schema_with_calendars, workdays::WorkingCalendars,
+};
+
+fn user() -> CurrentUser {
+    CurrentUser {
+        id: "alice@example.com".into(),
+        name: Some("Alice".into()),
+    }
+}
+
+fn as_user(query: impl Into<String>) -> Request {
+    Request::new(query).data(user())
+}
+
+fn as_other(user_id: &str, query: impl Into<String>) -> Request {
+    Request::new(query).data(CurrentUser {
+        id: user_id.into(),
+        name: None,
+    })
+}
 
 #[tokio::test]
 #[allow(clippy::too_many_lines)]
 async fn graphql_create_assign_and_query_tables() {
     // Repo + schema
     let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
     let schema = build_schema(std::sync::Arc::new(repo));
 
     // Create a resource
     let resp = schema
-        .execute(Request::new(
+        .execute(as_user(
             r#"mutation { createResource(input: { name: "Alice" }) { id name } }"#,
         ))
         .await;
@@ -20,7 +42,7 @@ async fn graphql_create_assign_and_query_tables() {
 
     // Create a project
     let resp = schema
-        .execute(Request::new(
+        .execute(as_user(
             r#"mutation { createProject(input: { name: "Alpha" }) { id name } }"#,
         ))
         .await;
@@ -32,7 +54,7 @@ async fn graphql_create_assign_and_query_tables() {
     let assign_mut = format!(
         r#"mutation {{ assign(input: {{ resourceId: "{r_id}", projectId: "{p_id}", month: "2024-03" }}) {{ id month }} }}"#
     );
-    let resp = schema.execute(Request::new(assign_mut)).await;
+    let resp = schema.execute(as_user(assign_mut)).await;
     assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
 
     // Query raw assignments to exercise DataLoaders via nested fields
@@ -77,7 +99,7 @@ async fn graphql_create_assign_and_query_tables() {
         query($months: [Month!]!) {
             projectMonthMatrix(months: $months) {
                 months
-                rows { project { id name } cells { resources { id name } } }
+                rows { nodes { project { id name } cells { resources { id name } } } }
             }
         }
     ";
@@ -88,7 +110,9 @@ async fn graphql_create_assign_and_query_tables() {
     let months = data["projectMonthMatrix"]["months"].as_array().unwrap();
     assert_eq!(months.len(), 1);
     assert_eq!(months[0].as_str().unwrap(), "2024-03");
-    let rows = data["projectMonthMatrix"]["rows"].as_array().unwrap();
+    let rows = data["projectMonthMatrix"]["rows"]["nodes"]
+        .as_array()
+        .unwrap();
     // Expect a row for every project, and exactly one cell per month
     assert!(!rows.is_empty());
     let mut found = false;
@@ -112,7 +136,7 @@ async fn graphql_create_assign_and_query_tables() {
         query($months: [Month!]!) {
             resourceMonthMatrix(months: $months) {
                 months
-                rows { resource { id name } cells { projects { id name } } }
+                rows { nodes { resource { id name } cells { projects { id name } } } }
             }
         }
     ";
@@ -123,7 +147,9 @@ async fn graphql_create_assign_and_query_tables() {
     let months = data["resourceMonthMatrix"]["months"].as_array().unwrap();
     assert_eq!(months.len(), 1);
     assert_eq!(months[0].as_str().unwrap(), "2024-03");
-    let rows = data["resourceMonthMatrix"]["rows"].as_array().unwrap();
+    let rows = data["resourceMonthMatrix"]["rows"]["nodes"]
+        .as_array()
+        .unwrap();
     assert!(!rows.is_empty());
     let mut found = false;
     for row in rows {
@@ -142,7 +168,7 @@ async fn graphql_create_assign_and_query_tables() {
 
     // Test deleteResource mutation and verify cascading effects
     let del_res = format!(r#"mutation {{ deleteResource(id: "{r_id}") }}"#);
-    let resp = schema.execute(Request::new(del_res)).await;
+    let resp = schema.execute(as_user(del_res)).await;
     assert!(resp.errors.is_empty());
     let data = serde_json::to_value(resp.data).unwrap();
     assert!(data["deleteResource"].as_bool().unwrap());
@@ -158,15 +184,1514 @@ async fn graphql_create_assign_and_query_tables() {
     let unassign_mut = format!(
         r#"mutation {{ unassign(input: {{ resourceId: "{r_id}", projectId: "{p_id}", month: "2024-03" }}) }}"#
     );
-    let resp = schema.execute(Request::new(unassign_mut)).await;
+    let resp = schema.execute(as_user(unassign_mut)).await;
     assert!(resp.errors.is_empty());
     let data = serde_json::to_value(resp.data).unwrap();
     assert!(!data["unassign"].as_bool().unwrap());
 
     // Delete project as well
     let del_proj = format!(r#"mutation {{ deleteProject(id: "{p_id}") }}"#);
-    let resp = schema.execute(Request::new(del_proj)).await;
+    let resp = schema.execute(as_user(del_proj)).await;
     assert!(resp.errors.is_empty());
     let data = serde_json::to_value(resp.data).unwrap();
     assert!(data["deleteProject"].as_bool().unwrap());
 }
+
+#[tokio::test]
+async fn read_only_schema_serves_queries_and_rejects_mutations() {
+    let path = std::env::temp_dir().join(format!("backend-read-only-{}.db", uuid::Uuid::new_v4()));
+    let url = format!("sqlite:{}", path.display());
+
+    // Seed the file through a writable connection first
+    let writable = SqliteRepository::connect(&url).await.unwrap();
+    writable.create_project("Alpha").await.unwrap();
+
+    let repo = SqliteRepository::connect_read_only(&url).await.unwrap();
+    assert!(repo.create_project("Beta").await.is_err());
+    let schema = build_read_only_schema(std::sync::Arc::new(repo));
+
+    let resp = schema
+        .execute(Request::new("query { projects { name } }"))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["projects"][0]["name"], "Alpha");
+
+    let resp = schema
+        .execute(Request::new(
+            r#"mutation { createProject(input: { name: "Gamma" }) { id } }"#,
+        ))
+        .await;
+    assert!(!resp.errors.is_empty());
+
+    drop(schema);
+    drop(writable);
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn mutations_require_a_user_and_are_audited() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Planner).await.unwrap();
+    let schema = build_schema(repo.clone());
+    let create = r#"mutation { createProject(input: { name: "Alpha" }) { id } }"#;
+
+    let resp = schema.execute(Request::new(create)).await;
+    assert_eq!(resp.errors.len(), 1);
+    assert_eq!(resp.errors[0].message, "Authentication required");
+    assert!(repo.list_projects().await.unwrap().is_empty());
+
+    let resp = schema.execute(as_user(create)).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let p_id = data["createProject"]["id"].as_str().unwrap().to_string();
+
+    assert_eq!(
+        repo.list_audit().await.unwrap(),
+        vec![AuditEntry {
+            user_id: user().id,
+            action: "createProject".into(),
+            detail: p_id,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn mutations_are_authorized_by_role_and_project_ownership() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role("admin", Role::Admin).await.unwrap();
+    repo.set_user_role("planner", Role::Planner).await.unwrap();
+    repo.set_user_role("other", Role::Planner).await.unwrap();
+    let schema = build_schema(repo.clone());
+    let errors = |resp: async_graphql::Response| -> Vec<String> {
+        resp.errors.into_iter().map(|e| e.message).collect()
+    };
+
+    // Viewers (including users without a grant) can't create anything
+    let create = r#"mutation { createProject(input: { name: "Alpha" }) { id } }"#;
+    let resp = schema.execute(as_other("nobody", create)).await;
+    assert_eq!(errors(resp), vec!["Requires the planner role"]);
+
+    // Planners can, and manage what they create
+    let resp = schema.execute(as_other("planner", create)).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let p_id = data["createProject"]["id"].as_str().unwrap().to_string();
+    let r_id = repo.create_resource("Res").await.unwrap().id;
+
+    let assign = format!(
+        r#"mutation {{ assign(input: {{ resourceId: "{r_id}", projectId: "{p_id}", month: "2024-03" }}) {{ id }} }}"#
+    );
+    let resp = schema.execute(as_other("other", assign.clone())).await;
+    assert_eq!(
+        errors(resp),
+        vec!["Only the project's managers can change its assignments"]
+    );
+    let resp = schema.execute(as_other("planner", assign.clone())).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+
+    // Ownership can be granted by an admin
+    let grant =
+        format!(r#"mutation {{ addProjectManager(projectId: "{p_id}", userId: "other") }}"#);
+    let resp = schema.execute(as_other("planner", grant.clone())).await;
+    assert_eq!(errors(resp), vec!["Requires the admin role"]);
+    let resp = schema.execute(as_other("admin", grant)).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let resp = schema.execute(as_other("other", assign)).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+
+    // Only admins delete
+    let delete = format!(r#"mutation {{ deleteProject(id: "{p_id}") }}"#);
+    let resp = schema.execute(as_other("planner", delete.clone())).await;
+    assert_eq!(errors(resp), vec!["Requires the admin role"]);
+    let resp = schema.execute(as_other("admin", delete)).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+
+    // Roles are managed by admins
+    let promote = r#"mutation { setUserRole(userId: "nobody", role: PLANNER) }"#;
+    let resp = schema.execute(as_other("planner", promote)).await;
+    assert_eq!(errors(resp), vec!["Requires the admin role"]);
+    let resp = schema.execute(as_other("admin", promote)).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    assert_eq!(repo.user_role("nobody").await.unwrap(), Some(Role::Planner));
+}
+
+#[tokio::test]
+async fn import_assignments_respects_project_managers() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role("planner", Role::Planner).await.unwrap();
+    repo.create_project("Theirs").await.unwrap();
+    let schema = build_schema(repo.clone());
+
+    let query = r"
+        mutation($csv: String!) {
+            importAssignments(csv: $csv, createMissing: true) {
+                dryRun imported createdProjects { name } errors { line message }
+            }
+        }
+    ";
+    let vars = Variables::from_json(serde_json::json!({
+        "csv": "Theirs,Alice,2025-01\nMine,Alice,2025-01\n"
+    }));
+    let resp = schema
+        .execute(as_other("planner", query).variables(vars))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let report = &data["importAssignments"];
+    assert_eq!(report["dryRun"], false);
+    assert_eq!(report["imported"], 1);
+    assert_eq!(report["createdProjects"][0]["name"], "Mine");
+    assert_eq!(report["errors"][0]["line"], 1);
+
+    let mine = repo.list_projects().await.unwrap()[0].clone();
+    assert_eq!(mine.name, "Mine");
+    assert!(repo.is_project_manager(mine.id, "planner").await.unwrap());
+}
+
+#[tokio::test]
+async fn utilisation_groups_assignment_counts() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let bob = repo.create_resource("Bob").await.unwrap();
+    for (r, month) in [(&alice, 1), (&alice, 2), (&bob, 2)] {
+        repo.assign(&backend_core::DomainAssignment {
+            resource_id: r.id,
+            project_id: alpha.id,
+            year: 2025,
+            month,
+            role: None,
+            note: None,
+            status: AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+    }
+    let schema = build_schema(repo);
+
+    let query = r#"
+        query($groupBy: UtilisationGroupBy!) {
+            utilisation(months: ["2025-01", "2025-02"], groupBy: $groupBy) {
+                month count project { name } resource { name }
+            }
+        }
+    "#;
+    let run = |group_by: &str| {
+        let vars = Variables::from_json(serde_json::json!({ "groupBy": group_by }));
+        schema.execute(Request::new(query).variables(vars))
+    };
+
+    let resp = run("PROJECT").await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    assert_eq!(
+        serde_json::to_value(resp.data).unwrap()["utilisation"],
+        serde_json::json!([
+            { "month": "2025-01", "count": 1, "project": { "name": "Alpha" }, "resource": null },
+            { "month": "2025-02", "count": 2, "project": { "name": "Alpha" }, "resource": null },
+        ])
+    );
+
+    let resp = run("RESOURCE").await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let mut booked: Vec<(String, i64)> = data["utilisation"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|u| {
+            assert!(u["month"].is_null());
+            (
+                u["resource"]["name"].as_str().unwrap().to_string(),
+                u["count"].as_i64().unwrap(),
+            )
+        })
+        .collect();
+    booked.sort();
+    assert_eq!(booked, [("Alice".to_string(), 2), ("Bob".to_string(), 1)]);
+
+    let resp = run("MONTH").await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["utilisation"][1]["month"], "2025-02");
+    assert_eq!(data["utilisation"][1]["count"], 2);
+}
+
+#[tokio::test]
+async fn assignments_carry_role_and_note_into_matrix_cells() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let schema = build_schema(repo);
+
+    let assign = |role: &str| {
+        format!(
+            r#"mutation {{ assign(input: {{ resourceId: "{}", projectId: "{}", month: "2025-04", role: {role}, note: "Mornings" }}) {{ role note }} }}"#,
+            alice.id, alpha.id
+        )
+    };
+    let resp = schema.execute(as_user(assign(r#""Tech Lead""#))).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["assign"],
+        serde_json::json!({ "role": "Tech Lead", "note": "Mornings" })
+    );
+
+    // Re-assigning replaces the role; blank text counts as no role.
+    let resp = schema.execute(as_user(assign(r#""  ""#))).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let resp = schema.execute(as_user(assign(r#""Reviewer""#))).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+
+    let resp = schema
+        .execute(Request::new(
+            r#"{ projectMonthMatrix(months: ["2025-04"]) {
+                rows { nodes { cells { assignments { role note resource { name } } } } }
+            } }"#,
+        ))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["projectMonthMatrix"]["rows"]["nodes"][0]["cells"][0]["assignments"],
+        serde_json::json!([
+            { "role": "Reviewer", "note": "Mornings", "resource": { "name": "Alice" } }
+        ])
+    );
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn programmes_roll_up_child_projects() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    let programme = repo.create_project("Programme").await.unwrap();
+    let web = repo.create_project("Web").await.unwrap();
+    let app = repo.create_project("App").await.unwrap();
+    repo.create_project("Other").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let bob = repo.create_resource("Bob").await.unwrap();
+    for (resource, project) in [(alice.id, web.id), (alice.id, app.id), (bob.id, app.id)] {
+        repo.assign(&backend_core::DomainAssignment {
+            resource_id: resource,
+            project_id: project,
+            year: 2025,
+            month: 6,
+            role: None,
+            note: None,
+            status: AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+    }
+    let schema = build_schema(repo.clone());
+
+    let set_parent = |project: uuid::Uuid, parent: uuid::Uuid| {
+        format!(
+            r#"mutation {{ setProjectParent(projectId: "{project}", parentId: "{parent}") {{ parent {{ name }} }} }}"#
+        )
+    };
+    for child in [web.id, app.id] {
+        let resp = schema
+            .execute(as_user(set_parent(child, programme.id)))
+            .await;
+        assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+        let data = serde_json::to_value(resp.data).unwrap();
+        assert_eq!(data["setProjectParent"]["parent"]["name"], "Programme");
+    }
+
+    // Nesting the programme under its own child would close a cycle.
+    let resp = schema
+        .execute(as_user(set_parent(programme.id, web.id)))
+        .await;
+    assert_eq!(
+        resp.errors[0].message,
+        "A project cannot be nested under itself"
+    );
+    let resp = schema
+        .execute(as_user(set_parent(web.id, uuid::Uuid::new_v4())))
+        .await;
+    assert_eq!(resp.errors[0].message, "Parent project not found");
+
+    let resp = schema
+        .execute(Request::new("{ projects { id children { name } } }"))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let children = data["projects"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["id"] == programme.id.to_string())
+        .map(|p| p["children"].clone())
+        .unwrap();
+    assert_eq!(
+        children,
+        serde_json::json!([{ "name": "App" }, { "name": "Web" }])
+    );
+
+    let resp = schema
+        .execute(Request::new(
+            r#"{ programmeMonthMatrix(months: ["2025-06"]) {
+                rows { nodes { project { name } cells { resources { name } assignments { project { name } } } } }
+            } }"#,
+        ))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let rows = &data["programmeMonthMatrix"]["rows"]["nodes"];
+    assert_eq!(rows.as_array().unwrap().len(), 2);
+    assert_eq!(rows[0]["project"]["name"], "Other");
+    assert_eq!(rows[1]["project"]["name"], "Programme");
+    let cell = &rows[1]["cells"][0];
+    let mut names: Vec<_> = cell["resources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    assert_eq!(names, ["Alice", "Bob"]);
+    assert_eq!(cell["assignments"].as_array().unwrap().len(), 3);
+
+    // The repository doesn't check for cycles, so one may already be stored.
+    let a = repo.create_project("Cycle A").await.unwrap();
+    let b = repo.create_project("Cycle B").await.unwrap();
+    repo.set_project_parent(a.id, Some(b.id)).await.unwrap();
+    repo.set_project_parent(b.id, Some(a.id)).await.unwrap();
+    let resp = schema.execute(as_user(set_parent(web.id, a.id))).await;
+    assert_eq!(
+        resp.errors[0].message,
+        "Parent project is nested under itself"
+    );
+}
+
+#[tokio::test]
+async fn nesting_a_project_requires_managing_its_new_parent() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role("planner", Role::Planner).await.unwrap();
+    let programme = repo.create_project("Programme").await.unwrap();
+    let web = repo.create_project("Web").await.unwrap();
+    repo.add_project_manager(web.id, "planner").await.unwrap();
+    let schema = build_schema(repo.clone());
+
+    let set_parent = format!(
+        r#"mutation {{ setProjectParent(projectId: "{}", parentId: "{}") {{ parent {{ name }} }} }}"#,
+        web.id, programme.id
+    );
+    let resp = schema
+        .execute(as_other("planner", set_parent.clone()))
+        .await;
+    assert_eq!(
+        resp.errors[0].message,
+        "Only the project's managers can change its assignments"
+    );
+    let projects = repo.list_projects().await.unwrap();
+    assert!(projects.iter().all(|p| p.parent_id.is_none()));
+
+    repo.add_project_manager(programme.id, "planner")
+        .await
+        .unwrap();
+    let resp = schema.execute(as_other("planner", set_parent)).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["setProjectParent"]["parent"]["name"], "Programme");
+
+    // Moving back to the top level only needs the project itself.
+    let resp = schema
+        .execute(as_other(
+            "planner",
+            format!(
+                r#"mutation {{ setProjectParent(projectId: "{}") {{ name }} }}"#,
+                web.id
+            ),
+        ))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn teams_group_resources_into_a_team_matrix() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    let web = repo.create_project("Web").await.unwrap();
+    let app = repo.create_project("App").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let bob = repo.create_resource("Bob").await.unwrap();
+    repo.create_resource("Carol").await.unwrap();
+    for (resource, project) in [(alice.id, web.id), (bob.id, web.id), (bob.id, app.id)] {
+        repo.assign(&backend_core::DomainAssignment {
+            resource_id: resource,
+            project_id: project,
+            year: 2025,
+            month: 9,
+            role: None,
+            note: None,
+            status: AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+    }
+    repo.set_capacity(&backend_core::Capacity {
+        resource_id: alice.id,
+        year: 2025,
+        month: 10,
+        percent: 50,
+    })
+    .await
+    .unwrap();
+    let schema = build_schema(repo);
+
+    let resp = schema
+        .execute(as_user(
+            r#"mutation { createTeam(input: { name: "Platform" }) { id name } }"#,
+        ))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let team_id = data["createTeam"]["id"].as_str().unwrap().to_string();
+
+    for resource in [alice.id, bob.id] {
+        let resp = schema
+            .execute(as_user(format!(
+                r#"mutation {{ setResourceTeam(resourceId: "{resource}", teamId: "{team_id}") {{ team {{ name }} }} }}"#
+            )))
+            .await;
+        assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+        let data = serde_json::to_value(resp.data).unwrap();
+        assert_eq!(data["setResourceTeam"]["team"]["name"], "Platform");
+    }
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{ renameTeam(id: "{team_id}", name: "Core") {{ name }} }}"#
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+
+    let resp = schema
+        .execute(Request::new(
+            r#"{
+                teams { name members { name } }
+                teamMonthMatrix(months: ["2025-09", "2025-10"]) {
+                    rows {
+                        team { name }
+                        cells {
+                            resources { name } projects { name } assignments { id }
+                            capacity remainingCapacity availableDays plannedDays
+                        }
+                    }
+                }
+            }"#,
+        ))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["teams"],
+        serde_json::json!([{ "name": "Core", "members": [{ "name": "Alice" }, { "name": "Bob" }] }])
+    );
+    let row = &data["teamMonthMatrix"]["rows"][0];
+    assert_eq!(row["team"]["name"], "Core");
+    assert_eq!(
+        row["cells"][0]["resources"],
+        serde_json::json!([{ "name": "Alice" }, { "name": "Bob" }])
+    );
+    assert_eq!(
+        row["cells"][0]["projects"],
+        serde_json::json!([{ "name": "App" }, { "name": "Web" }])
+    );
+    assert_eq!(row["cells"][0]["assignments"].as_array().unwrap().len(), 3);
+    assert_eq!(row["cells"][1]["assignments"], serde_json::json!([]));
+    // September has 22 weekdays and October 23; Alice works half of October.
+    let sums = |cell: &serde_json::Value| {
+        serde_json::json!({
+            "capacity": cell["capacity"],
+            "remainingCapacity": cell["remainingCapacity"],
+            "availableDays": cell["availableDays"],
+            "plannedDays": cell["plannedDays"],
+        })
+    };
+    let expected = |capacity: i32, remaining: i32, available: f64, planned: f64| serde_json::json!({ "capacity": capacity, "remainingCapacity": remaining, "availableDays": available, "plannedDays": planned });
+    assert_eq!(sums(&row["cells"][0]), expected(200, -100, 44.0, 66.0));
+    assert_eq!(sums(&row["cells"][1]), expected(150, 150, 34.5, 0.0));
+
+    // Deleting the team keeps its members.
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{ deleteTeam(id: "{team_id}") }}"#
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let resp = schema
+        .execute(Request::new("{ resources { name team { name } } }"))
+        .await;
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["resources"][0],
+        serde_json::json!({ "name": "Alice", "team": null })
+    );
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn placeholders_hand_their_assignments_to_a_hire() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role("planner", Role::Planner).await.unwrap();
+    repo.set_user_role("other", Role::Planner).await.unwrap();
+    let schema = build_schema(repo.clone());
+    let errors = |resp: async_graphql::Response| -> Vec<String> {
+        resp.errors.into_iter().map(|e| e.message).collect()
+    };
+
+    let resp = schema
+        .execute(as_other(
+            "planner",
+            r#"mutation {
+                createProject(input: { name: "Alpha" }) { id }
+                tbh: createResource(input: { name: "Backend Engineer #2 (TBH)", kind: PLACEHOLDER }) { id kind }
+                hire: createResource(input: { name: "Dana" }) { id kind }
+            }"#,
+        ))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["tbh"]["kind"], "PLACEHOLDER");
+    assert_eq!(data["hire"]["kind"], "PERSON");
+    let p_id = data["createProject"]["id"].as_str().unwrap().to_string();
+    let tbh = data["tbh"]["id"].as_str().unwrap().to_string();
+    let hire = data["hire"]["id"].as_str().unwrap().to_string();
+
+    for month in ["2025-01", "2025-02"] {
+        let resp = schema
+            .execute(as_other(
+                "planner",
+                format!(
+                    r#"mutation {{ assign(input: {{ resourceId: "{tbh}", projectId: "{p_id}", month: "{month}", role: "Backend" }}) {{ id }} }}"#
+                ),
+            ))
+            .await;
+        assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    }
+
+    let replace = |placeholder: &str, resource: &str| {
+        format!(
+            r#"mutation {{ replacePlaceholder(placeholderId: "{placeholder}", resourceId: "{resource}") {{ name assignments {{ month role }} }} }}"#
+        )
+    };
+    let resp = schema
+        .execute(as_other("planner", replace(&hire, &tbh)))
+        .await;
+    assert_eq!(errors(resp), vec!["Only placeholders can be replaced"]);
+    let resp = schema
+        .execute(as_other("planner", replace(&tbh, &tbh)))
+        .await;
+    assert_eq!(
+        errors(resp),
+        vec!["A placeholder can only be replaced by a person"]
+    );
+    // Moving assignments needs the same rights as changing them
+    let resp = schema
+        .execute(as_other("other", replace(&tbh, &hire)))
+        .await;
+    assert_eq!(
+        errors(resp),
+        vec!["Only the project's managers can change its assignments"]
+    );
+
+    let resp = schema
+        .execute(as_other("planner", replace(&tbh, &hire)))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["replacePlaceholder"],
+        serde_json::json!({
+            "name": "Dana",
+            "assignments": [
+                { "month": "2025-01", "role": "Backend" },
+                { "month": "2025-02", "role": "Backend" },
+            ],
+        })
+    );
+    let left = repo
+        .list_assignments(&backend_core::AssignmentFilter {
+            resource_id: Some(tbh.parse().unwrap()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(left.is_empty());
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn project_cost_burns_down_the_budget() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let bob = repo.create_resource("Bob").await.unwrap();
+    // Bob is booked before his rate starts, so his first month is unrated.
+    for (resource, month) in [
+        (alice.id, 1),
+        (alice.id, 2),
+        (bob.id, 2),
+        (alice.id, 3),
+        (bob.id, 3),
+    ] {
+        repo.assign(&backend_core::DomainAssignment {
+            resource_id: resource,
+            project_id: alpha.id,
+            year: 2025,
+            month,
+            role: None,
+            note: None,
+            status: AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+    }
+    let schema = build_schema(repo);
+
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{
+                a1: setCostRate(resourceId: "{a}", from: "2024-06", rate: 1000) {{ from rate }}
+                a2: setCostRate(resourceId: "{a}", from: "2025-03", rate: 1200) {{ from rate }}
+                b1: setCostRate(resourceId: "{b}", from: "2025-03", rate: 800) {{ from rate }}
+                setProjectBudget(projectId: "{p}", budget: 5000) {{ budget }}
+            }}"#,
+            a = alice.id,
+            b = bob.id,
+            p = alpha.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["setProjectBudget"]["budget"], 5000);
+
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{ setCostRate(resourceId: "{}", from: "2025-01", rate: -1) {{ rate }} }}"#,
+            alice.id
+        )))
+        .await;
+    assert_eq!(resp.errors[0].message, "Rate cannot be negative");
+
+    // January is outside the window but still counts towards the burn-down.
+    let resp = schema
+        .execute(Request::new(format!(
+            r#"{{
+                projectCost(projectId: "{}", months: ["2025-02", "2025-03", "2025-04"]) {{
+                    budget totalPlanned
+                    months {{ month planned cumulative remaining unratedCount }}
+                }}
+                resources {{ name costRates {{ from rate }} }}
+            }}"#,
+            alpha.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["projectCost"],
+        serde_json::json!({
+            "budget": 5000,
+            "totalPlanned": 4000,
+            "months": [
+                { "month": "2025-02", "planned": 1000, "cumulative": 2000, "remaining": 3000, "unratedCount": 1 },
+                { "month": "2025-03", "planned": 2000, "cumulative": 4000, "remaining": 1000, "unratedCount": 0 },
+                { "month": "2025-04", "planned": 0, "cumulative": 4000, "remaining": 1000, "unratedCount": 0 },
+            ],
+        })
+    );
+    assert_eq!(
+        data["resources"][0]["costRates"],
+        serde_json::json!([
+            { "from": "2024-06", "rate": 1000 },
+            { "from": "2025-03", "rate": 1200 },
+        ])
+    );
+
+    let resp = schema
+        .execute(Request::new(format!(
+            r#"{{ projectCost(projectId: "{}", months: ["2025-01"]) {{ totalPlanned }} }}"#,
+            uuid::Uuid::new_v4()
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    assert_eq!(
+        serde_json::to_value(resp.data).unwrap()["projectCost"],
+        serde_json::Value::Null
+    );
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn plan_vs_actual_compares_imported_actuals_with_assignments() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    repo.set_user_role("pat", Role::Planner).await.unwrap();
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    repo.create_resource("Bob").await.unwrap();
+    for month in [1, 2] {
+        repo.assign(&backend_core::DomainAssignment {
+            resource_id: alice.id,
+            project_id: alpha.id,
+            year: 2025,
+            month,
+            role: None,
+            note: None,
+            status: AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+    }
+    let schema = build_schema(repo.clone());
+
+    // Planners may only record actuals on projects they manage.
+    let import = r"mutation($csv: String!) {
+        importActuals(csv: $csv) { imported errors { line message } }
+    }";
+    let csv = "Alpha,Alice,2025-01,100\nAlpha,Alice,2025-02,60\nAlpha,Bob,2025-02,30\n";
+    let resp = schema
+        .execute(
+            as_other("pat", import)
+                .variables(Variables::from_json(serde_json::json!({ "csv": csv }))),
+        )
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["importActuals"]["imported"], 0);
+    assert_eq!(
+        data["importActuals"]["errors"][0]["message"],
+        "Only the project's managers can record its actuals"
+    );
+
+    let resp = schema
+        .execute(as_user(import).variables(Variables::from_json(serde_json::json!({ "csv": csv }))))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["importActuals"]["imported"], 3);
+    let audit = repo.list_audit().await.unwrap();
+    assert_eq!(audit.last().unwrap().action, "importActuals");
+
+    // Bob has actuals but no plan; Alice has a plan but nothing recorded in March.
+    let resp = schema
+        .execute(Request::new(format!(
+            r#"{{
+                planVsActual(projectId: "{}", months: ["2025-01", "2025-02", "2025-03"]) {{
+                    months
+                    rows {{
+                        resource {{ name }}
+                        cells {{ planned actual variance }}
+                        total {{ planned actual variance }}
+                    }}
+                    totals {{ planned actual variance }}
+                }}
+            }}"#,
+            alpha.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let cell = |planned: i32, actual: i32| serde_json::json!({ "planned": planned, "actual": actual, "variance": actual - planned });
+    assert_eq!(
+        data["planVsActual"],
+        serde_json::json!({
+            "months": ["2025-01", "2025-02", "2025-03"],
+            "rows": [
+                {
+                    "resource": { "name": "Alice" },
+                    "cells": [cell(100, 100), cell(100, 60), cell(0, 0)],
+                    "total": cell(200, 160),
+                },
+                {
+                    "resource": { "name": "Bob" },
+                    "cells": [cell(0, 0), cell(0, 30), cell(0, 0)],
+                    "total": cell(0, 30),
+                },
+            ],
+            "totals": [cell(100, 100), cell(100, 90), cell(0, 0)],
+        })
+    );
+
+    let resp = schema
+        .execute(Request::new(format!(
+            r#"{{ planVsActual(projectId: "{}", months: ["2025-01"]) {{ months }} }}"#,
+            uuid::Uuid::new_v4()
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    assert_eq!(
+        serde_json::to_value(resp.data).unwrap()["planVsActual"],
+        serde_json::Value::Null
+    );
+}
+
+#[tokio::test]
+async fn capacity_shows_free_time_in_the_resource_matrix() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let beta = repo.create_project("Beta").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    for (project, month) in [(alpha.id, 1), (alpha.id, 2), (beta.id, 2), (alpha.id, 3)] {
+        repo.assign(&backend_core::DomainAssignment {
+            resource_id: alice.id,
+            project_id: project,
+            year: 2025,
+            month,
+            role: None,
+            note: None,
+            status: AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+    }
+    let schema = build_schema(repo);
+
+    // Half time for February to April, then a sabbatical in April.
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{
+                half: setCapacity(resourceId: "{a}", from: "2025-02", to: "2025-04", capacity: 50) {{ name }}
+                off: setCapacity(resourceId: "{a}", from: "2025-04", to: "2025-04", capacity: 0) {{
+                    capacities {{ month percent }}
+                }}
+            }}"#,
+            a = alice.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["off"]["capacities"],
+        serde_json::json!([
+            { "month": "2025-02", "percent": 50 },
+            { "month": "2025-03", "percent": 50 },
+            { "month": "2025-04", "percent": 0 },
+        ])
+    );
+
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{ setCapacity(resourceId: "{}", from: "2025-03", to: "2025-02", capacity: 10) {{ name }} }}"#,
+            alice.id
+        )))
+        .await;
+    assert_eq!(resp.errors[0].message, "`to` must not precede `from`");
+
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{ setCapacity(resourceId: "{}", from: "2025-02", to: "2025-02", capacity: 150) {{ name }} }}"#,
+            alice.id
+        )))
+        .await;
+    assert_eq!(resp.errors[0].message, "Capacity must be between 0 and 100");
+
+    let query = r#"{
+        resourceMonthMatrix(months: ["2025-01", "2025-02", "2025-03", "2025-04"]) {
+            rows { nodes { cells { capacity remainingCapacity } } }
+        }
+    }"#;
+    let resp = schema.execute(Request::new(query)).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let cell = |capacity: i32, remaining: i32| serde_json::json!({ "capacity": capacity, "remainingCapacity": remaining });
+    assert_eq!(
+        data["resourceMonthMatrix"]["rows"]["nodes"][0]["cells"],
+        serde_json::json!([cell(100, 0), cell(50, -150), cell(50, -50), cell(0, 0)])
+    );
+
+    // Clearing the override returns the months to full time.
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{ setCapacity(resourceId: "{}", from: "2025-02", to: "2025-04") {{ capacities {{ month }} }} }}"#,
+            alice.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["setCapacity"]["capacities"], serde_json::json!([]));
+    let resp = schema.execute(Request::new(query)).await;
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["resourceMonthMatrix"]["rows"]["nodes"][0]["cells"][1],
+        cell(100, -100)
+    );
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn regional_calendars_turn_effort_into_person_days() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let bert = repo.create_resource("Bert").await.unwrap();
+    repo.assign(&backend_core::DomainAssignment {
+        resource_id: alice.id,
+        project_id: alpha.id,
+        year: 2025,
+        month: 5,
+        role: None,
+        note: None,
+        status: AssignmentStatus::Confirmed,
+    })
+    .await
+    .unwrap();
+    repo.apply_batch(&backend_core::Batch {
+        actuals: vec![backend_core::Actual {
+            resource_id: alice.id,
+            project_id: alpha.id,
+            year: 2025,
+            month: 5,
+            effort: 50,
+        }],
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let calendars = WorkingCalendars::from_json(include_str!("../../calendars.json")).unwrap();
+    let schema = build_schema_with_calendars(repo, calendars);
+
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{
+                uk: setResourceRegion(resourceId: "{a}", region: "UK") {{ region }}
+                de: setResourceRegion(resourceId: "{b}", region: "DE") {{ region }}
+                setCapacity(resourceId: "{b}", from: "2025-06", to: "2025-06", capacity: 50) {{ name }}
+            }}"#,
+            a = alice.id,
+            b = bert.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["uk"]["region"], "UK");
+
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{ setResourceRegion(resourceId: "{}", region: "FR") {{ region }} }}"#,
+            alice.id
+        )))
+        .await;
+    assert_eq!(resp.errors[0].message, "Unknown region 'FR'");
+
+    // May 2025 has 22 weekdays, less two bank holidays in each region; June
+    // has 21, less Whit Monday in Germany.
+    let resp = schema
+        .execute(Request::new(format!(
+            r#"{{
+                regions
+                resourceMonthMatrix(months: ["2025-05", "2025-06"]) {{
+                    rows {{ nodes {{ resource {{ name }} cells {{ workingDays availableDays plannedDays }} }} }}
+                }}
+                planVsActual(projectId: "{}", months: ["2025-05"]) {{
+                    rows {{ cells {{ plannedDays actualDays }} }}
+                }}
+            }}"#,
+            alpha.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["regions"], serde_json::json!(["DE", "UK"]));
+    let cell = |working: i32, available: f64, planned: f64| serde_json::json!({ "workingDays": working, "availableDays": available, "plannedDays": planned });
+    assert_eq!(
+        data["resourceMonthMatrix"]["rows"]["nodes"],
+        serde_json::json!([
+            { "resource": { "name": "Alice" }, "cells": [cell(20, 20.0, 20.0), cell(21, 21.0, 0.0)] },
+            { "resource": { "name": "Bert" }, "cells": [cell(20, 20.0, 0.0), cell(20, 10.0, 0.0)] },
+        ])
+    );
+    assert_eq!(
+        data["planVsActual"]["rows"][0]["cells"][0],
+        serde_json::json!({ "plannedDays": 20.0, "actualDays": 10.0 })
+    );
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn week_assignments_roll_up_into_month_matrices() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let bob = repo.create_resource("Bob").await.unwrap();
+    repo.assign(&backend_core::DomainAssignment {
+        resource_id: alice.id,
+        project_id: alpha.id,
+        year: 2025,
+        month: 2,
+        role: Some("Lead".into()),
+        note: None,
+        status: AssignmentStatus::Confirmed,
+    })
+    .await
+    .unwrap();
+    let schema = build_schema(repo);
+
+    // W05 runs 27 Jan to 2 Feb and W14 31 Mar to 6 Apr; each counts towards
+    // the month holding its Thursday.
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{
+                a: assignWeek(input: {{ resourceId: "{a}", projectId: "{p}", week: "2025-W09" }}) {{ id }}
+                b: assignWeek(input: {{ resourceId: "{b}", projectId: "{p}", week: "2025-W05" }}) {{ id }}
+                c: assignWeek(input: {{ resourceId: "{b}", projectId: "{p}", week: "2025-W14", role: "QA" }}) {{
+                    id week role resource {{ name }}
+                }}
+            }}"#,
+            a = alice.id,
+            b = bob.id,
+            p = alpha.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["c"],
+        serde_json::json!({
+            "id": format!("{}:{}:2025-W14", bob.id, alpha.id),
+            "week": "2025-W14",
+            "role": "QA",
+            "resource": { "name": "Bob" },
+        })
+    );
+
+    let resp = schema
+        .execute(Request::new(
+            r#"{
+                projectWeekMatrix(weeks: ["2025-W05", "2025-W09", "2025-W10", "2025-W14"]) {
+                    weeks
+                    rows { nodes { cells { resources { name } } } }
+                }
+                projectMonthMatrix(months: ["2025-01", "2025-02", "2025-03", "2025-04"]) {
+                    rows { nodes { cells { resources { name } assignments { role } } } }
+                }
+            }"#,
+        ))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["projectWeekMatrix"]["weeks"],
+        serde_json::json!(["2025-W05", "2025-W09", "2025-W10", "2025-W14"])
+    );
+    let names = |cells: &serde_json::Value| -> Vec<Vec<String>> {
+        cells
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| {
+                c["resources"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|r| r["name"].as_str().unwrap().to_string())
+                    .collect()
+            })
+            .collect()
+    };
+    assert_eq!(
+        names(&data["projectWeekMatrix"]["rows"]["nodes"][0]["cells"]),
+        [vec!["Bob"], vec!["Alice"], vec![], vec!["Bob"]]
+    );
+    let months = &data["projectMonthMatrix"]["rows"]["nodes"][0]["cells"];
+    assert_eq!(
+        names(months),
+        [vec!["Bob"], vec!["Alice"], vec![], vec!["Bob"]]
+    );
+    // Alice's week doesn't duplicate her monthly assignment in February.
+    assert_eq!(
+        months[1]["assignments"],
+        serde_json::json!([{ "role": "Lead" }])
+    );
+    assert_eq!(
+        months[3]["assignments"],
+        serde_json::json!([{ "role": "QA" }])
+    );
+
+    // A week takes its share of the month's weeks: one of five in January, one
+    // of four in April. Alice's February week adds nothing to her month.
+    let resp = schema
+        .execute(Request::new(
+            r#"{
+                resourceMonthMatrix(months: ["2025-01", "2025-02", "2025-04"]) {
+                    rows { nodes { resource { name } cells { remainingCapacity plannedDays } } }
+                }
+            }"#,
+        ))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let cell = |remaining: i32, planned: f64| serde_json::json!({ "remainingCapacity": remaining, "plannedDays": planned });
+    assert_eq!(
+        data["resourceMonthMatrix"]["rows"]["nodes"],
+        serde_json::json!([
+            { "resource": { "name": "Alice" }, "cells": [cell(100, 0.0), cell(0, 20.0), cell(100, 0.0)] },
+            { "resource": { "name": "Bob" }, "cells": [cell(80, 4.6), cell(100, 0.0), cell(75, 5.5)] },
+        ])
+    );
+
+    let unassign = format!(
+        r#"mutation {{ unassignWeek(input: {{ resourceId: "{}", projectId: "{}", week: "2025-W14" }}) }}"#,
+        bob.id, alpha.id
+    );
+    let resp = schema.execute(as_user(unassign.clone())).await;
+    assert_eq!(
+        serde_json::to_value(resp.data).unwrap()["unassignWeek"],
+        true
+    );
+    let resp = schema.execute(as_user(unassign)).await;
+    assert_eq!(
+        serde_json::to_value(resp.data).unwrap()["unassignWeek"],
+        false
+    );
+
+    let resp = schema
+        .execute(Request::new(
+            r#"{ projectWeekMatrix(weeks: ["2025-W53"]) { weeks } }"#,
+        ))
+        .await;
+    assert!(
+        resp.errors[0].message.contains("last week of its year"),
+        "{:?}",
+        resp.errors
+    );
+}
+
+#[tokio::test]
+async fn week_only_projects_count_in_cost_utilisation_and_plan() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    let beta = repo.create_project("Beta").await.unwrap();
+    let carol = repo.create_resource("Carol").await.unwrap();
+    let schema = build_schema(repo);
+
+    // W14 and W15 are two of April's four weeks; W05 is one of January's five.
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{
+                a: assignWeek(input: {{ resourceId: "{r}", projectId: "{p}", week: "2025-W05" }}) {{ id }}
+                b: assignWeek(input: {{ resourceId: "{r}", projectId: "{p}", week: "2025-W14" }}) {{ id }}
+                c: assignWeek(input: {{ resourceId: "{r}", projectId: "{p}", week: "2025-W15" }}) {{ id }}
+                setCostRate(resourceId: "{r}", from: "2025-01", rate: 1000) {{ rate }}
+                importActuals(csv: "Beta,Carol,2025-04,40\n") {{ imported }}
+            }}"#,
+            r = carol.id,
+            p = beta.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    assert_eq!(
+        serde_json::to_value(resp.data).unwrap()["importActuals"]["imported"],
+        1
+    );
+
+    let resp = schema
+        .execute(Request::new(format!(
+            r#"{{
+                projectCost(projectId: "{p}", months: ["2025-01", "2025-04"]) {{
+                    totalPlanned months {{ planned cumulative }}
+                }}
+                planVsActual(projectId: "{p}", months: ["2025-01", "2025-04"]) {{
+                    rows {{ resource {{ name }} cells {{ planned actual }} }}
+                }}
+                byProject: utilisation(months: ["2025-01", "2025-04"], groupBy: PROJECT) {{
+                    month count project {{ name }}
+                }}
+                byResource: utilisation(months: ["2025-01", "2025-04"], groupBy: RESOURCE) {{
+                    count resource {{ name }}
+                }}
+                byMonth: utilisation(months: ["2025-01", "2025-04"], groupBy: MONTH) {{
+                    month count
+                }}
+            }}"#,
+            p = beta.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["projectCost"],
+        serde_json::json!({
+            "totalPlanned": 700,
+            "months": [
+                { "planned": 200, "cumulative": 200 },
+                { "planned": 500, "cumulative": 700 },
+            ],
+        })
+    );
+    assert_eq!(
+        data["planVsActual"]["rows"],
+        serde_json::json!([{
+            "resource": { "name": "Carol" },
+            "cells": [{ "planned": 20, "actual": 0 }, { "planned": 50, "actual": 40 }],
+        }])
+    );
+    assert_eq!(
+        data["byProject"],
+        serde_json::json!([
+            { "month": "2025-01", "count": 1, "project": { "name": "Beta" } },
+            { "month": "2025-04", "count": 1, "project": { "name": "Beta" } },
+        ])
+    );
+    assert_eq!(
+        data["byResource"],
+        serde_json::json!([{ "count": 2, "resource": { "name": "Carol" } }])
+    );
+    assert_eq!(
+        data["byMonth"],
+        serde_json::json!([{ "month": "2025-01", "count": 1 }, { "month": "2025-04", "count": 1 }])
+    );
+}
+
+#[tokio::test]
+async fn month_ranges_resolve_relative_to_the_current_month() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let current = YearMonth::current();
+    for month in [current - 1, current + 2] {
+        repo.assign(&backend_core::DomainAssignment {
+            resource_id: alice.id,
+            project_id: alpha.id,
+            year: month.year,
+            month: i32::from(month.month),
+            role: None,
+            note: None,
+            status: AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+    }
+    let schema = build_schema(repo);
+
+    let resp = schema
+        .execute(Request::new(
+            r#"{
+                projectMonthMatrix(range: "-1..+2") { months rows { nodes { cells { resources { name } } } } }
+                utilisation(months: ["current", "+2"], groupBy: MONTH) { month count }
+            }"#,
+        ))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    let months: Vec<String> = (-1..=2).map(|n| (current + n).to_string()).collect();
+    assert_eq!(
+        data["projectMonthMatrix"]["months"],
+        serde_json::json!(months)
+    );
+    let counts: Vec<usize> = data["projectMonthMatrix"]["rows"]["nodes"][0]["cells"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["resources"].as_array().unwrap().len())
+        .collect();
+    assert_eq!(counts, [1, 0, 0, 1]);
+    assert_eq!(
+        data["utilisation"],
+        serde_json::json!([{ "month": months[3], "count": 1 }])
+    );
+
+    for field in [
+        r#"teamMonthMatrix(months: ["2025-01"], range: "2025-01..2025-02")"#,
+        "teamMonthMatrix",
+    ] {
+        let resp = schema
+            .execute(Request::new(format!("{{ {field} {{ months }} }}")))
+            .await;
+        assert_eq!(
+            resp.errors[0].message,
+            "Pass exactly one of `months` and `range`"
+        );
+    }
+    let resp = schema
+        .execute(Request::new(
+            r#"{ teamMonthMatrix(range: "2025-06..2025-01") { months } }"#,
+        ))
+        .await;
+    assert!(
+        resp.errors[0]
+            .message
+            .contains("must not end before it starts"),
+        "{:?}",
+        resp.errors
+    );
+}
+
+#[tokio::test]
+async fn project_status_filters_proposed_work() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let beta = repo.create_project("Beta").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    for project_id in [alpha.id, beta.id] {
+        repo.assign(&backend_core::DomainAssignment {
+            resource_id: alice.id,
+            project_id,
+            year: 2025,
+            month: 3,
+            role: None,
+            note: None,
+            status: AssignmentStatus::Confirmed,
+        })
+        .await
+        .unwrap();
+    }
+    let schema = build_schema(repo);
+
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{
+                setProjectDetails(projectId: "{}", input: {{
+                    status: PROPOSED, colour: " #1F77B4 ", description: "Bid for Q2"
+                }}) {{ status colour description }}
+            }}"#,
+            alpha.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["setProjectDetails"],
+        serde_json::json!({ "status": "PROPOSED", "colour": "#1f77b4", "description": "Bid for Q2" })
+    );
+
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{
+                setProjectDetails(projectId: "{}", input: {{ status: ACTIVE, colour: "blue" }}) {{ id }}
+            }}"#,
+            beta.id
+        )))
+        .await;
+    assert_eq!(
+        resp.errors[0].message,
+        "Colour must be a hex code like #1f77b4"
+    );
+
+    let resp = schema
+        .execute(Request::new(
+            r#"{
+                projects(statuses: [ACTIVE, ON_HOLD]) { name status colour }
+                projectMonthMatrix(months: ["2025-03"], filter: { statuses: [ACTIVE] }) {
+                    rows { nodes { project { name } } }
+                }
+                resourceMonthMatrix(months: ["2025-03"], filter: { statuses: [DONE] }) {
+                    rows { nodes { resource { name } } }
+                }
+            }"#,
+        ))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["projects"],
+        serde_json::json!([{ "name": "Beta", "status": "ACTIVE", "colour": null }])
+    );
+    assert_eq!(
+        data["projectMonthMatrix"]["rows"]["nodes"],
+        serde_json::json!([{ "project": { "name": "Beta" } }])
+    );
+    assert_eq!(
+        data["resourceMonthMatrix"]["rows"]["nodes"],
+        serde_json::json!([{ "resource": { "name": "Alice" } }])
+    );
+}
+
+#[tokio::test]
+#[allow(clippy::too_many_lines)]
+async fn tentative_assignments_are_kept_apart_until_confirmed() {
+    let repo = std::sync::Arc::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+    repo.set_user_role(&user().id, Role::Admin).await.unwrap();
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let beta = repo.create_project("Beta").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    let bob = repo.create_resource("Bob").await.unwrap();
+    let schema = build_schema(repo);
+
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{
+                a: assign(input: {{ resourceId: "{alice}", projectId: "{alpha}", month: "2025-03" }}) {{ status }}
+                b: assign(input: {{ resourceId: "{bob}", projectId: "{alpha}", month: "2025-03", status: TENTATIVE }}) {{ status }}
+                c: assign(input: {{ resourceId: "{alice}", projectId: "{beta}", month: "2025-03", status: TENTATIVE }}) {{ status }}
+            }}"#,
+            alice = alice.id,
+            bob = bob.id,
+            alpha = alpha.id,
+            beta = beta.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["a"]["status"], "CONFIRMED");
+    assert_eq!(data["b"]["status"], "TENTATIVE");
+
+    let query = r#"{
+        projectMonthMatrix(months: ["2025-03"]) {
+            rows { nodes { project { name } cells { confirmedResources { name } tentativeResources { name } } } }
+        }
+        resourceMonthMatrix(months: ["2025-03"]) {
+            rows { nodes { resource { name } cells { remainingCapacity remainingConfirmedCapacity } } }
+        }
+    }"#;
+    let resp = schema.execute(Request::new(query)).await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["projectMonthMatrix"]["rows"]["nodes"][0]["cells"][0],
+        serde_json::json!({
+            "confirmedResources": [{ "name": "Alice" }],
+            "tentativeResources": [{ "name": "Bob" }],
+        })
+    );
+    // Alice is confirmed on Alpha and pencilled in on Beta.
+    assert_eq!(
+        data["resourceMonthMatrix"]["rows"]["nodes"][0]["cells"][0],
+        serde_json::json!({ "remainingCapacity": -100, "remainingConfirmedCapacity": 0 })
+    );
+
+    let resp = schema
+        .execute(as_user(format!(
+            r#"mutation {{ confirmAssignments(projectId: "{}", months: ["2025-03", "2025-04"]) }}"#,
+            alpha.id
+        )))
+        .await;
+    assert!(resp.errors.is_empty(), "errors: {:?}", resp.errors);
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(data["confirmAssignments"], 1);
+
+    let resp = schema.execute(Request::new(query)).await;
+    let data = serde_json::to_value(resp.data).unwrap();
+    assert_eq!(
+        data["projectMonthMatrix"]["rows"]["nodes"][0]["cells"][0]["tentativeResources"],
+        serde_json::json!([])
+    );
+    assert_eq!(
+        data["projectMonthMatrix"]["rows"]["nodes"][1]["cells"][0]["tentativeResources"],
+        serde_json::json!([{ "name": "Alice" }]),
+        "Beta wasn't confirmed"
+    );
+
+    let resp = schema
+        .execute(as_other(
+            "mallory@example.com",
+            format!(
+                r#"mutation {{ confirmAssignments(projectId: "{}", months: ["2025-03"]) }}"#,
+                beta.id
+            ),
+        ))
+        .await;
+    assert!(!resp.errors.is_empty(), "non-managers can't confirm");
+}
@@ -0,0 +1,149 @@
+use backend_core::{
+    AssignmentFilter, Repository, SqliteRepository,
+    import::{ImportOptions, RowIssue, import_actuals_csv, import_csv},
+};
+
+#[tokio::test]
+async fn import_resolves_names_and_ids_and_reports_bad_rows() {
+    let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    let alpha = repo.create_project("Alpha").await.unwrap();
+    let alice = repo.create_resource("Alice").await.unwrap();
+    repo.create_resource("Twin").await.unwrap();
+    repo.create_resource("Twin").await.unwrap();
+
+    let csv = format!(
+        "project,resource,month\n\
+         Alpha,Alice,2024-01\n\
+         {},{},2024-02\n\
+         Alpha,Alice,2024-01\n\
+         Alpha,Bob,2024-03\n\
+         Alpha,Twin,2024-03\n\
+         Alpha,Alice,2024-13\n\
+         Alpha,Alice\n",
+        alpha.id, alice.id
+    );
+    let report = import_csv(&repo, &csv, &ImportOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(report.imported, 2);
+    assert!(report.created_projects.is_empty());
+    assert!(report.created_resources.is_empty());
+    assert_eq!(
+        report.skipped,
+        vec![RowIssue {
+            line: 4,
+            message: "Duplicate of line 2".into()
+        }]
+    );
+    let error_lines: Vec<u64> = report.errors.iter().map(|e| e.line).collect();
+    assert_eq!(error_lines, vec![5, 6, 7, 8]);
+
+    let rows = repo
+        .list_assignments(&AssignmentFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+
+    // Re-importing skips what is already there
+    let report = import_csv(&repo, "Alpha,Alice,2024-01\n", &ImportOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(report.imported, 0);
+    assert_eq!(report.skipped[0].message, "Already assigned");
+}
+
+#[tokio::test]
+async fn import_creates_missing_rows_unless_dry_run() {
+    let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    let csv = "Gamma,Carol,2025-01\nGamma,Carol,2025-02\n";
+
+    let dry_run = ImportOptions {
+        create_missing: true,
+        dry_run: true,
+        ..Default::default()
+    };
+    let report = import_csv(&repo, csv, &dry_run).await.unwrap();
+    assert_eq!(report.imported, 2);
+    assert_eq!(report.created_projects.len(), 1);
+    assert_eq!(report.created_resources.len(), 1);
+    assert!(repo.list_projects().await.unwrap().is_empty());
+
+    let options = ImportOptions {
+        create_missing: true,
+        ..Default::default()
+    };
+    let report = import_csv(&repo, csv, &options).await.unwrap();
+    assert_eq!(report.imported, 2);
+    assert_eq!(repo.list_projects().await.unwrap(), report.created_projects);
+    assert_eq!(
+        repo.list_resources().await.unwrap(),
+        report.created_resources
+    );
+
+    // Without create_missing unknown names are errors
+    let report = import_csv(&repo, "Delta,Carol,2025-01\n", &ImportOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(report.errors[0].message, "Unknown project 'Delta'");
+}
+
+#[tokio::test]
+async fn actuals_import_replaces_effort_and_never_creates_rows() {
+    let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    repo.create_project("Alpha").await.unwrap();
+    repo.create_resource("Alice").await.unwrap();
+
+    let options = ImportOptions {
+        create_missing: true,
+        ..Default::default()
+    };
+    let csv = "project,resource,month,effort\n\
+               Alpha,Alice,2025-01,80\n\
+               Alpha,Alice,2025-02,110\n\
+               Alpha,Alice,2025-01,90\n\
+               Alpha,Alice,2025-03,-5\n\
+               Alpha,Alice,2025-03\n\
+               Beta,Alice,2025-03,50\n";
+    let report = import_actuals_csv(&repo, csv, &options).await.unwrap();
+    assert_eq!(report.imported, 2);
+    assert!(report.created_projects.is_empty());
+    assert_eq!(report.skipped[0].message, "Duplicate of line 2");
+    assert_eq!(
+        report.errors,
+        vec![
+            RowIssue {
+                line: 5,
+                message: "Effort '-5' is not a whole, non-negative percentage".into()
+            },
+            RowIssue {
+                line: 6,
+                message: "Expected 4 fields: project,resource,month,effort".into()
+            },
+            RowIssue {
+                line: 7,
+                message: "Unknown project 'Beta'".into()
+            },
+        ]
+    );
+
+    // Importing a month again replaces what was recorded
+    let report = import_actuals_csv(&repo, "Alpha,Alice,2025-01,40\n", &options)
+        .await
+        .unwrap();
+    assert_eq!(report.imported, 1);
+    let efforts: Vec<i32> = repo
+        .list_actuals(&AssignmentFilter::default())
+        .await
+        .unwrap()
+        .iter()
+        .map(|a| a.effort)
+        .collect();
+    assert_eq!(efforts, vec![40, 110]);
+    assert!(
+        repo.list_assignments(&AssignmentFilter::default())
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -1,4 +1,7 @@
-use backend_core::{AssignmentFilter, DomainAssignment, Repository, SqliteRepository};
+use backend_core::{
+    AssignmentFilter, AssignmentStatus, CachedRepository, DomainAssignment, Repository,
+    SqliteRepository, testing::run_repository_conformance,
+};
 
 #[tokio::test]
 async fn repo_create_assign_delete_flow() {
@@ -14,6 +17,9 @@ async fn repo_create_assign_delete_flow() {
         project_id: p.id,
         year: 2024,
         month: 3,
+        role: None,
+        note: None,
+        status: AssignmentStatus::Confirmed,
     };
     repo.assign(&a).await.unwrap();
 
@@ -37,3 +43,35 @@ async fn repo_create_assign_delete_flow() {
     assert_eq!(repo.delete_resource(r.id).await.unwrap(), 1);
     assert_eq!(repo.delete_project(p.id).await.unwrap(), 1);
 }
+
+#[tokio::test]
+async fn sqlite_repo_conforms() {
+    let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    run_repository_conformance(&repo).await;
+}
+
+#[tokio::test]
+async fn cached_sqlite_repo_conforms() {
+    let repo = SqliteRepository::connect("sqlite::memory:").await.unwrap();
+    run_repository_conformance(&CachedRepository::new(repo)).await;
+}
+
+#[tokio::test]
+async fn cached_repo_invalidates_on_writes() {
+    let repo = CachedRepository::new(SqliteRepository::connect("sqlite::memory:").await.unwrap());
+
+    // Warm the caches
+    assert!(repo.list_projects().await.unwrap().is_empty());
+    assert!(repo.list_resources().await.unwrap().is_empty());
+
+    let p = repo.create_project("Cached").await.unwrap();
+    let r = repo.create_resource("Cached").await.unwrap();
+    assert_eq!(repo.list_projects().await.unwrap(), vec![p.clone()]);
+    assert_eq!(repo.list_resources().await.unwrap(), vec![r.clone()]);
+    assert_eq!(repo.projects(&[p.id]).await.unwrap(), vec![p.clone()]);
+
+    assert_eq!(repo.delete_project(p.id).await.unwrap(), 1);
+    assert!(repo.list_projects().await.unwrap().is_empty());
+    assert!(repo.projects(&[p.id]).await.unwrap().is_empty());
+    assert_eq!(repo.resources(&[r.id]).await.unwrap(), vec![r]);
+}
@@ -0,0 +1,145 @@
+//! Bearer-token authentication for incoming HTTP requests.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use backend_core::CurrentUser;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+
+/// Rejection reason for a presented credential.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthError(pub String);
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Turns a bearer token into the user it identifies.
+///
+/// Implementations are installed with [`authenticate`]; the HS256 JWT validator
+/// is the default, but any other scheme can be plugged in.
+pub trait Authenticator: Send + Sync {
+    /// # Errors
+    ///
+    /// Returns an error if the token is malformed, expired or not trusted.
+    fn authenticate(&self, token: &str) -> Result<CurrentUser, AuthError>;
+}
+
+pub type SharedAuthenticator = Option<Arc<dyn Authenticator>>;
+
+/// Validates HS256-signed JWTs against a locally configured secret.
+pub struct JwtAuthenticator {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    name: Option<String>,
+}
+
+impl JwtAuthenticator {
+    pub fn hs256(secret: &[u8]) -> Self {
+        Self {
+            key: DecodingKey::from_secret(secret),
+            validation: Validation::new(Algorithm::HS256),
+        }
+    }
+}
+
+impl Authenticator for JwtAuthenticator {
+    fn authenticate(&self, token: &str) -> Result<CurrentUser, AuthError> {
+        let data = jsonwebtoken::decode::<Claims>(token, &self.key, &self.validation)
+            .map_err(|e| AuthError(format!("Invalid token: {e}")))?;
+        Ok(CurrentUser {
+            id: data.claims.sub,
+            name: data.claims.name,
+        })
+    }
+}
+
+/// Middleware resolving the `Authorization: Bearer` header into a [`CurrentUser`].
+///
+/// Requests without the header pass through anonymously. A header that can't be
+/// validated is rejected with `401 Unauthorized`.
+pub async fn authenticate(
+    State(authenticator): State<SharedAuthenticator>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(value) = req.headers().get(header::AUTHORIZATION) else {
+        return next.run(req).await;
+    };
+
+    let user = value
+        .to_str()
+        .ok()
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| AuthError("Expected a Bearer token".into()))
+        .and_then(|token| {
+            authenticator
+                .as_ref()
+                .ok_or_else(|| AuthError("Authentication is not configured".into()))?
+                .authenticate(token.trim())
+        });
+
+    match user {
+        Ok(user) => {
+            req.extensions_mut().insert(user);
+            next.run(req).await
+        }
+        Err(e) => (StatusCode::UNAUTHORIZED, e.to_string()).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{EncodingKey, Header};
+
+    fn token(secret: &[u8], exp: u64) -> String {
+        let claims = serde_json::json!({ "sub": "alice", "name": "Alice", "exp": exp });
+        jsonwebtoken::encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret),
+        )
+        .unwrap()
+    }
+
+    fn in_one_hour() -> u64 {
+        jsonwebtoken::get_current_timestamp() + 3600
+    }
+
+    #[test]
+    fn jwt_authenticator_accepts_valid_token() {
+        let auth = JwtAuthenticator::hs256(b"secret");
+        let user = auth.authenticate(&token(b"secret", in_one_hour())).unwrap();
+        assert_eq!(
+            user,
+            CurrentUser {
+                id: "alice".into(),
+                name: Some("Alice".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn jwt_authenticator_rejects_bad_signature_and_expiry() {
+        let auth = JwtAuthenticator::hs256(b"secret");
+        assert!(auth.authenticate(&token(b"other", in_one_hour())).is_err());
+        assert!(auth.authenticate(&token(b"secret", 1)).is_err());
+        assert!(auth.authenticate("not-a-jwt").is_err());
+    }
+}
@@ -1,37 +1,203 @@
+mod auth;
+
 use std::sync::Arc;
 
 use async_graphql::http::GraphiQLSource;
-use async_graphql_axum::GraphQL;
-use axum::http::header;
-use axum::{Router, routing::get};
-use backend_core::{SqliteRepository, build_schema};
+use async_graphql::{ObjectType, Schema, SubscriptionType};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::extract::{Extension, Path, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::{Router, middleware, routing::get};
+use backend_core::{
+    CachedRepository, CurrentUser, Repository, SqliteRepository,
+    build_read_only_schema_with_calendars, build_schema_with_calendars, calendar,
+    export::{self, ExportError, Matrix},
+    workdays::WorkingCalendars,
+};
+use serde::Deserialize;
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::auth::{JwtAuthenticator, SharedAuthenticator};
+
 async fn graphiql() -> impl axum::response::IntoResponse {
     axum::response::Html(GraphiQLSource::build().endpoint("/graphql").finish())
 }
 
+/// Executes a GraphQL request, passing the authenticated user (if any) to resolvers.
+async fn graphql<Q, M, S>(
+    State(schema): State<Schema<Q, M, S>>,
+    user: Option<Extension<CurrentUser>>,
+    req: GraphQLRequest,
+) -> GraphQLResponse
+where
+    Q: ObjectType + 'static,
+    M: ObjectType + 'static,
+    S: SubscriptionType + 'static,
+{
+    let mut req = req.into_inner();
+    if let Some(Extension(user)) = user {
+        req = req.data(user);
+    }
+    schema.execute(req).await.into()
+}
+
+#[derive(Deserialize)]
+struct ExportWindow {
+    from: String,
+    to: String,
+}
+
+/// Serves `/export/{project,resource}-matrix.{csv,xlsx}?from=YYYY-MM&to=YYYY-MM`.
+async fn export_matrix(
+    State(repo): State<Arc<dyn Repository>>,
+    Path(file): Path<String>,
+    Query(window): Query<ExportWindow>,
+) -> Response {
+    let (matrix, format) = match file.split_once('.') {
+        Some(("project-matrix", format)) => (Matrix::Project, format),
+        Some(("resource-matrix", format)) => (Matrix::Resource, format),
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let content_type = match format {
+        "csv" => "text/csv; charset=utf-8",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+
+    let body = export::matrix_table(repo.as_ref(), matrix, &window.from, &window.to)
+        .await
+        .and_then(|table| {
+            if format == "csv" {
+                table.to_csv()
+            } else {
+                table.to_xlsx()
+            }
+        });
+    match body {
+        Ok(body) => (
+            [
+                (header::CONTENT_TYPE, content_type.to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{file}\""),
+                ),
+            ],
+            body,
+        )
+            .into_response(),
+        Err(e @ ExportError::InvalidWindow(_)) => {
+            (StatusCode::BAD_REQUEST, e.to_string()).into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Serves `/calendar/resource/{id}.ics`: one month-long event per assignment.
+async fn resource_calendar(
+    State(repo): State<Arc<dyn Repository>>,
+    Path(file): Path<String>,
+) -> Response {
+    let Some(id) = file
+        .strip_suffix(".ics")
+        .and_then(|id| uuid::Uuid::parse_str(id).ok())
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match calendar::resource_calendar(repo.as_ref(), id).await {
+        Ok(Some(body)) => (
+            [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+            body,
+        )
+            .into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+fn router<Q, M, S>(
+    schema: Schema<Q, M, S>,
+    repo: Arc<dyn Repository>,
+    authenticator: SharedAuthenticator,
+) -> Router
+where
+    Q: ObjectType + 'static,
+    M: ObjectType + 'static,
+    S: SubscriptionType + 'static,
+{
+    let exports = Router::new()
+        .route("/export/{file}", get(export_matrix))
+        .route("/calendar/resource/{file}", get(resource_calendar))
+        .with_state(repo);
+
+    let api = Router::new()
+        .route("/graphql", get(graphql::<Q, M, S>).post(graphql::<Q, M, S>))
+        .with_state(schema)
+        .merge(exports)
+        .layer(middleware::from_fn_with_state(
+            authenticator,
+            auth::authenticate,
+        ));
+
+    Router::new().route("/", get(graphiql)).merge(api).layer(
+        CorsLayer::new()
+            .allow_methods([axum::http::Method::GET, axum::http::Method::POST])
+            .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+            .allow_origin(Any),
+    )
+}
+
+/// Returns `true` if the env var `name` is set to `1` or `true`.
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Read DATABASE_URL
     let db_url = std::env::var("DATABASE_URL")
         .map_err(|_| "DATABASE_URL env var is required, e.g. sqlite:../backend/dev.db")?;
 
-    // Init repo
-    let repo = SqliteRepository::connect(&db_url).await?;
-
-    // Build GraphQL schema with repo in context
-    let schema = build_schema(Arc::new(repo));
-
-    let app = Router::new()
-        .route("/", get(graphiql))
-        .route_service("/graphql", GraphQL::new(schema.clone()))
-        .layer(
-            CorsLayer::new()
-                .allow_methods([axum::http::Method::GET, axum::http::Method::POST])
-                .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
-                .allow_origin(Any),
-        );
+    // Bearer tokens are HS256 JWTs signed with JWT_SECRET. Without it every request
+    // is anonymous, so mutations are unavailable.
+    let authenticator: SharedAuthenticator = match std::env::var("JWT_SECRET") {
+        Ok(secret) if !secret.is_empty() => {
+            Some(Arc::new(JwtAuthenticator::hs256(secret.as_bytes())))
+        }
+        _ => {
+            eprintln!(
+                "JWT_SECRET is not set; all requests are anonymous and mutations are rejected"
+            );
+            None
+        }
+    };
+
+    // Public holidays per region come from a local file; without one every
+    // weekday is a working day.
+    let calendars = match std::env::var("CALENDARS_FILE") {
+        Ok(path) if !path.is_empty() => WorkingCalendars::load(&path)?,
+        _ => WorkingCalendars::default(),
+    };
+
+    let app = if env_flag("READ_ONLY") {
+        // No caching here: another process may be writing to the same file.
+        let repo: Arc<dyn Repository> =
+            Arc::new(SqliteRepository::connect_read_only(&db_url).await?);
+        let schema = build_read_only_schema_with_calendars(repo.clone(), calendars);
+        router(schema, repo, authenticator)
+    } else {
+        // Init repo; project/resource reads are cached since the frontend polls the matrices
+        let repo: Arc<dyn Repository> = Arc::new(CachedRepository::new(
+            SqliteRepository::connect(&db_url).await?,
+        ));
+
+        // Build GraphQL schema with repo in context
+        router(
+            build_schema_with_calendars(repo.clone(), calendars),
+            repo,
+            authenticator,
+        )
+    };
 
     let addr = std::net::SocketAddr::from(([0, 0, 0, 0], 8000));
     println!("GraphQL IDE: http://{addr}/");
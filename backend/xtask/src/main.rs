@@ -1,3 +1,8 @@
+use backend_core::{
+    Repository, Role, SqliteRepository, YearMonth,
+    backup::{self, Backup},
+    import::{ImportOptions, ImportReport, import_actuals_csv, import_csv},
+};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -13,9 +18,54 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let db_path = args.next().unwrap_or_else(|| "./demo.db".to_string());
             generate_demo(&db_path).await?;
         }
+        "grant-role" => {
+            let (Some(db_path), Some(user_id), Some(role)) =
+                (args.next(), args.next(), args.next())
+            else {
+                return Err("usage: grant-role <db> <user-id> <viewer|planner|admin>".into());
+            };
+            grant_role(&db_path, &user_id, role.parse()?).await?;
+        }
+        "import-csv" => {
+            let rest: Vec<String> = args.collect();
+            let mut paths = rest.iter().filter(|a| !a.starts_with("--"));
+            let (Some(db_path), Some(csv_path)) = (paths.next(), paths.next()) else {
+                return Err("usage: import-csv <db> <file> [--create-missing] [--dry-run]".into());
+            };
+            let options = ImportOptions {
+                create_missing: rest.iter().any(|a| a == "--create-missing"),
+                dry_run: rest.iter().any(|a| a == "--dry-run"),
+                manager: None,
+            };
+            import_csv_file(db_path, csv_path, &options).await?;
+        }
+        "import-actuals" => {
+            let rest: Vec<String> = args.collect();
+            let mut paths = rest.iter().filter(|a| !a.starts_with("--"));
+            let (Some(db_path), Some(csv_path)) = (paths.next(), paths.next()) else {
+                return Err("usage: import-actuals <db> <file> [--dry-run]".into());
+            };
+            let options = ImportOptions {
+                dry_run: rest.iter().any(|a| a == "--dry-run"),
+                ..ImportOptions::default()
+            };
+            import_actuals_file(db_path, csv_path, &options).await?;
+        }
+        "export-json" => {
+            let (Some(db_path), Some(out_path)) = (args.next(), args.next()) else {
+                return Err("usage: export-json <db> <out>".into());
+            };
+            export_json(&db_path, &out_path).await?;
+        }
+        "import-json" => {
+            let (Some(db_path), Some(in_path)) = (args.next(), args.next()) else {
+                return Err("usage: import-json <db> <in>".into());
+            };
+            import_json(&db_path, &in_path).await?;
+        }
         "help" | "-h" | "--help" => {
             eprintln!(
-                "xtask commands:\n  generate-demo [path]     Create and seed a demo SQLite DB (default ./demo.db)"
+                "xtask commands:\n  generate-demo [path]     Create and seed a demo SQLite DB (default ./demo.db)\n  grant-role <db> <user> <role>\n                           Set a user's role (viewer, planner or admin)\n  import-csv <db> <file> [--create-missing] [--dry-run]\n                           Import project,resource,month assignment rows\n  import-actuals <db> <file> [--dry-run]\n                           Import project,resource,month,effort actual rows\n  export-json <db> <out>   Write a full JSON backup of the database\n  import-json <db> <in>    Restore a JSON backup (safe to repeat)"
             );
         }
         other => {
@@ -28,17 +78,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 async fn generate_demo(db_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    use time::{Month, OffsetDateTime};
-
-    // Helper: add n months to (year, month)
-    fn add_months(mut year: i32, month: Month, n: i32) -> (i32, u8) {
-        let total = i32::from(u8::from(month)) + n - 1; // Month is 1..=12
-        year += total.div_euclid(12);
-        let m0 = total.rem_euclid(12) + 1; // back to 1..=12
-        // safe: m0 is 1..=12
-        (year, u8::try_from(m0).expect("month in 1..=12"))
-    }
-
     let db_url = format!("sqlite:{db_path}");
 
     if let Some(parent) = PathBuf::from(db_path).parent()
@@ -111,9 +150,7 @@ async fn generate_demo(db_path: &str) -> Result<(), Box<dyn std::error::Error>>
             .await?;
 
         // Assignments relative to current month
-        let now = OffsetDateTime::now_utc().date();
-        let base_year = now.year();
-        let base_month = now.month();
+        let current = YearMonth::current();
 
         // (resource_id, project_id, offset months)
         let assignments: Vec<([u8; 16], [u8; 16], i32)> = vec![
@@ -124,15 +161,15 @@ async fn generate_demo(db_path: &str) -> Result<(), Box<dyn std::error::Error>>
         ];
 
         for (res_bytes, proj_bytes, offset) in assignments {
-            let (y, m) = add_months(base_year, base_month, offset);
+            let month = current + offset;
             sqlx::query(
                 "INSERT OR IGNORE INTO assignments (resource_id, project_id, year, month) \
                  VALUES (?, ?, ?, ?)",
             )
             .bind(res_bytes.to_vec()) // BLOB(16)
             .bind(proj_bytes.to_vec()) // BLOB(16)
-            .bind(y)
-            .bind(i32::from(m))
+            .bind(month.year)
+            .bind(i32::from(month.month))
             .execute(&pool)
             .await?;
         }
@@ -141,3 +178,86 @@ async fn generate_demo(db_path: &str) -> Result<(), Box<dyn std::error::Error>>
     println!("Demo database ready at: {db_path}");
     Ok(())
 }
+
+async fn grant_role(
+    db_path: &str,
+    user_id: &str,
+    role: Role,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = SqliteRepository::connect(&format!("sqlite:{db_path}")).await?;
+    repo.set_user_role(user_id, role).await?;
+    println!("Granted {role} to {user_id} in {db_path}");
+    Ok(())
+}
+
+async fn import_csv_file(
+    db_path: &str,
+    csv_path: &str,
+    options: &ImportOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let csv = std::fs::read_to_string(csv_path)?;
+    let repo = SqliteRepository::connect(&format!("sqlite:{db_path}")).await?;
+    let report = import_csv(&repo, &csv, options).await?;
+    print_report(&report, options.dry_run, "assignments");
+    Ok(())
+}
+
+async fn import_actuals_file(
+    db_path: &str,
+    csv_path: &str,
+    options: &ImportOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let csv = std::fs::read_to_string(csv_path)?;
+    let repo = SqliteRepository::connect(&format!("sqlite:{db_path}")).await?;
+    let report = import_actuals_csv(&repo, &csv, options).await?;
+    print_report(&report, options.dry_run, "actuals");
+    Ok(())
+}
+
+fn print_report(report: &ImportReport, dry_run: bool, noun: &str) {
+    for p in &report.created_projects {
+        println!("created project: {}", p.name);
+    }
+    for r in &report.created_resources {
+        println!("created resource: {}", r.name);
+    }
+    for issue in &report.skipped {
+        println!("line {}: skipped: {}", issue.line, issue.message);
+    }
+    for issue in &report.errors {
+        eprintln!("line {}: error: {}", issue.line, issue.message);
+    }
+    let verb = if dry_run { "Would import" } else { "Imported" };
+    println!(
+        "{verb} {} {noun} ({} skipped, {} errors)",
+        report.imported,
+        report.skipped.len(),
+        report.errors.len()
+    );
+}
+
+async fn export_json(db_path: &str, out_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = SqliteRepository::connect(&format!("sqlite:{db_path}")).await?;
+    let backup = backup::export(&repo).await?;
+    std::fs::write(out_path, backup.to_json()?)?;
+    println!(
+        "Exported {} projects, {} resources and {} assignments to {out_path}",
+        backup.projects.len(),
+        backup.resources.len(),
+        backup.assignments.len()
+    );
+    Ok(())
+}
+
+async fn import_json(db_path: &str, in_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let backup = Backup::from_json(&std::fs::read_to_string(in_path)?)?;
+    let repo = SqliteRepository::connect(&format!("sqlite:{db_path}")).await?;
+    backup::restore(&repo, &backup).await?;
+    println!(
+        "Restored {} projects, {} resources and {} assignments into {db_path}",
+        backup.projects.len(),
+        backup.resources.len(),
+        backup.assignments.len()
+    );
+    Ok(())
+}